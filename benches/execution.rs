@@ -0,0 +1,53 @@
+use std::io;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use ion::{compiler::Compiler, interpreter::Interpreter, source::SourceFile, vm::Vm};
+
+const PROGRAMS: &[(&str, &str)] = &[
+    (
+        "fib",
+        "fn fib(n: int): int { if n < 2 { return n } return fib(n - 1) + fib(n - 2) } fib(20)",
+    ),
+    (
+        "loop",
+        "let total = 0 for i in range(0, 100000) { if i % 3 == 0 { continue } total = total + i }",
+    ),
+    (
+        "closures",
+        "fn counter() { let count = 0 fn next(): int { count = count + 1 return count } return next } \
+         let next = counter() let i = 0 while i < 20000 { next() i = i + 1 }",
+    ),
+];
+
+fn execution(c: &mut Criterion) {
+    let mut group = c.benchmark_group("execution");
+
+    for (name, source) in PROGRAMS {
+        let file = SourceFile::from_string(format!("{}.ion", name), *source);
+        let program = file.parse().expect("benchmark programs are valid");
+
+        group.bench_with_input(
+            BenchmarkId::new("interpreter", name),
+            &program,
+            |b, program| {
+                b.iter(|| {
+                    Interpreter::with_output(Box::new(io::sink()))
+                        .interpret(program)
+                        .unwrap()
+                })
+            },
+        );
+
+        group.bench_with_input(BenchmarkId::new("vm", name), &program, |b, program| {
+            b.iter(|| {
+                let function = Compiler::new().compile(program).unwrap();
+                Vm::with_output(Box::new(io::sink())).run(function).unwrap()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, execution);
+criterion_main!(benches);
@@ -0,0 +1,47 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use ion::{lexer::Lexer, source::SourceFile};
+
+/// The number of times `chunk` is repeated in each generated input
+const SIZES: &[usize] = &[1_000, 10_000];
+
+/// A few lines touching every kind of token, with names numbered by `index` so that no two
+/// chunks are the same
+fn chunk(index: usize) -> String {
+    format!(
+        "/// Doubles the value, see `twice_{index}`
+fn double_{index}(value: int): int {{
+  let result = value * 2 + 0x{index:x} // keep it small
+  if result >= 1_000 && !done_{index} {{ result = result ~/ 3 }}
+  print(\"résumé {{result}} ☕\", 'raw\\t', r\"C:\\path\", 1.5e3, {index}u32)
+  return result
+}}
+"
+    )
+}
+
+fn generate(chunks: usize) -> SourceFile {
+    let source: String = (0..chunks).map(chunk).collect();
+    SourceFile::from_string(format!("generated_{}.ion", chunks), source)
+}
+
+fn lexer(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lexer");
+
+    for &size in SIZES {
+        let file = generate(size);
+        let tokens = Lexer::new(&file).count();
+        group.throughput(Throughput::Elements(tokens as u64));
+
+        group.bench_with_input(BenchmarkId::new("tokens", size), &file, |b, file| {
+            b.iter(|| Lexer::new(file).count())
+        });
+        group.bench_with_input(BenchmarkId::new("tokenize", size), &file, |b, file| {
+            b.iter(|| Lexer::new(file).tokenize())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, lexer);
+criterion_main!(benches);
@@ -0,0 +1,276 @@
+use std::{
+    fs,
+    io::{self, BufReader, Read, Write},
+};
+
+use crate::{
+    compiler::Compiler,
+    diagnostics::{renderer::Renderer, Diagnostic},
+    formatter,
+    lsp::Server,
+    modules::loader::Loader,
+    parser::ast::{printer::Printer, statements::Program},
+    repl::Repl,
+    source::{SourceFile, SourceMap},
+    vm::Vm,
+};
+
+pub const EXIT_SUCCESS: i32 = 0;
+/// The program was rejected with diagnostics, or failed at runtime
+pub const EXIT_FAILURE: i32 = 1;
+/// The command line itself was invalid, e.g. an unknown command or an unreadable file
+pub const EXIT_USAGE: i32 = 2;
+
+const USAGE: &str = "\
+usage: ion <command> <file>
+       ion fmt [--check] <file>
+       ion repl
+       ion lsp
+
+commands:
+    run      check and run a program
+    check    report diagnostics without running
+    tokens   print the tokens of a program
+    ast      print the syntax tree of a program
+    fmt      format a program in place, or only report whether it is formatted with --check
+    repl     evaluate lines interactively
+    lsp      run a language server over stdin and stdout
+
+pass - as the file to read the program from stdin, imports are then resolved
+relative to the current directory
+";
+
+/// The `ion` command line driver
+///
+/// Every command reads a single program, either from a file or from stdin, and writes its
+/// results to stdout and any diagnostics to stderr
+pub struct Cli {
+    stdin: Box<dyn Read>,
+    stdout: Box<dyn Write>,
+    stderr: Box<dyn Write>,
+    renderer: Renderer,
+}
+
+impl Default for Cli {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cli {
+    pub fn new() -> Self {
+        Self {
+            stdin: Box::new(io::stdin()),
+            stdout: Box::new(io::stdout()),
+            stderr: Box::new(io::stderr()),
+            renderer: Renderer::for_stderr(),
+        }
+    }
+
+    /// Creates a driver over the given streams, rendering diagnostics without colours
+    pub fn with_io(stdin: Box<dyn Read>, stdout: Box<dyn Write>, stderr: Box<dyn Write>) -> Self {
+        Self {
+            stdin,
+            stdout,
+            stderr,
+            renderer: Renderer::plain(),
+        }
+    }
+
+    /// Runs the command described by `args` (without the executable name), returning the exit
+    /// code
+    pub fn run(mut self, args: &[String]) -> i32 {
+        let (command, path, check) = match args {
+            [flag] if flag == "-h" || flag == "--help" || flag == "help" => {
+                return self.write_stdout(USAGE);
+            }
+            [command] if command == "repl" => return self.repl(),
+            [command] if command == "lsp" => return self.lsp(),
+            [command, flag, path] if command == "fmt" && flag == "--check" => {
+                (command.as_str(), path.as_str(), true)
+            }
+            [command, path] => (command.as_str(), path.as_str(), false),
+            _ => return self.usage_error("expected a command and a file"),
+        };
+
+        if !matches!(command, "run" | "check" | "tokens" | "ast" | "fmt") {
+            return self.usage_error(&format!("unknown command '{}'", command));
+        }
+
+        let file = match self.read_source(path) {
+            Ok(file) => file,
+            Err(error) => {
+                let message = format!("error: could not read '{}': {}\n", path, error);
+                self.write_stderr(&message);
+                return EXIT_USAGE;
+            }
+        };
+
+        match command {
+            "tokens" => self.tokens(&file),
+            "ast" => self.ast(&file),
+            "fmt" => self.format(&file, path, check),
+            "check" => match self.check(file) {
+                Ok(_) => EXIT_SUCCESS,
+                Err(code) => code,
+            },
+            _ => self.execute(file),
+        }
+    }
+
+    fn repl(self) -> i32 {
+        let mut stdin = BufReader::new(self.stdin);
+        Repl::new(self.stdout, self.stderr, self.renderer).run(&mut stdin);
+        EXIT_SUCCESS
+    }
+
+    fn lsp(self) -> i32 {
+        let mut stdin = BufReader::new(self.stdin);
+        Server::new(self.stdout).run(&mut stdin)
+    }
+
+    fn tokens(&mut self, file: &SourceFile) -> i32 {
+        let tokens = file.tokenize();
+
+        let mut output = String::new();
+        for token in tokens.iter() {
+            let start = file.location(token.span.start as usize);
+            output += &format!(
+                "{}:{} {:?} {:?}\n",
+                start.line,
+                start.column + 1,
+                token.kind,
+                file.text(token.span)
+            );
+        }
+        self.write_stdout(&output);
+
+        self.report(&tokens.diagnostics, file)
+    }
+
+    fn ast(&mut self, file: &SourceFile) -> i32 {
+        match file.parse() {
+            Ok(program) => {
+                let output = Printer::new(file).print_program(&program);
+                self.write_stdout(&format!("{}\n", output))
+            }
+            Err(diagnostics) => self.report(&diagnostics, file),
+        }
+    }
+
+    /// Rewrites the file at `path` in its canonical formatting, or only reports whether it is
+    /// formatted when `check` is set. Formatted stdin is written to stdout
+    fn format(&mut self, file: &SourceFile, path: &str, check: bool) -> i32 {
+        let formatted = match formatter::format(file) {
+            Ok(formatted) => formatted,
+            Err(diagnostics) => return self.report(&diagnostics, file),
+        };
+
+        if check {
+            if formatted == file.source() {
+                return EXIT_SUCCESS;
+            }
+
+            self.write_stderr(&format!("{} is not formatted\n", file.relative_path));
+            return EXIT_FAILURE;
+        }
+
+        if path == "-" {
+            return self.write_stdout(&formatted);
+        }
+
+        if formatted == file.source() {
+            return EXIT_SUCCESS;
+        }
+
+        match fs::write(path, formatted) {
+            Ok(()) => EXIT_SUCCESS,
+            Err(error) => {
+                let message = format!("error: could not write '{}': {}\n", path, error);
+                self.write_stderr(&message);
+                EXIT_USAGE
+            }
+        }
+    }
+
+    /// Loads `file` along with its imports and runs every static check on them, returning the
+    /// linked program or the exit code on failure
+    fn check(&mut self, file: SourceFile) -> Result<(Program, SourceMap), i32> {
+        let mut loader = Loader::new();
+        let graph = match loader.load(file) {
+            Ok(graph) => graph,
+            Err(diagnostics) => return Err(self.report_in(&diagnostics, loader.sources())),
+        };
+
+        if let Err(diagnostics) = graph.check() {
+            return Err(self.report_in(&diagnostics, loader.sources()));
+        }
+
+        Ok((graph.link(), loader.into_sources()))
+    }
+
+    fn execute(mut self, file: SourceFile) -> i32 {
+        let (program, sources) = match self.check(file) {
+            Ok(linked) => linked,
+            Err(code) => return code,
+        };
+
+        let function = match Compiler::new().compile(&program) {
+            Ok(function) => function,
+            Err(diagnostic) => return self.report_in(&[diagnostic], &sources),
+        };
+
+        let stdout = std::mem::replace(&mut self.stdout, Box::new(io::sink()));
+        match Vm::with_output(stdout).run(function) {
+            Ok(()) => EXIT_SUCCESS,
+            Err(error) => self.report_in(&[error.to_diagnostic()], &sources),
+        }
+    }
+
+    fn read_source(&mut self, path: &str) -> io::Result<SourceFile> {
+        if path != "-" {
+            return SourceFile::open(path);
+        }
+
+        let mut source = String::new();
+        self.stdin.read_to_string(&mut source)?;
+        Ok(SourceFile::from_string("<stdin>", source))
+    }
+
+    /// Renders `diagnostics` to stderr, returning the matching exit code
+    fn report(&mut self, diagnostics: &[Diagnostic], file: &SourceFile) -> i32 {
+        if diagnostics.is_empty() {
+            return EXIT_SUCCESS;
+        }
+
+        let output = self.renderer.render_all(diagnostics, file);
+        self.write_stderr(&output);
+        EXIT_FAILURE
+    }
+
+    /// Like `report`, for diagnostics that may point into any loaded file
+    fn report_in(&mut self, diagnostics: &[Diagnostic], sources: &SourceMap) -> i32 {
+        if diagnostics.is_empty() {
+            return EXIT_SUCCESS;
+        }
+
+        let output = self.renderer.render_all_in(diagnostics, sources);
+        self.write_stderr(&output);
+        EXIT_FAILURE
+    }
+
+    fn usage_error(&mut self, message: &str) -> i32 {
+        self.write_stderr(&format!("error: {}\n\n{}", message, USAGE));
+        EXIT_USAGE
+    }
+
+    fn write_stdout(&mut self, text: &str) -> i32 {
+        // a closed stdout (e.g. piping into `head`) is not worth failing over
+        let _ = self.stdout.write_all(text.as_bytes());
+        EXIT_SUCCESS
+    }
+
+    fn write_stderr(&mut self, text: &str) -> () {
+        let _ = self.stderr.write_all(text.as_bytes());
+    }
+}
@@ -0,0 +1,168 @@
+use std::{fmt, rc::Rc};
+
+use crate::{lexer::syntax::Span, source::SourceFile};
+
+macro_rules! opcodes {
+    ($($(#[$attribute:meta])* $name:ident),* $(,)?) => {
+        /// A single bytecode instruction, followed in the chunk by its operands (if any)
+        #[repr(u8)]
+        #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+        pub enum OpCode {
+            $($(#[$attribute])* $name),*
+        }
+
+        impl OpCode {
+            const ALL: &'static [OpCode] = &[$(OpCode::$name),*];
+
+            pub fn from_byte(byte: u8) -> Option<OpCode> {
+                Self::ALL.get(byte as usize).copied()
+            }
+        }
+    };
+}
+
+opcodes! {
+    /// `u16` constant index
+    Constant,
+    Null,
+    True,
+    False,
+    Pop,
+
+    /// `u8` stack slot relative to the frame base
+    GetLocal,
+    /// `u8` stack slot relative to the frame base
+    SetLocal,
+    /// `u16` constant index of the name
+    DefineGlobal,
+    /// `u16` constant index of the name
+    GetGlobal,
+    /// `u16` constant index of the name
+    SetGlobal,
+    /// `u8` upvalue index
+    GetUpvalue,
+    /// `u8` upvalue index
+    SetUpvalue,
+    CloseUpvalue,
+
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    FloorDivide,
+    Modulo,
+    Power,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Negate,
+    Not,
+    BitNot,
+    /// `u8` part count, pops the parts and pushes their text joined into a string
+    Interpolate,
+
+    /// `u16` forward offset
+    Jump,
+    /// `u16` forward offset, pops the condition
+    JumpIfFalse,
+    /// `u16` backward offset
+    Loop,
+    /// `u16` forward offset, taken when the boolean on top of the stack is false, otherwise pops it
+    AndJump,
+    /// `u16` forward offset, taken when the boolean on top of the stack is true, otherwise pops it
+    OrJump,
+    /// `u8` operator, 0 for `&&` and 1 for `||`; fails unless the top of the stack is a boolean
+    ExpectBool,
+    /// `u8` slot of the iterable (followed by the index), `u16` forward offset taken once exhausted
+    ForNext,
+
+    /// `u8` argument count
+    Call,
+    /// `u16` constant index of the function, then an `(is_local, index)` byte pair per upvalue
+    Closure,
+    Return,
+}
+
+/// A value known at compile time, stored in a chunk's constant pool
+#[derive(Debug, Clone)]
+pub enum Constant {
+    Int(i64),
+    Float(f64),
+    String(String),
+    Function(Rc<CompiledFunction>),
+}
+
+impl fmt::Display for Constant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Constant::Int(value) => write!(f, "{}", value),
+            Constant::Float(value) => write!(f, "{:?}", value),
+            Constant::String(value) => write!(f, "{:?}", value),
+            Constant::Function(function) => write!(f, "<fn {}>", function.name),
+        }
+    }
+}
+
+/// A sequence of bytecode along with its constants and the source spans it was compiled from
+#[derive(Debug, Clone, Default)]
+pub struct Chunk {
+    pub code: Vec<u8>,
+    pub constants: Vec<Constant>,
+    /// Run-length encoded `(offset, span)` pairs: every byte from `offset` up to the next entry
+    /// was compiled from `span`
+    spans: Vec<(usize, Span)>,
+}
+
+impl Chunk {
+    pub fn write(&mut self, byte: u8, span: &Span) -> () {
+        if self.spans.last().is_none_or(|(_, last)| last != span) {
+            self.spans.push((self.code.len(), *span));
+        }
+
+        self.code.push(byte);
+    }
+
+    pub fn add_constant(&mut self, constant: Constant) -> usize {
+        self.constants.push(constant);
+        self.constants.len() - 1
+    }
+
+    pub fn read_u16(&self, offset: usize) -> u16 {
+        u16::from_be_bytes([self.code[offset], self.code[offset + 1]])
+    }
+
+    /// Returns the span of the source that the byte at `offset` was compiled from
+    pub fn span_at(&self, offset: usize) -> Span {
+        let index = match self
+            .spans
+            .binary_search_by_key(&offset, |(start, _)| *start)
+        {
+            Ok(index) => index,
+            Err(next) => next.saturating_sub(1),
+        };
+
+        self.spans
+            .get(index)
+            .map(|(_, span)| *span)
+            .unwrap_or_default()
+    }
+
+    /// Returns the line of `file` that the byte at `offset` was compiled from
+    pub fn line_at(&self, offset: usize, file: &SourceFile) -> usize {
+        file.location(self.span_at(offset).start as usize).line
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CompiledFunction {
+    pub name: String,
+    pub arity: usize,
+    pub upvalue_count: usize,
+    pub chunk: Chunk,
+}
@@ -0,0 +1,98 @@
+use super::chunk::{Chunk, Constant, OpCode};
+use crate::source::SourceFile;
+
+/// Returns a human readable listing of `chunk` and of every function in its constant pool, with
+/// line numbers taken from `file`
+///
+/// ```text
+/// == <script> ==
+/// 0000    1 Constant            0 '1'
+/// 0003    | DefineGlobal        1 '"a"'
+/// ```
+pub fn disassemble(chunk: &Chunk, name: &str, file: &SourceFile) -> String {
+    let mut output = format!("== {} ==\n", name);
+
+    let mut offset = 0;
+    while offset < chunk.code.len() {
+        let (line, next_offset) = disassemble_instruction(chunk, offset, file);
+        output += &line;
+        output.push('\n');
+        offset = next_offset;
+    }
+
+    for constant in chunk.constants.iter() {
+        if let Constant::Function(function) = constant {
+            output.push('\n');
+            output += &disassemble(&function.chunk, &function.name, file);
+        }
+    }
+
+    output
+}
+
+/// Returns the listing of the instruction at `offset` and the offset of the next instruction
+pub fn disassemble_instruction(chunk: &Chunk, offset: usize, file: &SourceFile) -> (String, usize) {
+    let line = chunk.line_at(offset, file);
+    let line_column = if offset > 0 && line == chunk.line_at(offset - 1, file) {
+        "   |".to_string()
+    } else {
+        format!("{:4}", line)
+    };
+    let prefix = format!("{:04} {} ", offset, line_column);
+
+    let Some(op) = OpCode::from_byte(chunk.code[offset]) else {
+        return (
+            format!("{}<unknown opcode {}>", prefix, chunk.code[offset]),
+            offset + 1,
+        );
+    };
+
+    let name = format!("{:?}", op);
+    let (operands, size) = match op {
+        OpCode::Constant | OpCode::DefineGlobal | OpCode::GetGlobal | OpCode::SetGlobal => {
+            let index = chunk.read_u16(offset + 1) as usize;
+            (format!("{:4} '{}'", index, chunk.constants[index]), 3)
+        }
+        OpCode::GetLocal
+        | OpCode::SetLocal
+        | OpCode::GetUpvalue
+        | OpCode::SetUpvalue
+        | OpCode::Interpolate
+        | OpCode::Call
+        | OpCode::ExpectBool => (format!("{:4}", chunk.code[offset + 1]), 2),
+        OpCode::Jump | OpCode::JumpIfFalse | OpCode::AndJump | OpCode::OrJump => {
+            let jump = chunk.read_u16(offset + 1) as usize;
+            (format!("{:4} -> {}", offset, offset + 3 + jump), 3)
+        }
+        OpCode::Loop => {
+            let jump = chunk.read_u16(offset + 1) as usize;
+            (format!("{:4} -> {}", offset, offset + 3 - jump), 3)
+        }
+        OpCode::ForNext => {
+            let slot = chunk.code[offset + 1];
+            let jump = chunk.read_u16(offset + 2) as usize;
+            (format!("{:4} -> {}", slot, offset + 4 + jump), 4)
+        }
+        OpCode::Closure => {
+            let index = chunk.read_u16(offset + 1) as usize;
+            let mut operands = format!("{:4} '{}'", index, chunk.constants[index]);
+            let mut size = 3;
+
+            if let Constant::Function(function) = &chunk.constants[index] {
+                for _ in 0..function.upvalue_count {
+                    let is_local = chunk.code[offset + size] == 1;
+                    let index = chunk.code[offset + size + 1];
+                    let kind = if is_local { "local" } else { "upvalue" };
+                    operands += &format!(" ({} {})", kind, index);
+                    size += 2;
+                }
+            }
+
+            (operands, size)
+        }
+        _ => (String::new(), 1),
+    };
+
+    let listing = format!("{}{:<16} {}", prefix, name, operands);
+    (listing.trim_end().to_string(), offset + size)
+}
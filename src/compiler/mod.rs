@@ -0,0 +1,732 @@
+pub mod chunk;
+pub mod disassembler;
+
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    diagnostics::{Diagnostic, DiagnosticCode},
+    lexer::syntax::{LiteralValue, Span, SyntaxKind, Token},
+    parser::ast::{
+        base::{Expression, Identifier, Statement},
+        expressions::{
+            Assignment, Binary, Call, ErrorExpression, Grouping, Interpolation, InterpolationPart,
+            Literal, Unary, Variable,
+        },
+        statements::{
+            BlockStatement, BreakStatement, ContinueStatement, ErrorStatement, ExpressionStatement,
+            ForStatement, FunctionDeclaration, IfStatement, ImportStatement, LetStatement, Program,
+            ReturnStatement, StatementNode, WhileStatement,
+        },
+        visitor::{ExpressionVisitor, StatementVisitor, SyntaxNodeVisitor},
+    },
+};
+use chunk::{CompiledFunction, Constant, OpCode};
+
+type CompileResult = Result<(), Diagnostic>;
+
+struct Local {
+    name: String,
+    depth: usize,
+    captured: bool,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+struct UpvalueDescriptor {
+    is_local: bool,
+    index: u8,
+}
+
+struct Loop {
+    /// Offset that `continue` jumps back to
+    start: usize,
+    /// Scope depth outside of the loop body, locals deeper than this are discarded on a jump
+    scope_depth: usize,
+    /// Offsets of the `break` jumps to patch once the end of the loop is known
+    breaks: Vec<usize>,
+}
+
+/// Compilation state of a single function, functions nest as they are declared
+struct FunctionState {
+    function: CompiledFunction,
+    locals: Vec<Local>,
+    upvalues: Vec<UpvalueDescriptor>,
+    scope_depth: usize,
+    loops: Vec<Loop>,
+}
+
+impl FunctionState {
+    fn new(name: &str, arity: usize) -> Self {
+        Self {
+            function: CompiledFunction {
+                name: name.to_string(),
+                arity,
+                ..CompiledFunction::default()
+            },
+            // slot zero holds the function being called
+            locals: vec![Local {
+                name: String::new(),
+                depth: 0,
+                captured: false,
+            }],
+            upvalues: Vec::new(),
+            scope_depth: 0,
+            loops: Vec::new(),
+        }
+    }
+
+    fn resolve_local(&self, name: &str) -> Option<usize> {
+        self.locals
+            .iter()
+            .skip(1)
+            .rposition(|local| local.name == name)
+            .map(|index| index + 1)
+    }
+}
+
+/// Lowers a program into bytecode for the `vm`
+///
+/// Bindings at the top level of a program are globals looked up by name, every other binding
+/// lives in a stack slot. Locals captured by nested functions are turned into upvalues
+pub struct Compiler {
+    functions: RefCell<Vec<FunctionState>>,
+}
+
+impl Default for Compiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Compiler {
+    pub fn new() -> Self {
+        Self {
+            functions: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Compiles `program` into a function taking no arguments that runs it from top to bottom
+    pub fn compile(&self, program: &Program) -> Result<CompiledFunction, Diagnostic> {
+        self.functions
+            .replace(vec![FunctionState::new("<script>", 0)]);
+
+        for statement in program.statements.iter() {
+            self.compile_statement(statement)?;
+        }
+
+        let end = match program.statements.last() {
+            Some(statement) => statement.span(),
+            None => Span::default(),
+        };
+        self.emit_op(OpCode::Null, &end);
+        self.emit_op(OpCode::Return, &end);
+
+        let state = self.functions.borrow_mut().pop().unwrap();
+        Ok(state.function)
+    }
+
+    fn compile_statement(&self, statement: &StatementNode) -> CompileResult {
+        Statement::accept(statement, self)
+    }
+
+    fn compile_expression(&self, expression: &impl Expression) -> CompileResult {
+        Expression::accept(expression, self)
+    }
+
+    fn with_current<T>(&self, f: impl FnOnce(&mut FunctionState) -> T) -> T {
+        f(self.functions.borrow_mut().last_mut().unwrap())
+    }
+
+    fn emit_byte(&self, byte: u8, span: &Span) -> () {
+        self.with_current(|state| state.function.chunk.write(byte, span));
+    }
+
+    fn emit_op(&self, op: OpCode, span: &Span) -> () {
+        self.emit_byte(op as u8, span);
+    }
+
+    fn emit_u16(&self, value: u16, span: &Span) -> () {
+        for byte in value.to_be_bytes() {
+            self.emit_byte(byte, span);
+        }
+    }
+
+    fn current_offset(&self) -> usize {
+        self.with_current(|state| state.function.chunk.code.len())
+    }
+
+    fn add_constant(&self, constant: Constant, span: &Span) -> Result<u16, Diagnostic> {
+        let index = self.with_current(|state| state.function.chunk.add_constant(constant));
+        u16::try_from(index).map_err(|_| limit_exceeded("too many constants in one function", span))
+    }
+
+    fn emit_constant(&self, constant: Constant, span: &Span) -> CompileResult {
+        let index = self.add_constant(constant, span)?;
+        self.emit_op(OpCode::Constant, span);
+        self.emit_u16(index, span);
+        Ok(())
+    }
+
+    /// Emits a forward jump with a placeholder offset, returning the offset of the placeholder
+    fn emit_jump(&self, op: OpCode, span: &Span) -> usize {
+        self.emit_op(op, span);
+        self.emit_u16(u16::MAX, span);
+        self.current_offset() - 2
+    }
+
+    /// Points the jump whose placeholder is at `offset` to the current end of the chunk
+    fn patch_jump(&self, offset: usize, span: &Span) -> CompileResult {
+        let jump = self.current_offset() - offset - 2;
+        let jump = u16::try_from(jump).map_err(|_| limit_exceeded("jump is too large", span))?;
+
+        self.with_current(|state| {
+            let [high, low] = jump.to_be_bytes();
+            state.function.chunk.code[offset] = high;
+            state.function.chunk.code[offset + 1] = low;
+        });
+        Ok(())
+    }
+
+    fn emit_loop(&self, start: usize, span: &Span) -> CompileResult {
+        self.emit_op(OpCode::Loop, span);
+        let jump = self.current_offset() - start + 2;
+        let jump =
+            u16::try_from(jump).map_err(|_| limit_exceeded("loop body is too large", span))?;
+        self.emit_u16(jump, span);
+        Ok(())
+    }
+
+    fn begin_scope(&self) -> () {
+        self.with_current(|state| state.scope_depth += 1);
+    }
+
+    /// Leaves the current scope, discarding (or closing over) every local declared in it
+    fn end_scope(&self, span: &Span) -> () {
+        let ops = self.with_current(|state| {
+            state.scope_depth -= 1;
+
+            let mut ops = Vec::new();
+            while state
+                .locals
+                .last()
+                .is_some_and(|local| local.depth > state.scope_depth)
+            {
+                let local = state.locals.pop().unwrap();
+                ops.push(discard_op(&local));
+            }
+            ops
+        });
+
+        for op in ops {
+            self.emit_op(op, span);
+        }
+    }
+
+    /// Adds a local in the current scope, which must be done after compiling its initializer
+    fn add_local(&self, name: &Identifier) -> CompileResult {
+        let count = self.with_current(|state| {
+            state.locals.push(Local {
+                name: name.text.clone(),
+                depth: state.scope_depth,
+                captured: false,
+            });
+            state.locals.len()
+        });
+
+        if count > u8::MAX as usize + 1 {
+            return Err(limit_exceeded(
+                "too many local variables in one function",
+                &name.span,
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn is_global_scope(&self) -> bool {
+        let functions = self.functions.borrow();
+        functions.len() == 1 && functions[0].scope_depth == 0
+    }
+
+    /// Binds the value on top of the stack to `name`, either as a global or as a new local
+    fn define_variable(&self, name: &Identifier) -> CompileResult {
+        if !self.is_global_scope() {
+            return self.add_local(name);
+        }
+
+        let index = self.add_constant(Constant::String(name.text.clone()), &name.span)?;
+        self.emit_op(OpCode::DefineGlobal, &name.span);
+        self.emit_u16(index, &name.span);
+        Ok(())
+    }
+
+    /// Emits the instruction reading (or writing) the variable `name`
+    fn emit_variable(&self, name: &Identifier, set: bool) -> CompileResult {
+        let depth = self.functions.borrow().len() - 1;
+        let span = &name.span;
+
+        let local = self.functions.borrow()[depth].resolve_local(&name.text);
+        if let Some(slot) = local {
+            let op = if set {
+                OpCode::SetLocal
+            } else {
+                OpCode::GetLocal
+            };
+            self.emit_op(op, span);
+            self.emit_byte(slot as u8, span);
+            return Ok(());
+        }
+
+        if let Some(index) = self.resolve_upvalue(depth, &name.text, span)? {
+            let op = if set {
+                OpCode::SetUpvalue
+            } else {
+                OpCode::GetUpvalue
+            };
+            self.emit_op(op, span);
+            self.emit_byte(index, span);
+            return Ok(());
+        }
+
+        let index = self.add_constant(Constant::String(name.text.clone()), span)?;
+        let op = if set {
+            OpCode::SetGlobal
+        } else {
+            OpCode::GetGlobal
+        };
+        self.emit_op(op, span);
+        self.emit_u16(index, span);
+        Ok(())
+    }
+
+    /// Resolves `name` as a variable captured from a function enclosing the one at `depth`
+    fn resolve_upvalue(
+        &self,
+        depth: usize,
+        name: &str,
+        span: &Span,
+    ) -> Result<Option<u8>, Diagnostic> {
+        if depth == 0 {
+            return Ok(None);
+        }
+
+        let mut functions = self.functions.borrow_mut();
+        if let Some(slot) = functions[depth - 1].resolve_local(name) {
+            functions[depth - 1].locals[slot].captured = true;
+            drop(functions);
+            return self.add_upvalue(depth, true, slot as u8, span).map(Some);
+        }
+        drop(functions);
+
+        match self.resolve_upvalue(depth - 1, name, span)? {
+            Some(index) => self.add_upvalue(depth, false, index, span).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn add_upvalue(
+        &self,
+        depth: usize,
+        is_local: bool,
+        index: u8,
+        span: &Span,
+    ) -> Result<u8, Diagnostic> {
+        let mut functions = self.functions.borrow_mut();
+        let state = &mut functions[depth];
+        let descriptor = UpvalueDescriptor { is_local, index };
+
+        if let Some(existing) = state
+            .upvalues
+            .iter()
+            .position(|upvalue| *upvalue == descriptor)
+        {
+            return Ok(existing as u8);
+        }
+
+        if state.upvalues.len() > u8::MAX as usize {
+            return Err(limit_exceeded(
+                "too many captured variables in one function",
+                span,
+            ));
+        }
+
+        state.upvalues.push(descriptor);
+        state.function.upvalue_count = state.upvalues.len();
+        Ok((state.upvalues.len() - 1) as u8)
+    }
+
+    /// Emits a jump out of the innermost loop, discarding the locals declared inside of it
+    fn emit_loop_exit(&self, keyword: &Token, is_break: bool) -> CompileResult {
+        let (start, ops) = self.with_current(|state| {
+            let current_loop = state.loops.last().unwrap();
+            let ops: Vec<OpCode> = state
+                .locals
+                .iter()
+                .rev()
+                .take_while(|local| local.depth > current_loop.scope_depth)
+                .map(discard_op)
+                .collect();
+
+            (current_loop.start, ops)
+        });
+
+        for op in ops {
+            self.emit_op(op, &keyword.span);
+        }
+
+        if is_break {
+            let jump = self.emit_jump(OpCode::Jump, &keyword.span);
+            self.with_current(|state| state.loops.last_mut().unwrap().breaks.push(jump));
+            Ok(())
+        } else {
+            self.emit_loop(start, &keyword.span)
+        }
+    }
+
+    fn begin_loop(&self, start: usize) -> () {
+        self.with_current(|state| {
+            let scope_depth = state.scope_depth;
+            state.loops.push(Loop {
+                start,
+                scope_depth,
+                breaks: Vec::new(),
+            });
+        });
+    }
+
+    fn end_loop(&self, span: &Span) -> CompileResult {
+        let current_loop = self.with_current(|state| state.loops.pop().unwrap());
+        for jump in current_loop.breaks {
+            self.patch_jump(jump, span)?;
+        }
+
+        Ok(())
+    }
+
+    fn compile_function_body(&self, function: &FunctionDeclaration) -> CompileResult {
+        self.begin_scope();
+        for parameter in function.parameters.iter() {
+            self.add_local(&parameter.name)?;
+        }
+        for statement in function.body.statements.iter() {
+            self.compile_statement(statement)?;
+        }
+
+        self.emit_op(OpCode::Null, &function.body.span);
+        self.emit_op(OpCode::Return, &function.body.span);
+        Ok(())
+    }
+
+    fn compile_block(&self, block: &BlockStatement) -> CompileResult {
+        self.begin_scope();
+        for statement in block.statements.iter() {
+            self.compile_statement(statement)?;
+        }
+        self.end_scope(&block.span);
+
+        Ok(())
+    }
+}
+
+fn discard_op(local: &Local) -> OpCode {
+    if local.captured {
+        OpCode::CloseUpvalue
+    } else {
+        OpCode::Pop
+    }
+}
+
+fn limit_exceeded(message: &str, span: &Span) -> Diagnostic {
+    Diagnostic::error(DiagnosticCode::CompilerLimitExceeded, message, *span)
+}
+
+fn get_binary_op(kind: SyntaxKind) -> OpCode {
+    match kind {
+        SyntaxKind::Plus => OpCode::Add,
+        SyntaxKind::Minus => OpCode::Subtract,
+        SyntaxKind::Star => OpCode::Multiply,
+        SyntaxKind::Slash => OpCode::Divide,
+        SyntaxKind::TildeSlash => OpCode::FloorDivide,
+        SyntaxKind::Percent => OpCode::Modulo,
+        SyntaxKind::Carat => OpCode::Power,
+        SyntaxKind::Ampersand => OpCode::BitAnd,
+        SyntaxKind::Pipe => OpCode::BitOr,
+        SyntaxKind::Tilde => OpCode::BitXor,
+        SyntaxKind::EqualsEquals => OpCode::Equal,
+        SyntaxKind::BangEquals => OpCode::NotEqual,
+        SyntaxKind::LT => OpCode::Less,
+        SyntaxKind::LTE => OpCode::LessEqual,
+        SyntaxKind::GT => OpCode::Greater,
+        SyntaxKind::GTE => OpCode::GreaterEqual,
+        _ => unreachable!("{:?} is not a binary operator", kind),
+    }
+}
+
+impl SyntaxNodeVisitor<CompileResult> for Compiler {}
+
+impl ExpressionVisitor<CompileResult> for Compiler {
+    fn visit_literal(&self, literal: &Literal) -> CompileResult {
+        let token = &literal.token;
+        let span = &token.span;
+
+        match &token.value {
+            Some(LiteralValue::Int(value)) => {
+                self.emit_constant(Constant::Int(*value), span)?;
+            }
+            Some(LiteralValue::Float(value)) => {
+                self.emit_constant(Constant::Float(*value), span)?;
+            }
+            Some(LiteralValue::Str(value)) => {
+                self.emit_constant(Constant::String(value.clone()), span)?;
+            }
+            Some(LiteralValue::Bool(value)) => {
+                let op = if *value { OpCode::True } else { OpCode::False };
+                self.emit_op(op, span);
+            }
+            Some(LiteralValue::Interpolated(_)) => {
+                unreachable!("interpolated strings are parsed into interpolations")
+            }
+            None => self.emit_op(OpCode::Null, span),
+        }
+
+        Ok(())
+    }
+
+    fn visit_interpolation(&self, interpolation: &Interpolation) -> CompileResult {
+        let span = &interpolation.token.span;
+        for part in interpolation.parts.iter() {
+            match part {
+                InterpolationPart::Text(text) => {
+                    self.emit_constant(Constant::String(text.clone()), span)?;
+                }
+                InterpolationPart::Expression(expression) => self.compile_expression(expression)?,
+            }
+        }
+
+        let count = u8::try_from(interpolation.parts.len())
+            .map_err(|_| limit_exceeded("too many parts in one interpolated string", span))?;
+        self.emit_op(OpCode::Interpolate, span);
+        self.emit_byte(count, span);
+        Ok(())
+    }
+
+    fn visit_binary(&self, binary: &Binary) -> CompileResult {
+        self.compile_expression(binary.left.as_ref())?;
+
+        let kind = binary.operator.kind;
+        if matches!(kind, SyntaxKind::AmpersandAmpersand | SyntaxKind::PipePipe) {
+            let (op, operator) = if kind == SyntaxKind::AmpersandAmpersand {
+                (OpCode::AndJump, 0)
+            } else {
+                (OpCode::OrJump, 1)
+            };
+
+            let end_jump = self.emit_jump(op, &binary.left.span());
+            let right_span = binary.right.span();
+            self.compile_expression(binary.right.as_ref())?;
+            self.emit_op(OpCode::ExpectBool, &right_span);
+            self.emit_byte(operator, &right_span);
+            return self.patch_jump(end_jump, &right_span);
+        }
+
+        self.compile_expression(binary.right.as_ref())?;
+        let span = binary.left.span().to(&binary.right.span());
+        self.emit_op(get_binary_op(kind), &span);
+        Ok(())
+    }
+
+    fn visit_unary(&self, unary: &Unary) -> CompileResult {
+        self.compile_expression(unary.operand.as_ref())?;
+
+        let op = match unary.operator.kind {
+            SyntaxKind::Minus => OpCode::Negate,
+            SyntaxKind::Bang => OpCode::Not,
+            _ => OpCode::BitNot,
+        };
+        self.emit_op(op, &unary.operator.span.to(&unary.operand.span()));
+        Ok(())
+    }
+
+    fn visit_grouping(&self, grouping: &Grouping) -> CompileResult {
+        self.compile_expression(grouping.expression.as_ref())
+    }
+
+    fn visit_variable(&self, variable: &Variable) -> CompileResult {
+        self.emit_variable(&variable.name, false)
+    }
+
+    fn visit_assignment(&self, assignment: &Assignment) -> CompileResult {
+        self.compile_expression(assignment.value.as_ref())?;
+        self.emit_variable(&assignment.name, true)
+    }
+
+    fn visit_call(&self, call: &Call) -> CompileResult {
+        self.compile_expression(call.callee.as_ref())?;
+        for argument in call.arguments.iter() {
+            self.compile_expression(argument)?;
+        }
+
+        let count = u8::try_from(call.arguments.len())
+            .map_err(|_| limit_exceeded("too many arguments in one call", &call.span))?;
+        self.emit_op(OpCode::Call, &call.span);
+        self.emit_byte(count, &call.span);
+        Ok(())
+    }
+
+    fn visit_error_expression(&self, _error: &ErrorExpression) -> CompileResult {
+        unreachable!("programs with syntax errors are never compiled")
+    }
+}
+
+impl StatementVisitor<CompileResult> for Compiler {
+    fn visit_let_statement(&self, let_statement: &LetStatement) -> CompileResult {
+        self.compile_expression(&let_statement.initializer)?;
+        self.define_variable(&let_statement.name)
+    }
+
+    fn visit_expression_statement(
+        &self,
+        expression_statement: &ExpressionStatement,
+    ) -> CompileResult {
+        let expression = &expression_statement.expression;
+        self.compile_expression(expression)?;
+        self.emit_op(OpCode::Pop, &expression.span());
+        Ok(())
+    }
+
+    fn visit_block_statement(&self, block: &BlockStatement) -> CompileResult {
+        self.compile_block(block)
+    }
+
+    fn visit_function_declaration(&self, function: &FunctionDeclaration) -> CompileResult {
+        let name = &function.name;
+        let is_global = self.is_global_scope();
+
+        // declare the function before compiling its body so it can call itself
+        if !is_global {
+            self.add_local(name)?;
+        }
+
+        self.functions.borrow_mut().push(FunctionState::new(
+            name.display_name(),
+            function.parameters.len(),
+        ));
+        let result = self.compile_function_body(function);
+        let state = self.functions.borrow_mut().pop().unwrap();
+        result?;
+
+        let span = &name.span;
+        let index = self.add_constant(Constant::Function(Rc::new(state.function)), span)?;
+        self.emit_op(OpCode::Closure, span);
+        self.emit_u16(index, span);
+        for upvalue in state.upvalues {
+            self.emit_byte(upvalue.is_local as u8, span);
+            self.emit_byte(upvalue.index, span);
+        }
+
+        if is_global {
+            self.define_variable(name)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_return_statement(&self, return_statement: &ReturnStatement) -> CompileResult {
+        match &return_statement.value {
+            Some(value) => self.compile_expression(value)?,
+            None => self.emit_op(OpCode::Null, &return_statement.keyword.span),
+        }
+
+        self.emit_op(OpCode::Return, &return_statement.keyword.span);
+        Ok(())
+    }
+
+    fn visit_if_statement(&self, if_statement: &IfStatement) -> CompileResult {
+        let condition_span = if_statement.condition.span();
+        self.compile_expression(&if_statement.condition)?;
+        let else_jump = self.emit_jump(OpCode::JumpIfFalse, &condition_span);
+
+        self.compile_block(&if_statement.then_branch)?;
+
+        let span = &if_statement.then_branch.span;
+        match &if_statement.else_branch {
+            Some(else_branch) => {
+                let end_jump = self.emit_jump(OpCode::Jump, span);
+                self.patch_jump(else_jump, span)?;
+                self.compile_statement(else_branch)?;
+                self.patch_jump(end_jump, span)
+            }
+            None => self.patch_jump(else_jump, span),
+        }
+    }
+
+    fn visit_while_statement(&self, while_statement: &WhileStatement) -> CompileResult {
+        let start = self.current_offset();
+        let condition_span = while_statement.condition.span();
+        self.compile_expression(&while_statement.condition)?;
+        let exit_jump = self.emit_jump(OpCode::JumpIfFalse, &condition_span);
+
+        self.begin_loop(start);
+        self.compile_block(&while_statement.body)?;
+        self.emit_loop(start, &while_statement.body.span)?;
+
+        self.patch_jump(exit_jump, &condition_span)?;
+        self.end_loop(&while_statement.body.span)
+    }
+
+    fn visit_for_statement(&self, for_statement: &ForStatement) -> CompileResult {
+        let iterable_span = for_statement.iterable.span();
+        let keyword = &for_statement.keyword;
+
+        // the iterable and the index of the next item live in hidden locals for the whole loop
+        self.begin_scope();
+        self.compile_expression(&for_statement.iterable)?;
+        self.add_local(&Identifier {
+            text: "<iterable>".to_string(),
+            span: keyword.span,
+            source_text: None,
+        })?;
+        self.emit_constant(Constant::Int(0), &keyword.span)?;
+        self.add_local(&Identifier {
+            text: "<index>".to_string(),
+            span: keyword.span,
+            source_text: None,
+        })?;
+
+        let slot = self.with_current(|state| state.locals.len() - 2) as u8;
+        let start = self.current_offset();
+        self.emit_op(OpCode::ForNext, &iterable_span);
+        self.emit_byte(slot, &iterable_span);
+        self.emit_u16(u16::MAX, &iterable_span);
+        let exit_jump = self.current_offset() - 2;
+
+        self.begin_loop(start);
+        self.begin_scope();
+        self.add_local(&for_statement.variable)?;
+        self.compile_block(&for_statement.body)?;
+        self.end_scope(&for_statement.body.span);
+        self.emit_loop(start, &for_statement.body.span)?;
+
+        self.patch_jump(exit_jump, &iterable_span)?;
+        self.end_loop(&for_statement.body.span)?;
+        self.end_scope(&for_statement.body.span);
+        Ok(())
+    }
+
+    fn visit_break_statement(&self, break_statement: &BreakStatement) -> CompileResult {
+        self.emit_loop_exit(&break_statement.keyword, true)
+    }
+
+    fn visit_continue_statement(&self, continue_statement: &ContinueStatement) -> CompileResult {
+        self.emit_loop_exit(&continue_statement.keyword, false)
+    }
+
+    /// Imported modules are linked into the program ahead of the importing file, so there is
+    /// nothing left to do by the time an import is compiled
+    fn visit_import_statement(&self, _import: &ImportStatement) -> CompileResult {
+        Ok(())
+    }
+
+    fn visit_error_statement(&self, _error: &ErrorStatement) -> CompileResult {
+        unreachable!("programs with syntax errors are never compiled")
+    }
+}
@@ -0,0 +1,146 @@
+pub mod renderer;
+
+use std::fmt;
+
+use crate::lexer::syntax::Span;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// Stable identifiers for every diagnostic the toolchain can emit.
+///
+/// Codes are displayed as `E` followed by the zero-padded discriminant (e.g. `E0001`),
+/// so variants must never be reordered once released.
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum DiagnosticCode {
+    UnexpectedCharacter = 1, // lexer
+    UnterminatedString,
+    MalformedNumber,
+    UnterminatedComment,
+    IntegerOutOfRange,
+    InvalidNumberSuffix,
+    InvalidEscape,
+    FloatOutOfRange,
+
+    UnexpectedToken = 100, // parser
+    UnexpectedEndOfFile,
+    InvalidAssignmentTarget,
+    ReturnOutsideFunction,
+    BreakOutsideLoop,
+    ContinueOutsideLoop,
+    InvalidVisibility,
+    MisplacedImport,
+
+    CompilerLimitExceeded = 200, // compiler
+
+    UndefinedVariable = 300, // runtime
+    InvalidOperands,
+    DivisionByZero,
+    IntegerOverflow,
+    NotCallable,
+    ArityMismatch,
+    NotIterable,
+    InvalidCondition,
+    StackOverflow,
+    InvalidArgument,
+    NegativeExponent,
+
+    UndefinedName = 400, // resolver
+    DuplicateDeclaration,
+    UseBeforeDefinition,
+    PrivateName,
+
+    UnknownType = 500, // type checker
+    TypeMismatch,
+    InvalidOperandTypes,
+    NotAFunction,
+    WrongArgumentCount,
+    NotIterableType,
+    MissingReturn,
+
+    UnresolvedImport = 600, // module loader
+    ImportCycle,
+}
+
+impl fmt::Display for DiagnosticCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "E{:04}", *self as u16)
+    }
+}
+
+/// A secondary span attached to a diagnostic, e.g. pointing at a previous declaration
+#[derive(Debug, Clone)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: DiagnosticCode,
+    pub message: String,
+    pub span: Span,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+    pub help: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn error(code: DiagnosticCode, message: impl Into<String>, span: Span) -> Self {
+        Self {
+            severity: Severity::Error,
+            code,
+            message: message.into(),
+            span,
+            labels: Vec::new(),
+            notes: Vec::new(),
+            help: None,
+        }
+    }
+
+    pub fn with_label(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+        });
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}[{}]: {} ({})",
+            self.severity, self.code, self.message, self.span
+        )
+    }
+}
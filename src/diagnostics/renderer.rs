@@ -0,0 +1,305 @@
+use std::{collections::BTreeSet, env, io::IsTerminal};
+
+use super::{Diagnostic, Severity};
+use crate::{
+    lexer::syntax::Span,
+    source::{SourceFile, SourceMap},
+};
+
+const TAB_WIDTH: usize = 4;
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const BLUE: &str = "\x1b[1;34m";
+const CYAN: &str = "\x1b[1;36m";
+
+/// Renders diagnostics as human readable reports, quoting the offending source lines
+///
+/// ```text
+/// error[E0001]: unexpected character '`'
+///  --> test.ion:1:9
+///   |
+/// 1 | let x = `
+///   |         ^
+/// ```
+pub struct Renderer {
+    colored: bool,
+}
+
+/// A span resolved into zero-based line indices and character columns
+struct Annotation<'a> {
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+    message: Option<&'a str>,
+    primary: bool,
+}
+
+impl Renderer {
+    pub fn new(colored: bool) -> Self {
+        Self { colored }
+    }
+
+    /// A renderer without ANSI escape codes, suitable for CI logs and files
+    pub fn plain() -> Self {
+        Self::new(false)
+    }
+
+    /// A renderer that only uses colours when stderr is a terminal and `NO_COLOR` is unset
+    pub fn for_stderr() -> Self {
+        let colored = std::io::stderr().is_terminal() && env::var_os("NO_COLOR").is_none();
+        Self::new(colored)
+    }
+
+    pub fn render_all(&self, diagnostics: &[Diagnostic], file: &SourceFile) -> String {
+        diagnostics
+            .iter()
+            .map(|diagnostic| self.render(diagnostic, file))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Renders diagnostics that may point into any file of `sources`
+    pub fn render_all_in(&self, diagnostics: &[Diagnostic], sources: &SourceMap) -> String {
+        diagnostics
+            .iter()
+            .map(|diagnostic| self.render_in(diagnostic, sources))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    pub fn render(&self, diagnostic: &Diagnostic, file: &SourceFile) -> String {
+        self.render_with(diagnostic, file, None)
+    }
+
+    /// Renders `diagnostic` against the file its span points into, listing labels pointing into
+    /// other files as notes
+    pub fn render_in(&self, diagnostic: &Diagnostic, sources: &SourceMap) -> String {
+        let file = sources.get(diagnostic.span.file);
+        self.render_with(diagnostic, file, Some(sources))
+    }
+
+    fn render_with(
+        &self,
+        diagnostic: &Diagnostic,
+        file: &SourceFile,
+        sources: Option<&SourceMap>,
+    ) -> String {
+        let mut annotations = vec![resolve(&diagnostic.span, None, true, file)];
+        let mut notes = Vec::new();
+        for label in diagnostic.labels.iter() {
+            match sources {
+                Some(sources) if label.span.file != file.id() => {
+                    let other = sources.get(label.span.file);
+                    let location = other.location(label.span.start as usize);
+                    notes.push(format!(
+                        "{} at {}:{}:{}",
+                        label.message,
+                        other.relative_path,
+                        location.line,
+                        location.column + 1
+                    ));
+                }
+                _ => annotations.push(resolve(&label.span, Some(&label.message), false, file)),
+            }
+        }
+        notes.extend(diagnostic.notes.iter().cloned());
+
+        let mut shown_lines = BTreeSet::new();
+        for annotation in annotations.iter() {
+            shown_lines.extend(annotation.start_line..=annotation.end_line);
+        }
+
+        let severity_color = match diagnostic.severity {
+            Severity::Error => RED,
+            Severity::Warning => YELLOW,
+        };
+        let last_line = shown_lines.last().copied().unwrap_or(0);
+        let gutter_width = (last_line + 1).to_string().len();
+        let empty_gutter = " ".repeat(gutter_width);
+
+        let mut output = String::new();
+        output += &format!(
+            "{}{}\n",
+            self.paint(
+                severity_color,
+                &format!("{}[{}]", diagnostic.severity, diagnostic.code)
+            ),
+            self.paint(BOLD, &format!(": {}", diagnostic.message)),
+        );
+
+        let primary = &annotations[0];
+        output += &format!(
+            "{}{} {}:{}:{}\n",
+            empty_gutter,
+            self.paint(BLUE, "-->"),
+            file.relative_path,
+            primary.start_line + 1,
+            primary.start_column + 1
+        );
+        output += &format!("{} {}\n", empty_gutter, self.paint(BLUE, "|"));
+
+        let mut previous_line: Option<usize> = None;
+        for line in shown_lines {
+            if previous_line.is_some_and(|previous| line > previous + 1) {
+                output += &format!("{}\n", self.paint(BLUE, "..."));
+            }
+            previous_line = Some(line);
+
+            let text = get_line_text(file, line);
+            let line_length = text.len();
+            output += &format!(
+                "{} {}\n",
+                self.paint(
+                    BLUE,
+                    &format!("{:>width$} |", line + 1, width = gutter_width)
+                ),
+                expand_tabs(&text),
+            );
+
+            for annotation in annotations.iter() {
+                if line < annotation.start_line || line > annotation.end_line {
+                    continue;
+                }
+
+                let start = if line == annotation.start_line {
+                    annotation.start_column
+                } else {
+                    0
+                };
+                let end = if line == annotation.end_line {
+                    annotation.end_column
+                } else {
+                    line_length
+                };
+
+                let display_start = display_width(&text[..start.min(line_length)]);
+                let display_end =
+                    display_width(&text[..end.min(line_length)]) + end.saturating_sub(line_length);
+                let marker_count = display_end.saturating_sub(display_start).max(1);
+
+                let (marker, color) = if annotation.primary {
+                    ("^", severity_color)
+                } else {
+                    ("-", BLUE)
+                };
+                let mut underline = marker.repeat(marker_count);
+                if line == annotation.end_line {
+                    if let Some(message) = annotation.message {
+                        underline = format!("{} {}", underline, message);
+                    }
+                }
+
+                output += &format!(
+                    "{} {} {}{}\n",
+                    empty_gutter,
+                    self.paint(BLUE, "|"),
+                    " ".repeat(display_start),
+                    self.paint(color, &underline)
+                );
+            }
+        }
+
+        if !notes.is_empty() || diagnostic.help.is_some() {
+            output += &format!("{} {}\n", empty_gutter, self.paint(BLUE, "|"));
+        }
+
+        for note in notes.iter() {
+            output += &format!(
+                "{} {} {}\n",
+                empty_gutter,
+                self.paint(BLUE, "="),
+                self.paint_prefixed(BOLD, "note:", note)
+            );
+        }
+
+        if let Some(help) = &diagnostic.help {
+            output += &format!(
+                "{} {} {}\n",
+                empty_gutter,
+                self.paint(BLUE, "="),
+                self.paint_prefixed(CYAN, "help:", help)
+            );
+        }
+
+        output
+    }
+
+    fn paint(&self, color: &str, text: &str) -> String {
+        if self.colored {
+            format!("{}{}{}", color, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn paint_prefixed(&self, color: &str, prefix: &str, text: &str) -> String {
+        format!("{} {}", self.paint(color, prefix), text)
+    }
+}
+
+fn resolve<'a>(
+    span: &Span,
+    message: Option<&'a str>,
+    primary: bool,
+    file: &SourceFile,
+) -> Annotation<'a> {
+    let start = file.location(span.start as usize);
+    let end = file.location(span.end as usize);
+    let (start_line, mut end_line) = (start.line - 1, end.line - 1);
+    let mut end_column = end.column;
+
+    // a span ending right after a newline should not drag the next line into the report
+    if end_line > start_line && end_column == 0 {
+        end_line -= 1;
+        let line_start = file.line_start(end_line);
+        end_column = file.source()[line_start..span.end as usize].chars().count();
+    }
+
+    Annotation {
+        start_line,
+        start_column: start.column,
+        end_line,
+        end_column,
+        message,
+        primary,
+    }
+}
+
+/// Returns the characters of the given line, without its line terminator
+fn get_line_text(file: &SourceFile, line: usize) -> Vec<char> {
+    let start = file.line_start(line);
+    let end = if line + 1 < file.line_count() {
+        file.line_start(line + 1)
+    } else {
+        file.source().len()
+    };
+
+    file.source()[start..end]
+        .trim_end_matches(['\n', '\r'])
+        .chars()
+        .collect()
+}
+
+fn display_width(chars: &[char]) -> usize {
+    chars
+        .iter()
+        .map(|char| if *char == '\t' { TAB_WIDTH } else { 1 })
+        .sum()
+}
+
+fn expand_tabs(chars: &[char]) -> String {
+    chars
+        .iter()
+        .map(|char| {
+            if *char == '\t' {
+                " ".repeat(TAB_WIDTH)
+            } else {
+                char.to_string()
+            }
+        })
+        .collect()
+}
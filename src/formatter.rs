@@ -0,0 +1,451 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+};
+
+use crate::{
+    diagnostics::Diagnostic,
+    lexer::syntax::{Span, Token, Trivia},
+    parser::ast::{
+        base::{Expression, Statement},
+        expressions::{
+            Assignment, Binary, Call, ErrorExpression, ExpressionNode, Grouping, Interpolation,
+            Literal, Unary, Variable,
+        },
+        statements::{
+            BlockStatement, BreakStatement, ContinueStatement, ErrorStatement, ExpressionStatement,
+            ForStatement, FunctionDeclaration, IfStatement, ImportStatement, LetStatement, Program,
+            ReturnStatement, StatementNode, WhileStatement,
+        },
+        visitor::{ExpressionVisitor, StatementVisitor, SyntaxNodeVisitor},
+    },
+    source::SourceFile,
+};
+
+const INDENT: &str = "    ";
+/// Argument and parameter lists that would make a line longer than this are wrapped
+const MAX_WIDTH: usize = 100;
+
+/// Parses `file` and returns its canonical formatting
+pub fn format(file: &SourceFile) -> Result<String, Vec<Diagnostic>> {
+    let program = file.parse()?;
+    Ok(Formatter::new(file).format_program(&program))
+}
+
+/// Re-emits a parsed program as canonical ion source
+///
+/// Statements go on their own line, blocks are indented by four spaces and binary operators are
+/// surrounded by single spaces. Blank lines between statements are kept, but collapsed into
+/// one. The output only depends on the syntax tree, the comments and on where the blank lines
+/// were, so formatting is idempotent
+///
+/// Comments are taken from the trivia of the tokens. A comment following a statement on the
+/// same line stays there, and every other comment goes on its own line before the statement
+/// or closing brace that follows it. Comments between the tokens of a statement, outside of its
+/// blocks, go on their own lines before the statement
+pub struct Formatter<'a> {
+    file: &'a SourceFile,
+    /// The comments that were not written yet, in source order
+    comments: RefCell<VecDeque<Trivia>>,
+    output: RefCell<String>,
+    indent: Cell<usize>,
+    /// The column at which the expression being formatted starts, used to decide on wrapping
+    column: Cell<usize>,
+}
+
+impl<'a> Formatter<'a> {
+    pub fn new(file: &'a SourceFile) -> Self {
+        Self {
+            file,
+            comments: RefCell::new(file.tokenize().comments().cloned().collect()),
+            output: RefCell::new(String::new()),
+            indent: Cell::new(0),
+            column: Cell::new(0),
+        }
+    }
+
+    pub fn format_program(&self, program: &Program) -> String {
+        let end = self.file.source().len();
+        self.write_statements(&program.statements, end);
+        self.output.take()
+    }
+
+    /// Writes every statement, and every comment before `end`, on its own line, keeping a
+    /// single blank line wherever the source had at least one
+    fn write_statements(&self, statements: &[StatementNode], end: usize) -> () {
+        let mut previous_end_line = None;
+        for (index, statement) in statements.iter().enumerate() {
+            let span = statement.span();
+            self.write_comments_before(span.start as usize, &mut previous_end_line);
+
+            self.write_blank_line(span.start as usize, previous_end_line);
+            self.write_interior_comments(statement);
+            self.write(&self.indentation());
+            Statement::accept(statement, self);
+
+            // a comment after the statement but on the line of the next one belongs to the next
+            let next = statements.get(index + 1);
+            let next_start = next.map_or(end, |statement| statement.span().start as usize);
+            self.write_trailing_comments(span.end as usize, next_start);
+            self.write("\n");
+
+            previous_end_line = Some(self.line(span.end as usize));
+        }
+
+        self.write_comments_before(end, &mut previous_end_line);
+    }
+
+    /// Writes the comments starting before `position` that were not written yet
+    fn write_comments_before(&self, position: usize, previous_end_line: &mut Option<usize>) -> () {
+        while let Some(comment) = self.peek_comment() {
+            if comment.span.start as usize >= position {
+                break;
+            }
+
+            self.write_blank_line(comment.span.start as usize, *previous_end_line);
+            self.write(&self.indentation());
+            self.write(self.file.text(comment.span));
+            self.write("\n");
+            self.comments.borrow_mut().pop_front();
+
+            *previous_end_line = Some(self.line(comment.span.end as usize));
+        }
+    }
+
+    /// Writes the comments between the tokens of `statement` on their own lines, leaving those
+    /// inside its blocks to the blocks
+    ///
+    /// The statement itself is written without comments, and writing them before it keeps them
+    /// next to the code they describe rather than after the whole statement
+    fn write_interior_comments(&self, statement: &StatementNode) -> () {
+        let span = statement.span();
+        let blocks = block_spans(statement);
+        let interior = |comment: &Trivia| {
+            let within = |span: &Span| (span.start..span.end).contains(&comment.span.start);
+            within(&span) && !blocks.iter().any(within)
+        };
+
+        let (comments, remaining): (VecDeque<Trivia>, VecDeque<Trivia>) =
+            self.comments.take().into_iter().partition(interior);
+        self.comments.replace(remaining);
+        // syntax errors are written as they were, comments included
+        if let StatementNode::Error(_) = statement {
+            return;
+        }
+
+        for comment in comments {
+            self.write(&self.indentation());
+            self.write(self.file.text(comment.span));
+            self.write("\n");
+        }
+    }
+
+    /// Writes the comments starting after `position` on the same line and before `end`, which
+    /// stay at the end of the line
+    fn write_trailing_comments(&self, position: usize, end: usize) -> () {
+        let line = self.line(position);
+        while let Some(comment) = self.peek_comment() {
+            let start = comment.span.start as usize;
+            if start < position || start >= end || self.line(start) != line {
+                break;
+            }
+
+            self.write(" ");
+            self.write(self.file.text(comment.span));
+            self.comments.borrow_mut().pop_front();
+        }
+    }
+
+    /// Writes a blank line if the source had any between the previous line and `position`
+    fn write_blank_line(&self, position: usize, previous_end_line: Option<usize>) -> () {
+        let start_line = self.line(position);
+        if previous_end_line.is_some_and(|end_line| start_line > end_line + 1) {
+            self.write("\n");
+        }
+    }
+
+    fn peek_comment(&self) -> Option<Trivia> {
+        self.comments.borrow().front().cloned()
+    }
+
+    fn line(&self, position: usize) -> usize {
+        self.file.location(position).line
+    }
+
+    fn write_block(&self, block: &BlockStatement) -> () {
+        let end = block.span.end as usize;
+        let has_comments = self
+            .peek_comment()
+            .is_some_and(|comment| (comment.span.start as usize) < end);
+        if block.statements.is_empty() && !has_comments {
+            return self.write("{}");
+        }
+
+        // comments after the first statement on the line of `{` belong to the statements
+        let first = block.statements.first();
+        let first_start = first.map_or(end, |statement| statement.span().start as usize);
+        self.write("{");
+        self.write_trailing_comments(block.span.start as usize, first_start);
+        self.write("\n");
+        self.indent.set(self.indent.get() + 1);
+        self.write_statements(&block.statements, end);
+        self.indent.set(self.indent.get() - 1);
+        self.write(&self.indentation());
+        self.write("}");
+    }
+
+    /// Writes `prefix` followed by `expression`, which starts right after the prefix
+    fn write_expression(&self, prefix: &str, expression: &ExpressionNode) -> () {
+        let column = self.indentation().len() + width(prefix);
+        let expression = self.format_expression(expression, column);
+        self.write(prefix);
+        self.write(&expression);
+    }
+
+    fn format_expression(&self, expression: &ExpressionNode, column: usize) -> String {
+        let enclosing = self.column.replace(column);
+        let formatted = Expression::accept(expression, self);
+        self.column.set(enclosing);
+
+        formatted
+    }
+
+    /// Joins `items` into a parenthesized list, putting every item on its own line when the
+    /// list would not fit on the current line
+    ///
+    /// `formatted_item` is called with the column at which the item starts, and `rest` is the
+    /// text that follows the list on the same line
+    fn format_list<T>(
+        &self,
+        column: usize,
+        items: &[T],
+        rest: &str,
+        formatted_item: impl Fn(&T, usize) -> String,
+    ) -> String {
+        let flat: Vec<String> = items
+            .iter()
+            .map(|item| formatted_item(item, column))
+            .collect();
+        let flat = format!("({})", flat.join(", "));
+        if !flat.contains('\n') && column + width(&flat) + width(rest) <= MAX_WIDTH {
+            return flat;
+        }
+
+        let indentation = self.indentation();
+        let item_indentation = indentation.clone() + INDENT;
+        self.indent.set(self.indent.get() + 1);
+        let items: Vec<String> = items
+            .iter()
+            .map(|item| {
+                let item = formatted_item(item, item_indentation.len());
+                format!("{}{}", item_indentation, item)
+            })
+            .collect();
+        self.indent.set(self.indent.get() - 1);
+
+        format!("(\n{}\n{})", items.join(",\n"), indentation)
+    }
+
+    fn indentation(&self) -> String {
+        INDENT.repeat(self.indent.get())
+    }
+
+    fn write(&self, text: &str) -> () {
+        self.output.borrow_mut().push_str(text);
+    }
+}
+
+impl SyntaxNodeVisitor<String> for Formatter<'_> {}
+
+impl ExpressionVisitor<String> for Formatter<'_> {
+    fn visit_literal(&self, literal: &Literal) -> String {
+        self.file.text(literal.token.span).to_string()
+    }
+
+    fn visit_interpolation(&self, interpolation: &Interpolation) -> String {
+        self.file.text(interpolation.token.span).to_string()
+    }
+
+    fn visit_binary(&self, binary: &Binary) -> String {
+        let column = self.column.get();
+        let left = self.format_expression(binary.left.as_ref(), column);
+        let operator = self.file.text(binary.operator.span);
+        let right_column = column + width(&left) + width(operator) + 2;
+        let right = self.format_expression(binary.right.as_ref(), right_column);
+
+        format!("{} {} {}", left, operator, right)
+    }
+
+    fn visit_unary(&self, unary: &Unary) -> String {
+        let operator = self.file.text(unary.operator.span);
+        let column = self.column.get() + width(operator);
+        let operand = self.format_expression(unary.operand.as_ref(), column);
+
+        format!("{}{}", operator, operand)
+    }
+
+    fn visit_grouping(&self, grouping: &Grouping) -> String {
+        let column = self.column.get() + 1;
+        let expression = self.format_expression(grouping.expression.as_ref(), column);
+
+        format!("({})", expression)
+    }
+
+    fn visit_variable(&self, variable: &Variable) -> String {
+        variable.name.text.clone()
+    }
+
+    fn visit_assignment(&self, assignment: &Assignment) -> String {
+        let column = self.column.get() + width(&assignment.name.text) + 3;
+        let value = self.format_expression(assignment.value.as_ref(), column);
+
+        format!("{} = {}", assignment.name.text, value)
+    }
+
+    fn visit_call(&self, call: &Call) -> String {
+        let column = self.column.get();
+        let callee = self.format_expression(call.callee.as_ref(), column);
+        let arguments = self.format_list(
+            column + width(&callee),
+            &call.arguments,
+            "",
+            |argument, column| self.format_expression(argument, column),
+        );
+
+        format!("{}{}", callee, arguments)
+    }
+
+    /// Syntax errors are kept as they were written
+    fn visit_error_expression(&self, error: &ErrorExpression) -> String {
+        self.file.text(error.span).to_string()
+    }
+}
+
+impl SyntaxNodeVisitor<()> for Formatter<'_> {}
+
+impl StatementVisitor<()> for Formatter<'_> {
+    fn visit_let_statement(&self, let_statement: &LetStatement) -> () {
+        let type_annotation = match &let_statement.type_annotation {
+            Some(annotation) => format!(": {}", annotation),
+            None => String::new(),
+        };
+
+        let prefix = format!(
+            "{}let {}{} = ",
+            visibility(&let_statement.visibility),
+            let_statement.name.text,
+            type_annotation
+        );
+        self.write_expression(&prefix, &let_statement.initializer);
+    }
+
+    fn visit_expression_statement(&self, expression_statement: &ExpressionStatement) -> () {
+        self.write_expression("", &expression_statement.expression);
+    }
+
+    fn visit_block_statement(&self, block: &BlockStatement) -> () {
+        self.write_block(block);
+    }
+
+    fn visit_function_declaration(&self, function: &FunctionDeclaration) -> () {
+        let prefix = format!(
+            "{}fn {}",
+            visibility(&function.visibility),
+            function.name.text
+        );
+        let return_type = match &function.return_type {
+            Some(annotation) => format!(": {}", annotation),
+            None => String::new(),
+        };
+
+        let column = self.indentation().len() + width(&prefix);
+        let rest = format!("{} {{", return_type);
+        let parameters = self.format_list(column, &function.parameters, &rest, |parameter, _| {
+            format!("{}: {}", parameter.name.text, parameter.type_annotation)
+        });
+
+        self.write(&prefix);
+        self.write(&parameters);
+        self.write(&return_type);
+        self.write(" ");
+        self.write_block(&function.body);
+    }
+
+    fn visit_return_statement(&self, return_statement: &ReturnStatement) -> () {
+        match &return_statement.value {
+            Some(value) => self.write_expression("return ", value),
+            None => self.write("return"),
+        }
+    }
+
+    fn visit_if_statement(&self, if_statement: &IfStatement) -> () {
+        self.write_expression("if ", &if_statement.condition);
+        self.write(" ");
+        self.write_block(&if_statement.then_branch);
+
+        if let Some(else_branch) = &if_statement.else_branch {
+            self.write(" else ");
+            Statement::accept(else_branch.as_ref(), self);
+        }
+    }
+
+    fn visit_while_statement(&self, while_statement: &WhileStatement) -> () {
+        self.write_expression("while ", &while_statement.condition);
+        self.write(" ");
+        self.write_block(&while_statement.body);
+    }
+
+    fn visit_for_statement(&self, for_statement: &ForStatement) -> () {
+        let prefix = format!("for {} in ", for_statement.variable.text);
+        self.write_expression(&prefix, &for_statement.iterable);
+        self.write(" ");
+        self.write_block(&for_statement.body);
+    }
+
+    fn visit_break_statement(&self, _break_statement: &BreakStatement) -> () {
+        self.write("break");
+    }
+
+    fn visit_continue_statement(&self, _continue_statement: &ContinueStatement) -> () {
+        self.write("continue");
+    }
+
+    fn visit_import_statement(&self, import: &ImportStatement) -> () {
+        self.write(&format!("import {}", import.path));
+    }
+
+    fn visit_error_statement(&self, error: &ErrorStatement) -> () {
+        self.write(self.file.text(error.span));
+    }
+}
+
+fn visibility(visibility: &Option<Token>) -> &'static str {
+    match visibility {
+        Some(_) => "pub ",
+        None => "",
+    }
+}
+
+/// Returns the spans of the blocks directly inside `statement`, whose comments are written
+/// along with the statements of the block
+fn block_spans(statement: &StatementNode) -> Vec<Span> {
+    match statement {
+        StatementNode::Block(block) => vec![block.span],
+        StatementNode::Function(function) => vec![function.body.span],
+        StatementNode::If(if_statement) => {
+            let mut spans = vec![if_statement.then_branch.span];
+            if let Some(else_branch) = &if_statement.else_branch {
+                spans.extend(block_spans(else_branch));
+            }
+            spans
+        }
+        StatementNode::While(while_statement) => vec![while_statement.body.span],
+        StatementNode::For(for_statement) => vec![for_statement.body.span],
+        _ => Vec::new(),
+    }
+}
+
+/// Returns the number of columns taken by the first line of `text`
+fn width(text: &str) -> usize {
+    text.lines().next().unwrap_or("").chars().count()
+}
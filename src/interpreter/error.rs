@@ -0,0 +1,120 @@
+use std::fmt;
+
+use crate::{
+    diagnostics::{Diagnostic, DiagnosticCode},
+    lexer::syntax::Span,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuntimeErrorKind {
+    UndefinedVariable(String),
+    InvalidOperands {
+        operator: String,
+        left: &'static str,
+        right: &'static str,
+    },
+    InvalidOperand {
+        operator: String,
+        operand: &'static str,
+    },
+    DivisionByZero,
+    IntegerOverflow,
+    NotCallable(&'static str),
+    ArityMismatch {
+        expected: usize,
+        found: usize,
+    },
+    NotIterable(&'static str),
+    InvalidCondition(&'static str),
+    StackOverflow,
+    InvalidArgument(String),
+    NegativeExponent,
+}
+
+impl RuntimeErrorKind {
+    pub fn code(&self) -> DiagnosticCode {
+        match self {
+            RuntimeErrorKind::UndefinedVariable(_) => DiagnosticCode::UndefinedVariable,
+            RuntimeErrorKind::InvalidOperands { .. } | RuntimeErrorKind::InvalidOperand { .. } => {
+                DiagnosticCode::InvalidOperands
+            }
+            RuntimeErrorKind::DivisionByZero => DiagnosticCode::DivisionByZero,
+            RuntimeErrorKind::IntegerOverflow => DiagnosticCode::IntegerOverflow,
+            RuntimeErrorKind::NotCallable(_) => DiagnosticCode::NotCallable,
+            RuntimeErrorKind::ArityMismatch { .. } => DiagnosticCode::ArityMismatch,
+            RuntimeErrorKind::NotIterable(_) => DiagnosticCode::NotIterable,
+            RuntimeErrorKind::InvalidCondition(_) => DiagnosticCode::InvalidCondition,
+            RuntimeErrorKind::StackOverflow => DiagnosticCode::StackOverflow,
+            RuntimeErrorKind::InvalidArgument(_) => DiagnosticCode::InvalidArgument,
+            RuntimeErrorKind::NegativeExponent => DiagnosticCode::NegativeExponent,
+        }
+    }
+}
+
+impl fmt::Display for RuntimeErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuntimeErrorKind::UndefinedVariable(name) => write!(f, "undefined variable '{}'", name),
+            RuntimeErrorKind::InvalidOperands {
+                operator,
+                left,
+                right,
+            } => write!(
+                f,
+                "cannot apply '{}' to operands of type {} and {}",
+                operator, left, right
+            ),
+            RuntimeErrorKind::InvalidOperand { operator, operand } => {
+                write!(
+                    f,
+                    "cannot apply '{}' to an operand of type {}",
+                    operator, operand
+                )
+            }
+            RuntimeErrorKind::DivisionByZero => write!(f, "division by zero"),
+            RuntimeErrorKind::IntegerOverflow => write!(f, "integer overflow"),
+            RuntimeErrorKind::NotCallable(type_name) => {
+                write!(f, "a value of type {} is not callable", type_name)
+            }
+            RuntimeErrorKind::ArityMismatch { expected, found } => write!(
+                f,
+                "expected {} argument(s), but {} were given",
+                expected, found
+            ),
+            RuntimeErrorKind::NotIterable(type_name) => {
+                write!(f, "a value of type {} is not iterable", type_name)
+            }
+            RuntimeErrorKind::InvalidCondition(type_name) => {
+                write!(f, "expected a condition of type bool, got {}", type_name)
+            }
+            RuntimeErrorKind::StackOverflow => write!(f, "stack overflow"),
+            RuntimeErrorKind::InvalidArgument(message) => write!(f, "{}", message),
+            RuntimeErrorKind::NegativeExponent => {
+                write!(f, "cannot raise an int to a negative power")
+            }
+        }
+    }
+}
+
+/// An error raised while running a program, located at the node that failed to evaluate
+#[derive(Debug, Clone)]
+pub struct RuntimeError {
+    pub kind: RuntimeErrorKind,
+    pub span: Span,
+}
+
+impl RuntimeError {
+    pub fn new(kind: RuntimeErrorKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic::error(self.kind.code(), self.kind.to_string(), self.span)
+    }
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({})", self.kind, self.span)
+    }
+}
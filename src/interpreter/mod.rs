@@ -0,0 +1,434 @@
+pub mod environment;
+pub mod error;
+pub mod natives;
+pub mod operators;
+pub mod value;
+
+use std::{
+    cell::{Cell, RefCell},
+    io::{self, Write},
+    rc::Rc,
+};
+
+use crate::{
+    lexer::syntax::{LiteralValue, Span, SyntaxKind, Token},
+    parser::ast::{
+        base::{Expression, Statement},
+        expressions::{
+            Assignment, Binary, Call, ErrorExpression, Grouping, Interpolation, InterpolationPart,
+            Literal, Unary, Variable,
+        },
+        statements::{
+            BlockStatement, BreakStatement, ContinueStatement, ErrorStatement, ExpressionStatement,
+            ForStatement, FunctionDeclaration, IfStatement, ImportStatement, LetStatement, Program,
+            ReturnStatement, StatementNode, WhileStatement,
+        },
+        visitor::{ExpressionVisitor, StatementVisitor, SyntaxNodeVisitor},
+    },
+};
+use environment::Environment;
+use error::{RuntimeError, RuntimeErrorKind};
+use value::{Function, Value};
+
+/// Calls nested deeper than this are reported as a stack overflow instead of crashing the host
+pub(crate) const MAX_CALL_DEPTH: usize = 200;
+
+/// The reason statement execution stopped before reaching the end of a block
+pub enum Unwind {
+    Error(RuntimeError),
+    Return(Value),
+    Break,
+    Continue,
+}
+
+impl From<RuntimeError> for Unwind {
+    fn from(error: RuntimeError) -> Self {
+        Unwind::Error(error)
+    }
+}
+
+type EvaluationResult = Result<Value, RuntimeError>;
+type ExecutionResult = Result<(), Unwind>;
+
+/// Runs programs by walking their syntax tree
+///
+/// The interpreter keeps its global environment between calls to `interpret`, so bindings made
+/// by one program remain visible to the next
+pub struct Interpreter {
+    environment: RefCell<Rc<RefCell<Environment>>>,
+    output: RefCell<Box<dyn Write>>,
+    call_depth: Cell<usize>,
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Interpreter {
+    pub fn new() -> Self {
+        Self::with_output(Box::new(io::stdout()))
+    }
+
+    /// Creates an interpreter that writes everything printed by the program into `output`
+    pub fn with_output(output: Box<dyn Write>) -> Self {
+        let mut globals = Environment::new();
+        for native in natives::get_natives() {
+            globals.define(native.name, Value::NativeFunction(native));
+        }
+
+        Self {
+            environment: RefCell::new(Rc::new(RefCell::new(globals))),
+            output: RefCell::new(output),
+            call_depth: Cell::new(0),
+        }
+    }
+
+    pub fn interpret(&self, program: &Program) -> Result<(), RuntimeError> {
+        for statement in program.statements.iter() {
+            match self.execute(statement) {
+                Ok(()) => (),
+                Err(Unwind::Error(error)) => return Err(error),
+                Err(_) => unreachable!("the parser rejects jumps outside of loops and functions"),
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn evaluate(&self, expression: &impl Expression) -> EvaluationResult {
+        Expression::accept(expression, self)
+    }
+
+    fn execute(&self, statement: &StatementNode) -> ExecutionResult {
+        Statement::accept(statement, self)
+    }
+
+    /// Executes `statements` in `environment`, restoring the current environment afterwards even
+    /// if execution unwinds
+    fn execute_block(
+        &self,
+        statements: &[StatementNode],
+        environment: Environment,
+    ) -> ExecutionResult {
+        let previous = self.environment.replace(Rc::new(RefCell::new(environment)));
+
+        let result = statements
+            .iter()
+            .try_for_each(|statement| self.execute(statement));
+
+        self.environment.replace(previous);
+        result
+    }
+
+    fn new_scope(&self) -> Environment {
+        Environment::with_enclosing(self.environment.borrow().clone())
+    }
+
+    fn call(&self, callee: &Value, arguments: Vec<Value>, span: &Span) -> EvaluationResult {
+        let error = |kind| RuntimeError::new(kind, *span);
+
+        match callee {
+            Value::Function(function) => {
+                let parameters = &function.declaration.parameters;
+                if parameters.len() != arguments.len() {
+                    return Err(error(RuntimeErrorKind::ArityMismatch {
+                        expected: parameters.len(),
+                        found: arguments.len(),
+                    }));
+                }
+
+                if self.call_depth.get() >= MAX_CALL_DEPTH {
+                    return Err(error(RuntimeErrorKind::StackOverflow));
+                }
+
+                let mut environment = Environment::with_enclosing(function.closure.clone());
+                for (parameter, argument) in parameters.iter().zip(arguments) {
+                    environment.define(&parameter.name.text, argument);
+                }
+
+                self.call_depth.set(self.call_depth.get() + 1);
+                let result = self.execute_block(&function.declaration.body.statements, environment);
+                self.call_depth.set(self.call_depth.get() - 1);
+
+                match result {
+                    Ok(()) => Ok(Value::Null),
+                    Err(Unwind::Return(value)) => Ok(value),
+                    Err(Unwind::Error(error)) => Err(error),
+                    Err(_) => unreachable!("the parser rejects jumps outside of loops"),
+                }
+            }
+            Value::NativeFunction(native) => {
+                if let Some(arity) = native.arity {
+                    if arity != arguments.len() {
+                        return Err(error(RuntimeErrorKind::ArityMismatch {
+                            expected: arity,
+                            found: arguments.len(),
+                        }));
+                    }
+                }
+
+                let mut output = self.output.borrow_mut();
+                (native.function)(output.as_mut(), &arguments).map_err(error)
+            }
+            _ => Err(error(RuntimeErrorKind::NotCallable(callee.type_name()))),
+        }
+    }
+
+    /// Checks that an operand of `&&` or `||` is a boolean
+    fn expect_bool_operand(
+        &self,
+        operator: &Token,
+        operand: &Value,
+        span: Span,
+    ) -> Result<bool, RuntimeError> {
+        match operand {
+            Value::Bool(value) => Ok(*value),
+            _ => Err(RuntimeError::new(
+                RuntimeErrorKind::InvalidOperand {
+                    operator: operators::lexeme(operator.kind),
+                    operand: operand.type_name(),
+                },
+                span,
+            )),
+        }
+    }
+
+    fn evaluate_condition(&self, condition: &impl Expression, span: Span) -> Result<bool, Unwind> {
+        match self.evaluate(condition)? {
+            Value::Bool(value) => Ok(value),
+            value => Err(Unwind::Error(RuntimeError::new(
+                RuntimeErrorKind::InvalidCondition(value.type_name()),
+                span,
+            ))),
+        }
+    }
+}
+
+impl SyntaxNodeVisitor<EvaluationResult> for Interpreter {}
+
+impl ExpressionVisitor<EvaluationResult> for Interpreter {
+    fn visit_literal(&self, literal: &Literal) -> EvaluationResult {
+        let value = match &literal.token.value {
+            Some(LiteralValue::Int(value)) => Value::Int(*value),
+            Some(LiteralValue::Float(value)) => Value::Float(*value),
+            Some(LiteralValue::Str(value)) => Value::String(value.clone()),
+            Some(LiteralValue::Bool(value)) => Value::Bool(*value),
+            Some(LiteralValue::Interpolated(_)) => {
+                unreachable!("interpolated strings are parsed into interpolations")
+            }
+            None => Value::Null,
+        };
+
+        Ok(value)
+    }
+
+    fn visit_interpolation(&self, interpolation: &Interpolation) -> EvaluationResult {
+        let mut text = String::new();
+        for part in interpolation.parts.iter() {
+            match part {
+                InterpolationPart::Text(part) => text.push_str(part),
+                InterpolationPart::Expression(expression) => {
+                    text.push_str(&self.evaluate(expression)?.to_string())
+                }
+            }
+        }
+
+        Ok(Value::String(text))
+    }
+
+    fn visit_binary(&self, binary: &Binary) -> EvaluationResult {
+        let left = self.evaluate(binary.left.as_ref())?;
+        let operator = &binary.operator;
+
+        // short-circuit before evaluating the right operand
+        if matches!(
+            operator.kind,
+            SyntaxKind::AmpersandAmpersand | SyntaxKind::PipePipe
+        ) {
+            let left_value = self.expect_bool_operand(operator, &left, binary.left.span())?;
+            if left_value == (operator.kind == SyntaxKind::PipePipe) {
+                return Ok(Value::Bool(left_value));
+            }
+
+            let right = self.evaluate(binary.right.as_ref())?;
+            let right_value = self.expect_bool_operand(operator, &right, binary.right.span())?;
+            return Ok(Value::Bool(right_value));
+        }
+
+        let right = self.evaluate(binary.right.as_ref())?;
+        operators::binary(operator.kind, &left, &right)
+            .map_err(|kind| RuntimeError::new(kind, binary.left.span().to(&binary.right.span())))
+    }
+
+    fn visit_unary(&self, unary: &Unary) -> EvaluationResult {
+        let operand = self.evaluate(unary.operand.as_ref())?;
+        operators::unary(unary.operator.kind, &operand)
+            .map_err(|kind| RuntimeError::new(kind, unary.operator.span.to(&unary.operand.span())))
+    }
+
+    fn visit_grouping(&self, grouping: &Grouping) -> EvaluationResult {
+        self.evaluate(grouping.expression.as_ref())
+    }
+
+    fn visit_variable(&self, variable: &Variable) -> EvaluationResult {
+        let name = &variable.name;
+        self.environment
+            .borrow()
+            .borrow()
+            .get(&name.text)
+            .ok_or_else(|| {
+                RuntimeError::new(
+                    RuntimeErrorKind::UndefinedVariable(name.display_name().to_string()),
+                    name.span,
+                )
+            })
+    }
+
+    fn visit_assignment(&self, assignment: &Assignment) -> EvaluationResult {
+        let value = self.evaluate(assignment.value.as_ref())?;
+        let name = &assignment.name;
+
+        let assigned = self
+            .environment
+            .borrow()
+            .borrow_mut()
+            .assign(&name.text, value.clone());
+        if !assigned {
+            return Err(RuntimeError::new(
+                RuntimeErrorKind::UndefinedVariable(name.display_name().to_string()),
+                name.span,
+            ));
+        }
+
+        Ok(value)
+    }
+
+    fn visit_call(&self, call: &Call) -> EvaluationResult {
+        let callee = self.evaluate(call.callee.as_ref())?;
+
+        let mut arguments = Vec::with_capacity(call.arguments.len());
+        for argument in call.arguments.iter() {
+            arguments.push(self.evaluate(argument)?);
+        }
+
+        self.call(&callee, arguments, &call.span)
+    }
+
+    fn visit_error_expression(&self, _error: &ErrorExpression) -> EvaluationResult {
+        unreachable!("programs with syntax errors are never run")
+    }
+}
+
+impl SyntaxNodeVisitor<ExecutionResult> for Interpreter {}
+
+impl StatementVisitor<ExecutionResult> for Interpreter {
+    fn visit_let_statement(&self, let_statement: &LetStatement) -> ExecutionResult {
+        let value = self.evaluate(&let_statement.initializer)?;
+        self.environment
+            .borrow()
+            .borrow_mut()
+            .define(&let_statement.name.text, value);
+
+        Ok(())
+    }
+
+    fn visit_expression_statement(
+        &self,
+        expression_statement: &ExpressionStatement,
+    ) -> ExecutionResult {
+        self.evaluate(&expression_statement.expression)?;
+        Ok(())
+    }
+
+    fn visit_block_statement(&self, block: &BlockStatement) -> ExecutionResult {
+        self.execute_block(&block.statements, self.new_scope())
+    }
+
+    fn visit_function_declaration(&self, function: &FunctionDeclaration) -> ExecutionResult {
+        let environment = self.environment.borrow().clone();
+        let value = Value::Function(Rc::new(Function {
+            declaration: Rc::new(function.clone()),
+            closure: environment.clone(),
+        }));
+
+        environment.borrow_mut().define(&function.name.text, value);
+        Ok(())
+    }
+
+    fn visit_return_statement(&self, return_statement: &ReturnStatement) -> ExecutionResult {
+        let value = match &return_statement.value {
+            Some(value) => self.evaluate(value)?,
+            None => Value::Null,
+        };
+
+        Err(Unwind::Return(value))
+    }
+
+    fn visit_if_statement(&self, if_statement: &IfStatement) -> ExecutionResult {
+        let condition = &if_statement.condition;
+        if self.evaluate_condition(condition, condition.span())? {
+            return self.visit_block_statement(&if_statement.then_branch);
+        }
+
+        match &if_statement.else_branch {
+            Some(else_branch) => self.execute(else_branch),
+            None => Ok(()),
+        }
+    }
+
+    fn visit_while_statement(&self, while_statement: &WhileStatement) -> ExecutionResult {
+        let condition = &while_statement.condition;
+        while self.evaluate_condition(condition, condition.span())? {
+            match self.visit_block_statement(&while_statement.body) {
+                Ok(()) | Err(Unwind::Continue) => (),
+                Err(Unwind::Break) => break,
+                Err(unwind) => return Err(unwind),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn visit_for_statement(&self, for_statement: &ForStatement) -> ExecutionResult {
+        let iterable = self.evaluate(&for_statement.iterable)?;
+        let span = for_statement.iterable.span();
+
+        let mut index = 0;
+        while let Some(item) = iterable
+            .item(index)
+            .map_err(|kind| RuntimeError::new(kind, span))?
+        {
+            index += 1;
+            let mut environment = self.new_scope();
+            environment.define(&for_statement.variable.text, item);
+
+            match self.execute_block(&for_statement.body.statements, environment) {
+                Ok(()) | Err(Unwind::Continue) => (),
+                Err(Unwind::Break) => break,
+                Err(unwind) => return Err(unwind),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn visit_break_statement(&self, _break_statement: &BreakStatement) -> ExecutionResult {
+        Err(Unwind::Break)
+    }
+
+    fn visit_continue_statement(&self, _continue_statement: &ContinueStatement) -> ExecutionResult {
+        Err(Unwind::Continue)
+    }
+
+    /// Imported modules are linked into the program ahead of the importing file, see
+    /// `modules::ModuleGraph::link`
+    fn visit_import_statement(&self, _import: &ImportStatement) -> ExecutionResult {
+        Ok(())
+    }
+
+    fn visit_error_statement(&self, _error: &ErrorStatement) -> ExecutionResult {
+        unreachable!("programs with syntax errors are never run")
+    }
+}
@@ -0,0 +1,64 @@
+use std::{io::Write, rc::Rc};
+
+use super::{
+    error::RuntimeErrorKind,
+    value::{NativeFunction, NativeFunctionPointer, Value},
+};
+
+/// Returns every function that is available to ion programs without being declared
+pub fn get_natives() -> Vec<Rc<NativeFunction>> {
+    vec![
+        native("print", None, print),
+        native("range", Some(2), range),
+        native("len", Some(1), len),
+        native("str", Some(1), str),
+    ]
+}
+
+fn native(
+    name: &'static str,
+    arity: Option<usize>,
+    function: NativeFunctionPointer,
+) -> Rc<NativeFunction> {
+    Rc::new(NativeFunction {
+        name,
+        arity,
+        function,
+    })
+}
+
+/// Writes every argument separated by spaces, followed by a newline
+fn print(output: &mut dyn Write, arguments: &[Value]) -> Result<Value, RuntimeErrorKind> {
+    let line: Vec<String> = arguments.iter().map(|value| value.to_string()).collect();
+    writeln!(output, "{}", line.join(" "))
+        .map_err(|error| RuntimeErrorKind::InvalidArgument(error.to_string()))?;
+
+    Ok(Value::Null)
+}
+
+/// Returns the half-open range of integers `[start, end)`
+fn range(_output: &mut dyn Write, arguments: &[Value]) -> Result<Value, RuntimeErrorKind> {
+    match (&arguments[0], &arguments[1]) {
+        (Value::Int(start), Value::Int(end)) => Ok(Value::Range(*start, *end)),
+        (start, end) => Err(RuntimeErrorKind::InvalidArgument(format!(
+            "range expects two ints, got {} and {}",
+            start.type_name(),
+            end.type_name()
+        ))),
+    }
+}
+
+fn len(_output: &mut dyn Write, arguments: &[Value]) -> Result<Value, RuntimeErrorKind> {
+    match &arguments[0] {
+        Value::String(string) => Ok(Value::Int(string.chars().count() as i64)),
+        Value::Range(start, end) => Ok(Value::Int(end.saturating_sub(*start).max(0))),
+        value => Err(RuntimeErrorKind::InvalidArgument(format!(
+            "len expects a string or range, got {}",
+            value.type_name()
+        ))),
+    }
+}
+
+fn str(_output: &mut dyn Write, arguments: &[Value]) -> Result<Value, RuntimeErrorKind> {
+    Ok(Value::String(arguments[0].to_string()))
+}
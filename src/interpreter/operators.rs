@@ -0,0 +1,162 @@
+use super::{error::RuntimeErrorKind, value::Value};
+use crate::lexer::{syntax::SyntaxKind, syntax_facts};
+
+/// Applies a prefix operator to an already evaluated operand
+pub fn unary(operator: SyntaxKind, operand: &Value) -> Result<Value, RuntimeErrorKind> {
+    let result = match (operator, operand) {
+        (SyntaxKind::Minus, Value::Int(value)) => value.checked_neg().map(Value::Int),
+        (SyntaxKind::Minus, Value::Float(value)) => Some(Value::Float(-value)),
+        (SyntaxKind::Bang, Value::Bool(value)) => Some(Value::Bool(!value)),
+        (SyntaxKind::Tilde, Value::Int(value)) => Some(Value::Int(!value)),
+        _ => {
+            return Err(RuntimeErrorKind::InvalidOperand {
+                operator: lexeme(operator),
+                operand: operand.type_name(),
+            })
+        }
+    };
+
+    result.ok_or(RuntimeErrorKind::IntegerOverflow)
+}
+
+/// Applies an infix operator to already evaluated operands
+///
+/// `&&` and `||` are short-circuiting and thus evaluated by the caller, they are only accepted
+/// here once both operands are known to be booleans
+pub fn binary(
+    operator: SyntaxKind,
+    left: &Value,
+    right: &Value,
+) -> Result<Value, RuntimeErrorKind> {
+    use Value::{Bool, Float, Int};
+
+    let invalid_operands = || RuntimeErrorKind::InvalidOperands {
+        operator: lexeme(operator),
+        left: left.type_name(),
+        right: right.type_name(),
+    };
+
+    match operator {
+        SyntaxKind::EqualsEquals => return Ok(Bool(left == right)),
+        SyntaxKind::BangEquals => return Ok(Bool(left != right)),
+        SyntaxKind::Plus => {
+            if let (Value::String(left), Value::String(right)) = (left, right) {
+                return Ok(Value::String(format!("{}{}", left, right)));
+            }
+        }
+        SyntaxKind::LT | SyntaxKind::LTE | SyntaxKind::GT | SyntaxKind::GTE => {
+            if let (Value::String(left), Value::String(right)) = (left, right) {
+                return Ok(Bool(compare(operator, left, right)));
+            }
+        }
+        SyntaxKind::Slash | SyntaxKind::TildeSlash | SyntaxKind::Percent
+            if is_number(left) && is_zero(right) =>
+        {
+            return Err(RuntimeErrorKind::DivisionByZero);
+        }
+        // the result would not be an int, as the type checker assumes it is
+        SyntaxKind::Carat if matches!((left, right), (Int(_), Int(right)) if *right < 0) => {
+            return Err(RuntimeErrorKind::NegativeExponent);
+        }
+        _ => (),
+    }
+
+    let result = match (operator, left, right) {
+        (SyntaxKind::AmpersandAmpersand | SyntaxKind::Ampersand, Bool(left), Bool(right)) => {
+            Some(Bool(*left && *right))
+        }
+        (SyntaxKind::PipePipe | SyntaxKind::Pipe, Bool(left), Bool(right)) => {
+            Some(Bool(*left || *right))
+        }
+        (SyntaxKind::Tilde, Bool(left), Bool(right)) => Some(Bool(left != right)),
+
+        (SyntaxKind::Plus, Int(left), Int(right)) => left.checked_add(*right).map(Int),
+        (SyntaxKind::Minus, Int(left), Int(right)) => left.checked_sub(*right).map(Int),
+        (SyntaxKind::Star, Int(left), Int(right)) => left.checked_mul(*right).map(Int),
+        (SyntaxKind::TildeSlash, Int(left), Int(right)) => floor_divide(*left, *right).map(Int),
+        (SyntaxKind::Percent, Int(left), Int(right)) => modulo(*left, *right).map(Int),
+        (SyntaxKind::Carat, Int(left), Int(right)) => u32::try_from(*right)
+            .ok()
+            .and_then(|exponent| left.checked_pow(exponent))
+            .map(Int),
+        (SyntaxKind::Ampersand, Int(left), Int(right)) => Some(Int(left & right)),
+        (SyntaxKind::Pipe, Int(left), Int(right)) => Some(Int(left | right)),
+        (SyntaxKind::Tilde, Int(left), Int(right)) => Some(Int(left ^ right)),
+
+        (_, Int(_) | Float(_), Int(_) | Float(_)) => {
+            let (left, right) = (to_float(left), to_float(right));
+            match operator {
+                SyntaxKind::Plus => Some(Float(left + right)),
+                SyntaxKind::Minus => Some(Float(left - right)),
+                SyntaxKind::Star => Some(Float(left * right)),
+                SyntaxKind::Slash => Some(Float(left / right)),
+                SyntaxKind::TildeSlash => Some(Float((left / right).floor())),
+                SyntaxKind::Percent => Some(Float(left - right * (left / right).floor())),
+                SyntaxKind::Carat => Some(Float(left.powf(right))),
+                SyntaxKind::LT | SyntaxKind::LTE | SyntaxKind::GT | SyntaxKind::GTE => {
+                    Some(Bool(compare(operator, &left, &right)))
+                }
+                _ => return Err(invalid_operands()),
+            }
+        }
+
+        _ => return Err(invalid_operands()),
+    };
+
+    result.ok_or(RuntimeErrorKind::IntegerOverflow)
+}
+
+pub(super) fn lexeme(operator: SyntaxKind) -> String {
+    syntax_facts::get_operator_lexeme(&operator)
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn compare<T: PartialOrd>(kind: SyntaxKind, left: &T, right: &T) -> bool {
+    match kind {
+        SyntaxKind::LT => left < right,
+        SyntaxKind::LTE => left <= right,
+        SyntaxKind::GT => left > right,
+        _ => left >= right,
+    }
+}
+
+/// Divides rounding towards negative infinity, so `-7 ~/ 2` is `-4`
+fn floor_divide(left: i64, right: i64) -> Option<i64> {
+    let quotient = left.checked_div(right)?;
+    if left % right != 0 && (left < 0) != (right < 0) {
+        return Some(quotient - 1);
+    }
+
+    Some(quotient)
+}
+
+/// Returns the remainder with the sign of the divisor, matching `floor_divide`
+fn modulo(left: i64, right: i64) -> Option<i64> {
+    let remainder = left.checked_rem(right)?;
+    if remainder != 0 && (remainder < 0) != (right < 0) {
+        return Some(remainder + right);
+    }
+
+    Some(remainder)
+}
+
+fn is_number(value: &Value) -> bool {
+    matches!(value, Value::Int(_) | Value::Float(_))
+}
+
+fn is_zero(value: &Value) -> bool {
+    match value {
+        Value::Int(value) => *value == 0,
+        Value::Float(value) => *value == 0.0,
+        _ => false,
+    }
+}
+
+fn to_float(value: &Value) -> f64 {
+    match value {
+        Value::Int(value) => *value as f64,
+        Value::Float(value) => *value,
+        _ => unreachable!("only numbers can be converted to floats"),
+    }
+}
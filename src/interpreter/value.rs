@@ -0,0 +1,119 @@
+use std::{cell::RefCell, fmt, io::Write, rc::Rc};
+
+use super::{environment::Environment, error::RuntimeErrorKind};
+use crate::{parser::ast::statements::FunctionDeclaration, vm::Closure};
+
+#[derive(Clone)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+    String(String),
+    Bool(bool),
+    Null,
+    Range(i64, i64),
+    Function(Rc<Function>),
+    NativeFunction(Rc<NativeFunction>),
+    /// A function compiled to bytecode, only created by the `vm`
+    Closure(Rc<Closure>),
+}
+
+impl Value {
+    /// Returns the name of this value's type, as used in error messages and type annotations
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Int(_) => "int",
+            Value::Float(_) => "float",
+            Value::String(_) => "string",
+            Value::Bool(_) => "bool",
+            Value::Null => "null",
+            Value::Range(_, _) => "range",
+            Value::Function(_) | Value::NativeFunction(_) | Value::Closure(_) => "function",
+        }
+    }
+
+    /// Returns the item at `index` when iterating over this value with `for`, or None past the
+    /// last one
+    ///
+    /// Items are computed one at a time, so ranges are never materialised
+    pub fn item(&self, index: i64) -> Result<Option<Value>, RuntimeErrorKind> {
+        match self {
+            Value::Range(start, end) => {
+                let item = start + index;
+                Ok((item < *end).then_some(Value::Int(item)))
+            }
+            Value::String(string) => Ok(string
+                .chars()
+                .nth(index as usize)
+                .map(|char| Value::String(char.to_string()))),
+            value => Err(RuntimeErrorKind::NotIterable(value.type_name())),
+        }
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Int(left), Value::Int(right)) => left == right,
+            (Value::Float(left), Value::Float(right)) => left == right,
+            (Value::Int(left), Value::Float(right)) | (Value::Float(right), Value::Int(left)) => {
+                (*left as f64) == *right
+            }
+            (Value::String(left), Value::String(right)) => left == right,
+            (Value::Bool(left), Value::Bool(right)) => left == right,
+            (Value::Null, Value::Null) => true,
+            (Value::Range(left_start, left_end), Value::Range(right_start, right_end)) => {
+                left_start == right_start && left_end == right_end
+            }
+            (Value::Function(left), Value::Function(right)) => Rc::ptr_eq(left, right),
+            (Value::NativeFunction(left), Value::NativeFunction(right)) => Rc::ptr_eq(left, right),
+            (Value::Closure(left), Value::Closure(right)) => Rc::ptr_eq(left, right),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Int(value) => write!(f, "{}", value),
+            Value::Float(value) if value.is_finite() && value.fract() == 0.0 => {
+                write!(f, "{:.1}", value)
+            }
+            Value::Float(value) => write!(f, "{}", value),
+            Value::String(value) => write!(f, "{}", value),
+            Value::Bool(value) => write!(f, "{}", value),
+            Value::Null => write!(f, "null"),
+            Value::Range(start, end) => write!(f, "range({}, {})", start, end),
+            Value::Function(function) => {
+                write!(f, "<fn {}>", function.declaration.name.display_name())
+            }
+            Value::NativeFunction(function) => write!(f, "<native fn {}>", function.name),
+            Value::Closure(closure) => write!(f, "<fn {}>", closure.function.name),
+        }
+    }
+}
+
+impl fmt::Debug for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::String(value) => write!(f, "{:?}", value),
+            _ => write!(f, "{}", self),
+        }
+    }
+}
+
+/// A user defined function, closing over the environment it was declared in
+pub struct Function {
+    pub declaration: Rc<FunctionDeclaration>,
+    pub closure: Rc<RefCell<Environment>>,
+}
+
+pub type NativeFunctionPointer = fn(&mut dyn Write, &[Value]) -> Result<Value, RuntimeErrorKind>;
+
+/// A function implemented in Rust, see `natives::get_natives`
+pub struct NativeFunction {
+    pub name: &'static str,
+    /// The exact number of arguments expected, or None if the function is variadic
+    pub arity: Option<usize>,
+    pub function: NativeFunctionPointer,
+}
@@ -1,74 +1,177 @@
 pub mod syntax;
 pub mod syntax_facts;
 
-use syntax::{CloneableAny, Location, Span, SyntaxKind, Token, TokenStream};
+use std::ops::RangeInclusive;
+
+use syntax::{LiteralValue, Span, StringPart, SyntaxKind, Token, TokenStream, Trivia, TriviaKind};
+
+use crate::{
+    diagnostics::{Diagnostic, DiagnosticCode},
+    source::{FileId, SourceFile},
+};
+
+/// The suffixes giving an integer literal the range of a sized integer type; values are always
+/// stored as 64-bit signed integers, so `u64` literals cannot exceed `i64::MAX` either
+const INTEGER_SUFFIXES: [&str; 8] = ["i8", "i16", "i32", "i64", "u8", "u16", "u32", "u64"];
+/// The suffixes making a literal a float, rounded to single precision for `f32`
+const FLOAT_SUFFIXES: [&str; 2] = ["f32", "f64"];
+
+/// Returns the values an integer literal with the given suffix can take
+fn integer_range(suffix: Option<&str>) -> RangeInclusive<i64> {
+    match suffix {
+        Some("i8") => i8::MIN as i64..=i8::MAX as i64,
+        Some("i16") => i16::MIN as i64..=i16::MAX as i64,
+        Some("i32") => i32::MIN as i64..=i32::MAX as i64,
+        Some("u8") => 0..=u8::MAX as i64,
+        Some("u16") => 0..=u16::MAX as i64,
+        Some("u32") => 0..=u32::MAX as i64,
+        Some("u64") => 0..=i64::MAX,
+        _ => i64::MIN..=i64::MAX,
+    }
+}
+
+/// Returns the diagnostic for the literal of a signed type's minimum at `span` when no `-` comes
+/// before it, e.g. `128i8` on its own, `value` being the minimum the lexer read it as
+pub fn unnegated_minimum(value: i64, span: Span) -> Diagnostic {
+    let ty = ["i8", "i16", "i32"]
+        .into_iter()
+        .find(|ty| *integer_range(Some(ty)).start() == value)
+        .unwrap_or("i64");
 
-use crate::source::SourceFile;
+    integer_out_of_range(ty, span)
+}
+
+/// Returns the diagnostic for an integer literal at `span` that does not fit the type `ty`
+fn integer_out_of_range(ty: &str, span: Span) -> Diagnostic {
+    let range = integer_range(Some(ty));
+    Diagnostic::error(
+        DiagnosticCode::IntegerOutOfRange,
+        format!("integer literal is too large for '{}'", ty),
+        span,
+    )
+    .with_note(format!(
+        "'{}' literals range from {} to {}",
+        ty,
+        range.start(),
+        range.end()
+    ))
+}
 
-pub struct Lexer {
-    source: Vec<char>,
-    tokens: Vec<Token>,
-    lexeme_start_location: Location,
+/// Lexes a file one token at a time, reading its source in place
+///
+/// Positions are byte offsets into the source, and lexemes are slices of it. Tokens and trivia
+/// only hold their span, so no lexeme is ever copied; their text is read back from the source
+/// with `SourceFile::text`
+pub struct Lexer<'a> {
+    source: &'a str,
+    diagnostics: Vec<Diagnostic>,
+    /// The trivia after the last token, read once the end of the source is reached
+    end_trivia: Vec<Trivia>,
+    file: FileId,
+    lexeme_start: usize,
     position: usize,
-    column: usize,
-    line: usize,
 }
 
-impl Lexer {
-    pub fn new(file: SourceFile) -> Lexer {
+impl<'a> Lexer<'a> {
+    pub fn new(file: &'a SourceFile) -> Lexer<'a> {
         Self {
-            source: file.source.chars().collect(),
-            tokens: Vec::new(),
-
-            lexeme_start_location: Location {
-                file_name: file.relative_path,
-                position: 0,
-                column: 0,
-                line: 1,
-            },
+            source: file.source(),
+            diagnostics: Vec::new(),
+            end_trivia: Vec::new(),
+            file: file.id(),
+            lexeme_start: 0,
             position: 0,
-            column: 0,
-            line: 1,
         }
     }
 
-    /// Tokenizes the entire source string, and returns a reference to the tokens vector
+    /// Tokenizes the entire source string, and returns the tokens along with every lexical
+    /// diagnostic that was reported along the way
+    ///
+    /// Lexical errors never abort tokenization; the offending lexeme is emitted as a
+    /// `SyntaxKind::Bad` token so later stages can keep going
+    ///
+    /// Whitespace and comments are kept as trivia: the trivia following a token on the same line
+    /// is its trailing trivia, and everything else up to the next token is that token's leading
+    /// trivia
     pub fn tokenize(&mut self) -> TokenStream {
-        while !self.is_finished() {
-            self.lex();
+        self.tokenize_from(0, |_| false)
+    }
+
+    /// Tokenizes the source from the byte offset `start`, until the end of the source or
+    /// until `stop` returns true for the offset right after a token and its trailing trivia
+    ///
+    /// `start` must be where the leading trivia of a token starts, i.e. right after the trailing
+    /// trivia of the previous one, since the lexer carries no state from one token to the next.
+    /// The end trivia of the returned stream is only set if the end of the source was reached
+    pub fn tokenize_from(
+        &mut self,
+        start: usize,
+        mut stop: impl FnMut(usize) -> bool,
+    ) -> TokenStream {
+        self.position = start;
+        self.end_trivia.clear();
+
+        let mut tokens = Vec::new();
+        while let Some(token) = self.next() {
+            tokens.push(token);
+            if stop(self.position) {
+                break;
+            }
         }
 
-        TokenStream::new(std::mem::take(&mut self.tokens))
+        let mut tokens =
+            TokenStream::with_diagnostics(tokens, std::mem::take(&mut self.diagnostics));
+        tokens.end_trivia = std::mem::take(&mut self.end_trivia);
+
+        tokens
     }
 
-    /// Lexes the current character, and pushes a token into `self.tokens` accordingly
-    fn lex(&mut self) -> () {
-        let char = self.current_char();
-        self.lexeme_start_location = self.current_location();
+    /// The diagnostics reported for the tokens lexed so far
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
 
+    /// The trivia after the last token, which is only known once the lexer returned None
+    pub fn end_trivia(&self) -> &[Trivia] {
+        &self.end_trivia
+    }
+
+    /// Lexes a token along with its trailing trivia, giving it `leading_trivia`
+    fn lex_with_trivia(&mut self, leading_trivia: Vec<Trivia>) -> Token {
+        let mut token = self.lex();
+        token.leading_trivia = leading_trivia;
+        token.trailing_trivia = self.read_trivia(true);
+
+        token
+    }
+
+    /// Lexes the token starting at the current character
+    fn lex(&mut self) -> Token {
+        let char = self.current_char();
+        self.lexeme_start = self.position;
         self.advance();
         match char {
-            '+' => self.push_token(SyntaxKind::Plus, None),
-            '-' => self.push_token(SyntaxKind::Minus, None),
-            '*' => self.push_token(SyntaxKind::Star, None),
-            '/' => {
-                let mut kind = SyntaxKind::Slash;
+            '+' => self.token(SyntaxKind::Plus, None),
+            '-' => self.token(SyntaxKind::Minus, None),
+            '*' => self.token(SyntaxKind::Star, None),
+            '/' => self.token(SyntaxKind::Slash, None),
+            '%' => self.token(SyntaxKind::Percent, None),
+            '^' => self.token(SyntaxKind::Carat, None),
+            '~' => {
+                let mut kind = SyntaxKind::Tilde;
                 if self.match_char('/') {
-                    kind = SyntaxKind::SlashSlash;
+                    kind = SyntaxKind::TildeSlash;
                 }
 
-                self.push_token(kind, None);
+                self.token(kind, None)
             }
-            '%' => self.push_token(SyntaxKind::Percent, None),
-            '^' => self.push_token(SyntaxKind::Carat, None),
-            '~' => self.push_token(SyntaxKind::Tilde, None),
             '&' => {
                 let mut kind = SyntaxKind::Ampersand;
                 if self.match_char('&') {
                     kind = SyntaxKind::AmpersandAmpersand;
                 }
 
-                self.push_token(kind, None);
+                self.token(kind, None)
             }
             '|' => {
                 let mut kind = SyntaxKind::Pipe;
@@ -76,7 +179,7 @@ impl Lexer {
                     kind = SyntaxKind::PipePipe;
                 }
 
-                self.push_token(kind, None);
+                self.token(kind, None)
             }
             '!' => {
                 let mut kind = SyntaxKind::Bang;
@@ -84,7 +187,7 @@ impl Lexer {
                     kind = SyntaxKind::BangEquals;
                 }
 
-                self.push_token(kind, None);
+                self.token(kind, None)
             }
             '=' => {
                 let mut kind = SyntaxKind::Equals;
@@ -92,16 +195,16 @@ impl Lexer {
                     kind = SyntaxKind::EqualsEquals;
                 }
 
-                self.push_token(kind, None);
+                self.token(kind, None)
             }
-            '"' | '\'' => self.read_string(),
+            '"' | '\'' => self.read_string(char),
             '<' => {
                 let mut kind = SyntaxKind::LT;
                 if self.match_char('=') {
                     kind = SyntaxKind::LTE;
                 }
 
-                self.push_token(kind, None);
+                self.token(kind, None)
             }
             '>' => {
                 let mut kind = SyntaxKind::GT;
@@ -109,111 +212,178 @@ impl Lexer {
                     kind = SyntaxKind::GTE;
                 }
 
-                self.push_token(kind, None);
+                self.token(kind, None)
             }
-            ':' => self.push_token(SyntaxKind::Colon, None),
+            ':' => self.token(SyntaxKind::Colon, None),
+            '(' => self.token(SyntaxKind::LeftParen, None),
+            ')' => self.token(SyntaxKind::RightParen, None),
+            '{' => self.token(SyntaxKind::LeftBrace, None),
+            '}' => self.token(SyntaxKind::RightBrace, None),
+            ',' => self.token(SyntaxKind::Comma, None),
+            // a dot followed by a digit starts a float such as `.5`
+            '.' if !self.current_char().is_ascii_digit() => self.token(SyntaxKind::Dot, None),
 
             _ => {
-                if char.is_whitespace() {
-                    return self.skip_whitespace();
+                if char == 'r' && matches!(self.current_char(), '"' | '\'') {
+                    return self.read_raw_string();
                 }
 
                 if char.is_alphabetic() || char == '_' {
                     return self.read_identifier_or_keyword();
                 }
 
-                if char.is_numeric() || char == '.' {
+                if char.is_ascii_digit() || char == '.' {
                     return self.read_number();
                 }
 
-                panic!("unexpected character '{}'", char)
+                self.report(
+                    DiagnosticCode::UnexpectedCharacter,
+                    format!("unexpected character '{}'", char),
+                );
+                self.token(SyntaxKind::Bad, None)
             }
         }
     }
 
     /// Reads an identifier or keyword from the current position in the source.
     /// Advances the lexer while the current character is alphanumeric or an underscore.
-    /// If the lexeme matches a keyword, it pushes a token of the corresponding keyword kind.
-    /// Otherwise, it pushes a token of kind `Identifier`.
-    fn read_identifier_or_keyword(&mut self) -> () {
-        while !self.is_finished()
-            && (self.current_char().is_alphanumeric() || self.current_char() == '_')
-        {
+    /// If the lexeme matches a keyword, it returns a token of the corresponding keyword kind.
+    /// Otherwise, it returns a token of kind `Identifier`.
+    fn read_identifier_or_keyword(&mut self) -> Token {
+        while self.current_char().is_alphanumeric() || self.current_char() == '_' {
             self.advance();
         }
 
-        let current_lexeme = self.current_lexeme();
-        match current_lexeme.as_str() {
+        let lexeme = self.lexeme();
+        match lexeme {
             "true" | "false" => {
-                return self.push_token(
+                return self.token(
                     SyntaxKind::BoolLiteral,
-                    Some(Box::new(current_lexeme == "true")),
+                    Some(LiteralValue::Bool(lexeme == "true")),
                 )
             }
-            "null" => return self.push_token(SyntaxKind::NullLiteral, None),
+            "null" => return self.token(SyntaxKind::NullLiteral, None),
             _ => (),
         }
 
-        let keyword_kind = syntax_facts::get_keyword_kind(&current_lexeme);
-        if let Some(kind) = keyword_kind {
-            return self.push_token(kind, None);
+        let keyword_kind = syntax_facts::get_keyword_kind(lexeme);
+        self.token(keyword_kind.unwrap_or(SyntaxKind::Identifier), None)
+    }
+
+    /// Reads the whitespace and comments starting at the current position
+    ///
+    /// When reading trailing trivia, stops at the end of the line so the next line belongs to
+    /// the next token
+    fn read_trivia(&mut self, trailing: bool) -> Vec<Trivia> {
+        let mut trivia = Vec::new();
+        while !self.is_finished() {
+            self.lexeme_start = self.position;
+
+            let char = self.current_char();
+            let kind = match (char, self.peek_char(1)) {
+                ('\r' | '\n', _) if trailing => break,
+                (char, _) if char.is_whitespace() => {
+                    self.read_whitespace(trailing);
+                    TriviaKind::Whitespace
+                }
+                ('/', '/') => self.read_line_comment(),
+                ('/', '*') => {
+                    self.read_block_comment();
+                    TriviaKind::BlockComment
+                }
+                _ => break,
+            };
+
+            trivia.push(Trivia {
+                kind,
+                span: self.current_span(),
+            });
         }
 
-        self.push_token(SyntaxKind::Identifier, None);
+        self.lexeme_start = self.position;
+        trivia
     }
 
-    /// Advances the lexer past any whitespace characters, updating the current location accordingly.
-    /// If a newline character is encountered, the line number is incremented and the column is reset to 0.
-    /// After advancing past all whitespace, the `lexeme_start_location` is updated to the current
-    /// location to avoid including whitespaces in lexemes.
-    fn skip_whitespace(&mut self) -> () {
+    fn read_whitespace(&mut self, trailing: bool) -> () {
         while !self.is_finished() && self.current_char().is_whitespace() {
-            let char = self.current_char();
-            if char == '\n' {
-                self.advance_new_line();
-            } else {
-                self.advance();
+            if trailing && matches!(self.current_char(), '\r' | '\n') {
+                break;
             }
+
+            self.advance();
         }
-        self.lexeme_start_location = self.current_location();
     }
 
-    /// Pushes a token into `self.tokens` using the current span and current lexeme
-    fn push_token(&mut self, kind: SyntaxKind, value: Option<Box<dyn CloneableAny>>) -> () {
-        let token = Token {
+    /// Reads a comment running to the end of the line, which documents the next declaration if
+    /// it starts with exactly three slashes
+    fn read_line_comment(&mut self) -> TriviaKind {
+        while !self.is_finished() && self.current_char() != '\n' {
+            self.advance();
+        }
+
+        let lexeme = self.lexeme();
+        if lexeme.starts_with("///") && !lexeme.starts_with("////") {
+            TriviaKind::DocComment
+        } else {
+            TriviaKind::LineComment
+        }
+    }
+
+    /// Reads a block comment, in which every `/*` must be closed by its own `*/`
+    fn read_block_comment(&mut self) -> () {
+        self.advance_multiple(2);
+
+        let mut depth = 1;
+        while depth > 0 {
+            if self.is_finished() {
+                let opening = Span::new(self.file, self.lexeme_start, self.lexeme_start + 2);
+                let diagnostic = Diagnostic::error(
+                    DiagnosticCode::UnterminatedComment,
+                    "unterminated block comment",
+                    opening,
+                );
+                return self.diagnostics.push(diagnostic);
+            }
+
+            match (self.current_char(), self.peek_char(1)) {
+                ('/', '*') => {
+                    depth += 1;
+                    self.advance_multiple(2);
+                }
+                ('*', '/') => {
+                    depth -= 1;
+                    self.advance_multiple(2);
+                }
+                _ => self.advance(),
+            }
+        }
+    }
+
+    /// Creates a token spanning the current lexeme, without any trivia yet
+    fn token(&self, kind: SyntaxKind, value: Option<LiteralValue>) -> Token {
+        Token {
             kind,
             span: self.current_span(),
             value,
-            text: self.current_lexeme(),
-        };
-
-        self.tokens.push(token);
+            leading_trivia: Vec::new(),
+            trailing_trivia: Vec::new(),
+        }
     }
 
-    fn current_lexeme(&self) -> String {
-        let span = self.current_span();
-        self.source[span.start.position..span.end.position]
-            .iter()
-            .collect()
+    /// Records a diagnostic spanning the current lexeme
+    fn report(&mut self, code: DiagnosticCode, message: String) -> () {
+        let diagnostic = Diagnostic::error(code, message, self.current_span());
+        self.diagnostics.push(diagnostic);
     }
 
-    /// Returns a Span representing the lexeme start location to the current location
-    fn current_span(&self) -> Span {
-        let end_location = self.current_location();
-        Span {
-            start: self.lexeme_start_location.to_owned(),
-            end: end_location,
-        }
+    /// Returns the source text from the start of the current lexeme to the current position
+    fn lexeme(&self) -> &'a str {
+        &self.source[self.lexeme_start..self.position]
     }
 
-    /// Returns a Location representing the current position, column, and line
-    fn current_location(&self) -> Location {
-        Location {
-            file_name: self.lexeme_start_location.file_name.clone(),
-            position: self.position,
-            column: self.column,
-            line: self.line,
-        }
+    /// Returns a Span from the start of the current lexeme to the current position
+    fn current_span(&self) -> Span {
+        Span::new(self.file, self.lexeme_start, self.position)
     }
 
     /// Returns the character at the current position
@@ -221,14 +391,21 @@ impl Lexer {
         self.peek_char(0)
     }
 
-    /// Returns the character at the given offset from the current position.
-    /// Returns null terminator if out of bounds
+    /// Returns the character at the given offset, counted in characters, from the current
+    /// position. Returns null terminator if out of bounds
     fn peek_char(&self, offset: usize) -> char {
-        if self.is_finished_at_offset(offset) {
-            '\0'
-        } else {
-            self.source[self.position + offset]
-        }
+        self.source[self.position..]
+            .chars()
+            .nth(offset)
+            .unwrap_or('\0')
+    }
+
+    /// Returns the character right before the current position, or null terminator at the start
+    fn peek_previous_char(&self) -> char {
+        self.source[..self.position]
+            .chars()
+            .next_back()
+            .unwrap_or('\0')
     }
 
     /// Returns whether the current character matched the expected character
@@ -248,69 +425,381 @@ impl Lexer {
         self.advance_multiple(1);
     }
 
-    /// Advances the current position (as well as the column) by `amount`
+    /// Advances the current position by `amount` characters, stopping at the end of the source
     fn advance_multiple(&mut self, amount: usize) -> () {
-        self.position += amount;
-        self.column += amount;
+        for char in self.source[self.position..].chars().take(amount) {
+            self.position += char.len_utf8();
+        }
     }
 
-    /// Advances the current position and line, and resets the column back to 0
-    fn advance_new_line(&mut self) -> () {
-        self.position += 1;
-        self.line += 1;
-        self.column = 0;
+    /// Returns true if the lexer has reached the end of the source, false otherwise
+    fn is_finished(&self) -> bool {
+        self.position >= self.source.len()
     }
 
-    /// Determines whether the lexer has reached the end of the source with the given offset
+    /// Reads a string delimited by `quote`, which is either `"` or `'`, unescaping its escape
+    /// sequences and splitting it into text and expression parts at every `{...}`
     ///
-    /// Returns true if the current position plus the specified offset is greater than or equal
-    /// to the length of the source string, indicating that the end has been reached
-    fn is_finished_at_offset(&self, offset: usize) -> bool {
-        self.position + offset >= self.source.len()
+    /// Strings may span multiple lines; the lines of their tokens and diagnostics follow from
+    /// their spans like any other token's
+    fn read_string(&mut self, quote: char) -> Token {
+        let start = self.lexeme_start;
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut terminated = false;
+        while !self.is_finished() {
+            match self.current_char() {
+                char if char == quote => {
+                    self.advance();
+                    terminated = true;
+                    break;
+                }
+                '\\' => text.extend(self.read_escape()),
+                '{' => {
+                    if !text.is_empty() {
+                        parts.push(StringPart::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(self.read_interpolation());
+                }
+                char => {
+                    text.push(char);
+                    self.advance();
+                }
+            }
+        }
+
+        self.lexeme_start = start;
+        if !terminated {
+            return self.report_unterminated_string();
+        }
+
+        if parts.is_empty() {
+            return self.token(SyntaxKind::StringLiteral, Some(LiteralValue::Str(text)));
+        }
+
+        if !text.is_empty() {
+            parts.push(StringPart::Text(text));
+        }
+        let value = LiteralValue::Interpolated(parts);
+        self.token(SyntaxKind::InterpolatedStringLiteral, Some(value))
     }
 
-    /// Returns true if the lexer has reached the end of the source, false otherwise
-    fn is_finished(&self) -> bool {
-        self.position >= self.source.len()
+    /// Reads a raw string such as `r"C:\path"`, in which backslashes and braces are plain text
+    fn read_raw_string(&mut self) -> Token {
+        let quote = self.current_char();
+        self.advance();
+        while !self.is_finished() && self.current_char() != quote {
+            self.advance();
+        }
+
+        if !self.match_char(quote) {
+            return self.report_unterminated_string();
+        }
+
+        let lexeme = self.lexeme();
+        let value = lexeme[2..lexeme.len() - 1].to_string();
+        self.token(SyntaxKind::StringLiteral, Some(LiteralValue::Str(value)))
+    }
+
+    fn report_unterminated_string(&mut self) -> Token {
+        self.report(
+            DiagnosticCode::UnterminatedString,
+            "unterminated string literal".to_string(),
+        );
+        self.token(SyntaxKind::Bad, None)
     }
 
-    fn read_string(&mut self) -> () {
-        while !self.is_finished() && self.current_char() != '"' {
+    /// Reads an escape sequence, returning the character it stands for, or None after reporting
+    /// an invalid one
+    fn read_escape(&mut self) -> Option<char> {
+        let start = self.position;
+        self.advance();
+        if self.is_finished() {
+            // the string is reported as unterminated
+            return None;
+        }
+
+        let char = self.current_char();
+        self.advance();
+        let escaped = match char {
+            'n' => Some('\n'),
+            't' => Some('\t'),
+            'r' => Some('\r'),
+            '0' => Some('\0'),
+            '\\' | '"' | '\'' | '{' | '}' => Some(char),
+            'u' => self.read_unicode_escape(),
+            _ => None,
+        };
+
+        if escaped.is_none() {
+            let message = match char {
+                'u' => "invalid unicode escape".to_string(),
+                _ => format!("unknown escape sequence '\\{}'", char),
+            };
+            let help = match char {
+                'u' => "unicode escapes are written as '\\u{...}' with up to 6 hexadecimal digits",
+                _ => "valid escapes are '\\n', '\\t', '\\r', '\\0', '\\\\', '\\\"', '\\'', '\\{', '\\}' and '\\u{...}'",
+            };
+            let span = Span::new(self.file, start, self.position);
+            let diagnostic = Diagnostic::error(DiagnosticCode::InvalidEscape, message, span);
+            self.diagnostics.push(diagnostic.with_help(help));
+        }
+
+        escaped
+    }
+
+    /// Reads the `{...}` of a unicode escape, returning the character its digits stand for
+    fn read_unicode_escape(&mut self) -> Option<char> {
+        if !self.match_char('{') {
+            return None;
+        }
+
+        let start = self.position;
+        while self.current_char().is_ascii_hexdigit() {
             self.advance();
         }
+        let digits = &self.source[start..self.position];
+        if !self.match_char('}') || digits.is_empty() || digits.len() > 6 {
+            return None;
+        }
 
-        let terminated = self.match_char('"');
-        if !terminated {
-            panic!("unterminated string literal");
+        u32::from_str_radix(digits, 16)
+            .ok()
+            .and_then(char::from_u32)
+    }
+
+    /// Reads the tokens of an interpolated expression, from its `{` up to the matching `}`
+    fn read_interpolation(&mut self) -> StringPart {
+        let start = self.position;
+        self.advance();
+
+        let mut tokens = Vec::new();
+        let mut depth = 0;
+        let mut leading_trivia = self.read_trivia(false);
+        while !self.is_finished() && (depth != 0 || self.current_char() != '}') {
+            let token = self.lex_with_trivia(leading_trivia);
+            match token.kind {
+                SyntaxKind::LeftBrace => depth += 1,
+                SyntaxKind::RightBrace => depth -= 1,
+                _ => (),
+            }
+            tokens.push(token);
+
+            leading_trivia = self.read_trivia(false);
         }
+        self.match_char('}');
 
-        let current_lexeme = self.current_lexeme();
-        let value = current_lexeme.trim_matches('"').to_owned();
-        self.push_token(SyntaxKind::StringLiteral, Some(Box::new(value)));
+        StringPart::Expression {
+            tokens,
+            span: Span::new(self.file, start, self.position),
+        }
     }
 
-    fn read_number(&mut self) -> () {
-        let mut decimal_used = false;
+    /// Reads a number literal, made of an optional radix prefix (`0x`, `0o` or `0b`), digits
+    /// optionally separated by underscores, a fraction and an exponent for decimal floats, and
+    /// an optional type suffix such as `u8` or `f32`
+    fn read_number(&mut self) -> Token {
+        let radix = match (self.peek_previous_char(), self.current_char()) {
+            ('0', 'x' | 'X') => 16,
+            ('0', 'o' | 'O') => 8,
+            ('0', 'b' | 'B') => 2,
+            _ => 10,
+        };
+        if radix != 10 {
+            self.advance();
+            return self.read_radix_integer(radix);
+        }
+
+        let mut decimal_used = self.peek_previous_char() == '.';
+        let mut malformed = false;
         while !self.is_finished()
-            && (self.current_char().is_numeric() || self.current_char() == '.')
+            && (self.current_char().is_ascii_digit() || matches!(self.current_char(), '.' | '_'))
         {
             let current_char_is_decimal = self.current_char() == '.';
-            if decimal_used && current_char_is_decimal {
-                panic!("malformed number literal");
+            malformed |= decimal_used && current_char_is_decimal;
+            decimal_used |= current_char_is_decimal;
+            self.advance();
+        }
+
+        if malformed {
+            self.report(
+                DiagnosticCode::MalformedNumber,
+                "malformed number literal".to_string(),
+            );
+            return self.token(SyntaxKind::Bad, None);
+        }
+
+        // an exponent needs digits, so `1e` is read as `1` with an invalid suffix
+        let exponent_digit = match self.peek_char(1) {
+            '+' | '-' => self.peek_char(2),
+            char => char,
+        };
+        let exponent_used =
+            matches!(self.current_char(), 'e' | 'E') && exponent_digit.is_ascii_digit();
+        if exponent_used {
+            self.advance_multiple(2);
+            while self.current_char().is_ascii_digit() || self.current_char() == '_' {
+                self.advance();
             }
+        }
 
-            decimal_used |= current_char_is_decimal;
+        let digits = self.lexeme().replace('_', "");
+        let suffix = self.read_number_suffix();
+        let float = decimal_used || exponent_used;
+        match (suffix, float) {
+            (None, false) => self.integer_token(digits.parse::<u64>().ok(), None),
+            (Some(suffix), false) if INTEGER_SUFFIXES.contains(&suffix) => {
+                self.integer_token(digits.parse::<u64>().ok(), Some(suffix))
+            }
+            (None, true) | (Some("f64"), _) => {
+                self.float_token(digits.parse::<f64>().ok(), "f64", format!("{:e}", f64::MAX))
+            }
+            (Some("f32"), _) => {
+                let value = digits.parse::<f32>().ok().map(|value| value as f64);
+                self.float_token(value, "f32", format!("{:e}", f32::MAX))
+            }
+            (Some(suffix), true) => {
+                self.report_number_suffix(suffix, "float literal", &FLOAT_SUFFIXES)
+            }
+            (Some(suffix), false) => {
+                let valid = [INTEGER_SUFFIXES.as_slice(), &FLOAT_SUFFIXES].concat();
+                self.report_number_suffix(suffix, "number literal", &valid)
+            }
+        }
+    }
+
+    /// Reads the digits of an integer after its radix prefix, along with an optional suffix
+    fn read_radix_integer(&mut self, radix: u32) -> Token {
+        while self.current_char().is_ascii_hexdigit() || self.current_char() == '_' {
+            // letters end a binary or octal literal, starting its suffix
+            if radix != 16 && self.current_char().is_ascii_alphabetic() {
+                break;
+            }
             self.advance();
         }
 
-        let current_lexeme = self.current_lexeme();
-        let value = current_lexeme.parse::<f64>().unwrap_or(0.0);
-        let kind = if decimal_used {
-            SyntaxKind::FloatLiteral
-        } else {
-            SyntaxKind::IntLiteral
+        let lexeme = self.lexeme();
+        let digits = lexeme[2..].replace('_', "");
+        let invalid = digits.chars().find(|char| !char.is_digit(radix));
+        let name = match radix {
+            16 => "hexadecimal literal",
+            8 => "octal literal",
+            _ => "binary literal",
         };
+        if digits.is_empty() || invalid.is_some() {
+            self.read_number_suffix();
+            let message = match invalid {
+                Some(char) => format!("invalid digit '{}' in {}", char, name),
+                None => format!("missing digits after '{}'", &lexeme[..2]),
+            };
+            self.report(DiagnosticCode::MalformedNumber, message);
+            return self.token(SyntaxKind::Bad, None);
+        }
+
+        let value = u64::from_str_radix(&digits, radix).ok();
+        match self.read_number_suffix() {
+            None => self.integer_token(value, None),
+            Some(suffix) if INTEGER_SUFFIXES.contains(&suffix) => {
+                self.integer_token(value, Some(suffix))
+            }
+            Some(suffix) => self.report_number_suffix(suffix, name, &INTEGER_SUFFIXES),
+        }
+    }
+
+    /// Reads the letters, digits and underscores right after a number, if any
+    fn read_number_suffix(&mut self) -> Option<&'a str> {
+        let start = self.position;
+        while self.current_char().is_alphanumeric() || self.current_char() == '_' {
+            self.advance();
+        }
+
+        let suffix = &self.source[start..self.position];
+        (!suffix.is_empty()).then_some(suffix)
+    }
+
+    /// Creates an integer literal token, or reports it if it does not fit its type, which is
+    /// `i64` unless a suffix says otherwise
+    ///
+    /// The magnitude of a signed type's minimum, such as `128i8`, only fits once negated. Its
+    /// token holds the minimum itself, and the parser either folds the `-` before it into the
+    /// literal or reports it, see `unnegated_minimum`
+    fn integer_token(&mut self, value: Option<u64>, suffix: Option<&str>) -> Token {
+        let range = integer_range(suffix);
+        let (min, max) = (*range.start() as i128, *range.end() as i128);
+        match value.map(i128::from) {
+            Some(value) if (min..=max).contains(&value) => {
+                let value = LiteralValue::Int(value as i64);
+                return self.token(SyntaxKind::IntLiteral, Some(value));
+            }
+            Some(value) if min < 0 && value == -min => {
+                let value = LiteralValue::Int(*range.start());
+                return self.token(SyntaxKind::IntLiteral, Some(value));
+            }
+            _ => (),
+        }
+
+        let diagnostic = integer_out_of_range(suffix.unwrap_or("i64"), self.current_span());
+        self.diagnostics.push(diagnostic);
+        self.token(SyntaxKind::Bad, None)
+    }
+
+    /// Returns a float literal, or reports the number as malformed if its digits could not be
+    /// parsed, rather than reading it as zero
+    ///
+    /// Parsing rounds a number too large for the type `ty`, whose largest value is `max`, to
+    /// infinity, which is reported like an integer that does not fit its type
+    fn float_token(&mut self, value: Option<f64>, ty: &str, max: String) -> Token {
+        match value {
+            Some(value) if value.is_finite() => {
+                self.token(SyntaxKind::FloatLiteral, Some(LiteralValue::Float(value)))
+            }
+            Some(_) => {
+                let diagnostic = Diagnostic::error(
+                    DiagnosticCode::FloatOutOfRange,
+                    format!("float literal is too large for '{}'", ty),
+                    self.current_span(),
+                )
+                .with_note(format!("the largest '{}' is {}", ty, max));
+                self.diagnostics.push(diagnostic);
+                self.token(SyntaxKind::Bad, None)
+            }
+            None => {
+                self.report(
+                    DiagnosticCode::MalformedNumber,
+                    "malformed number literal".to_string(),
+                );
+                self.token(SyntaxKind::Bad, None)
+            }
+        }
+    }
+
+    /// Reports a suffix that is not one of `valid` for a literal described as `literal`
+    fn report_number_suffix(&mut self, suffix: &str, literal: &str, valid: &[&str]) -> Token {
+        let valid: Vec<String> = valid.iter().map(|suffix| format!("'{}'", suffix)).collect();
+        let diagnostic = Diagnostic::error(
+            DiagnosticCode::InvalidNumberSuffix,
+            format!("invalid suffix '{}' for {}", suffix, literal),
+            self.current_span(),
+        )
+        .with_help(format!("valid suffixes are {}", valid.join(", ")));
+
+        self.diagnostics.push(diagnostic);
+        self.token(SyntaxKind::Bad, None)
+    }
+}
+
+impl Iterator for Lexer<'_> {
+    type Item = Token;
+
+    /// Lexes the next token along with its trivia, or reads the end trivia and returns None
+    /// once the end of the source is reached
+    fn next(&mut self) -> Option<Token> {
+        let leading_trivia = self.read_trivia(false);
+        if self.is_finished() {
+            self.end_trivia.extend(leading_trivia);
+            return None;
+        }
 
-        self.push_token(kind, Some(Box::new(value)));
+        Some(self.lex_with_trivia(leading_trivia))
     }
 }
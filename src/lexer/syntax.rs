@@ -1,131 +1,145 @@
-use std::{
-    any::{Any, TypeId},
-    fmt,
+use std::{fmt, ops::Range};
+
+use crate::{
+    diagnostics::{Diagnostic, DiagnosticCode},
+    source::{FileId, SourceFile},
 };
 
-#[derive(Clone)]
+/// A line and column resolved from a byte offset by `SourceFile::location`
+///
+/// Lines start at 1 and columns at 0, and columns count characters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Location {
-    pub file_name: String,
     pub line: usize,
     pub column: usize,
-    pub position: usize,
-}
-
-impl Default for Location {
-    fn default() -> Self {
-        Self {
-            file_name: "<default>".to_string(),
-            line: 1,
-            column: 0,
-            position: 0,
-        }
-    }
 }
 
 impl fmt::Display for Location {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}:{}:{}", self.file_name, self.line, self.column)
+        write!(f, "{}:{}", self.line, self.column)
     }
 }
 
-impl fmt::Debug for Location {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}:{}:{}", self.file_name, self.line, self.column)
-    }
-}
-
-#[derive(Clone)]
+/// A range of byte offsets into a single file, always on character boundaries
+///
+/// Lines and columns are not stored, and are looked up through the file when needed
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
 pub struct Span {
-    pub start: Location,
-    pub end: Location,
+    pub file: FileId,
+    pub start: u32,
+    pub end: u32,
 }
 
 impl Span {
+    pub fn new(file: FileId, start: usize, end: usize) -> Self {
+        Self {
+            file,
+            start: start as u32,
+            end: end as u32,
+        }
+    }
+
     pub fn len(&self) -> usize {
-        self.end.position - self.start.position
+        (self.end - self.start) as usize
     }
-}
 
-impl Default for Span {
-    fn default() -> Self {
-        Self {
-            start: Location::default(),
-            end: Location::default(),
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn range(&self) -> Range<usize> {
+        self.start as usize..self.end as usize
+    }
+
+    pub fn contains(&self, position: usize) -> bool {
+        self.range().contains(&position) || position == self.end as usize
+    }
+
+    /// Returns a span starting where `self` starts and ending where `other` ends
+    pub fn to(&self, other: &Span) -> Span {
+        Span {
+            file: self.file,
+            start: self.start,
+            end: other.end,
         }
     }
 }
 
 impl fmt::Display for Span {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{} - {}", self.start, self.end)
+        write!(f, "{}..{}", self.start, self.end)
     }
 }
 
 impl fmt::Debug for Span {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{} - {}", self.start, self.end)
+        write!(f, "{}@{}..{}", self.file.index(), self.start, self.end)
     }
 }
 
-pub trait CloneableAny: Any {
-    fn clone_box<'a>(&'a self) -> Box<dyn CloneableAny>;
+/// The value of a literal token, checked when it is lexed
+#[derive(Debug, Clone, PartialEq)]
+pub enum LiteralValue {
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Bool(bool),
+    /// The parts of a string with `{...}` interpolations, see `SyntaxKind::InterpolatedStringLiteral`
+    Interpolated(Vec<StringPart>),
 }
 
-impl<T: Any + Clone> CloneableAny for T {
-    fn clone_box<'a>(&'a self) -> Box<dyn CloneableAny> {
-        Box::new(self.clone())
-    }
+/// A piece of an interpolated string
+#[derive(Debug, Clone, PartialEq)]
+pub enum StringPart {
+    /// Text with its escape sequences replaced by the characters they stand for
+    Text(String),
+    /// The tokens between the braces of an interpolation, whose span includes the braces
+    Expression { tokens: Vec<Token>, span: Span },
 }
 
-impl dyn CloneableAny {
-    #[inline]
-    pub fn downcast_ref<T: CloneableAny>(&self) -> Option<&T> {
-        if self.is::<T>() {
-            // SAFETY: just checked whether we are pointing to the correct type, and we can rely on
-            // that check for memory safety because we have implemented Any for all types; no other
-            // impls can exist as they would conflict with our impl.
-            unsafe { Some(self.downcast_ref_unchecked()) }
-        } else {
-            None
-        }
-    }
-
-    #[inline]
-    pub unsafe fn downcast_ref_unchecked<T: CloneableAny>(&self) -> &T {
-        debug_assert!(self.is::<T>());
-        // SAFETY: caller guarantees that T is the correct type
-        unsafe { &*(self as *const dyn CloneableAny as *const T) }
-    }
-
-    #[inline]
-    pub fn is<T: CloneableAny>(&self) -> bool {
-        // Get `TypeId` of the type this function is instantiated with.
-        let t = TypeId::of::<T>();
+/// The kind of a piece of trivia, see `Trivia`
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum TriviaKind {
+    Whitespace,
+    /// A comment running to the end of the line, e.g. `// note`
+    LineComment,
+    /// A comment delimited by `/*` and `*/`, which may be nested
+    BlockComment,
+    /// A line comment starting with exactly three slashes, documenting the declaration after it
+    DocComment,
+}
 
-        // Get `TypeId` of the type in the trait object (`self`).
-        let concrete = self.type_id();
+/// Source text that carries no meaning for the parser, such as whitespace and comments
+///
+/// Trivia is attached to the tokens around it, so the exact source text can be rebuilt from a
+/// `TokenStream`, see `TokenStream::source_text`. Like tokens, trivia only knows its span, and
+/// its text is read from the source with `SourceFile::text`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub span: Span,
+}
 
-        // Compare both `TypeId`s on equality.
-        t == concrete
+impl Trivia {
+    pub fn is_comment(&self) -> bool {
+        self.kind != TriviaKind::Whitespace
     }
 }
 
+/// A lexeme of the source, along with the trivia around it
+///
+/// A token does not hold its text, which is read from the source with `SourceFile::text`
+#[derive(Debug, Clone, PartialEq)]
 pub struct Token {
     pub kind: SyntaxKind,
     pub span: Span,
-    pub text: String,
-    pub value: Option<Box<dyn CloneableAny>>,
-}
-
-impl Clone for Token {
-    fn clone(&self) -> Self {
-        Self {
-            kind: self.kind,
-            span: self.span.clone(),
-            text: self.text.clone(),
-            value: self.value.as_deref().map(|b| b.clone_box()),
-        }
-    }
+    /// The value of a literal, or None for any other token and for `null`
+    pub value: Option<LiteralValue>,
+    /// The trivia between the previous token and this one, except what the previous token holds
+    /// as trailing trivia
+    pub leading_trivia: Vec<Trivia>,
+    /// The trivia following this token on the same line
+    pub trailing_trivia: Vec<Trivia>,
 }
 
 impl Default for Token {
@@ -133,31 +147,104 @@ impl Default for Token {
         Self {
             kind: SyntaxKind::NullLiteral,
             span: Span::default(),
-            text: "null".to_string(),
             value: None,
+            leading_trivia: Vec::new(),
+            trailing_trivia: Vec::new(),
         }
     }
 }
 
 impl Token {
-    pub fn downcast_value<T: CloneableAny + 'static>(&self) -> Option<&T> {
-        self.value.as_ref().unwrap().downcast_ref::<T>()
+    /// Returns the text of the doc comments right before this token, without their `///`
+    /// markers, or None if the token is not documented
+    pub fn doc_comment(&self, file: &SourceFile) -> Option<String> {
+        let lines: Vec<&str> = self
+            .leading_trivia
+            .iter()
+            .filter(|trivia| trivia.kind == TriviaKind::DocComment)
+            .map(|trivia| {
+                let line = file.text(trivia.span).trim_start_matches('/');
+                line.strip_prefix(' ').unwrap_or(line).trim_end()
+            })
+            .collect();
+
+        if lines.is_empty() {
+            None
+        } else {
+            Some(lines.join("\n"))
+        }
+    }
+
+    /// Returns the span of the token along with its leading and trailing trivia
+    pub fn full_span(&self) -> Span {
+        let start = self
+            .leading_trivia
+            .first()
+            .map_or(self.span, |trivia| trivia.span);
+        let end = self
+            .trailing_trivia
+            .last()
+            .map_or(self.span, |trivia| trivia.span);
+
+        start.to(&end)
+    }
+
+    /// Returns the token's text surrounded by its leading and trailing trivia
+    pub fn full_text<'a>(&self, file: &'a SourceFile) -> &'a str {
+        file.text(self.full_span())
     }
 }
 
 pub struct TokenStream {
     pub tokens: Vec<Token>,
+    pub diagnostics: Vec<Diagnostic>,
+    /// The trivia after the last token, or the whole source if there are no tokens
+    pub end_trivia: Vec<Trivia>,
     position: usize,
 }
 
 impl TokenStream {
     pub fn new(tokens: Vec<Token>) -> Self {
+        Self::with_diagnostics(tokens, Vec::new())
+    }
+
+    pub fn with_diagnostics(tokens: Vec<Token>, diagnostics: Vec<Diagnostic>) -> Self {
         TokenStream {
             tokens,
+            diagnostics,
+            end_trivia: Vec::new(),
             position: 0,
         }
     }
 
+    /// Rebuilds the exact source text the tokens were lexed from out of their spans in `file`
+    pub fn source_text(&self, file: &SourceFile) -> String {
+        let mut text: String = self
+            .tokens
+            .iter()
+            .map(|token| token.full_text(file))
+            .collect();
+        for trivia in self.end_trivia.iter() {
+            text.push_str(file.text(trivia.span));
+        }
+
+        text
+    }
+
+    /// Returns every comment of the source, in order
+    pub fn comments(&self) -> impl Iterator<Item = &Trivia> {
+        self.tokens
+            .iter()
+            .flat_map(|token| {
+                token
+                    .leading_trivia
+                    .iter()
+                    .chain(token.trailing_trivia.iter())
+            })
+            .chain(self.end_trivia.iter())
+            .filter(|trivia| trivia.is_comment())
+    }
+
     pub fn iter(&self) -> std::slice::Iter<'_, Token> {
         self.tokens.iter()
     }
@@ -166,6 +253,10 @@ impl TokenStream {
         self.tokens.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
     pub fn first(&self) -> &Token {
         self.at(0)
     }
@@ -174,17 +265,38 @@ impl TokenStream {
         &self.tokens[index]
     }
 
-    pub fn consume(&mut self, kind: SyntaxKind) -> () {
+    /// Consumes the current token if it is of the given kind, otherwise returns a diagnostic
+    /// describing what was expected
+    pub fn consume(&mut self, kind: SyntaxKind) -> Result<&Token, Diagnostic> {
         if self.is_finished() {
-            panic!("Expected {:?}, got EOF", kind);
+            return Err(Diagnostic::error(
+                DiagnosticCode::UnexpectedEndOfFile,
+                format!("expected {:?}, got end of file", kind),
+                self.end_span(),
+            ));
         }
 
-        let token = self.advance();
-        if token.kind == kind {
-            return;
+        if self.current().kind == kind {
+            return Ok(self.advance());
         }
 
-        panic!("Expected {:?}, got {:?}", kind, token.kind);
+        let token = self.current();
+        Err(Diagnostic::error(
+            DiagnosticCode::UnexpectedToken,
+            format!("expected {:?}, got {:?}", kind, token.kind),
+            token.span,
+        ))
+    }
+
+    /// Returns an empty span right after the last token, used to report errors at end of file
+    pub fn end_span(&self) -> Span {
+        match self.tokens.last() {
+            Some(token) => Span {
+                start: token.span.end,
+                ..token.span
+            },
+            None => Span::default(),
+        }
     }
 
     pub fn match_kind(&mut self, kind: SyntaxKind) -> bool {
@@ -211,7 +323,7 @@ impl TokenStream {
     }
 
     pub fn check_kind_at_offset(&mut self, kind: SyntaxKind, offset: usize) -> bool {
-        !self.is_finished() && self.peek(offset).kind == kind
+        !self.is_finished_at_offset(offset) && self.peek(offset).kind == kind
     }
 
     pub fn current(&self) -> &Token {
@@ -226,6 +338,16 @@ impl TokenStream {
         &self.tokens[self.position - offset]
     }
 
+    /// The index of the current token
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Moves to the token at `position`, e.g. to parse part of the stream again
+    pub fn seek(&mut self, position: usize) -> () {
+        self.position = position;
+    }
+
     pub fn is_finished(&self) -> bool {
         self.is_finished_at_offset(0)
     }
@@ -240,32 +362,6 @@ impl TokenStream {
     }
 }
 
-impl fmt::Debug for Token {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut dbg = f.debug_struct("Token");
-
-        dbg.field("kind", &self.kind)
-            .field("span", &self.span)
-            .field("text", &self.text);
-
-        if let Some(v) = &self.value {
-            if let Some(s) = v.downcast_ref::<String>() {
-                dbg.field("value", &Some(s)); // Pass Some(s) directly
-            } else if let Some(s) = v.downcast_ref::<bool>() {
-                dbg.field("value", &Some(s));
-            } else if let Some(s) = v.downcast_ref::<f64>() {
-                dbg.field("value", &Some(s));
-            } else {
-                dbg.field("value", &"Unknown Type");
-            }
-        } else {
-            dbg.field("value", &None::<String>); // Explicitly show None
-        }
-
-        dbg.finish()
-    }
-}
-
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum SyntaxKind {
@@ -273,10 +369,10 @@ pub enum SyntaxKind {
     Minus,
     Star,
     Slash,
-    SlashSlash,
     Percent,
     Carat,
     Tilde,
+    TildeSlash,
     Ampersand,
     Pipe,
     AmpersandAmpersand,
@@ -290,14 +386,64 @@ pub enum SyntaxKind {
     EqualsEquals,
     BangEquals,
     Colon,
+    LeftParen,
+    RightParen,
+    LeftBrace,
+    RightBrace,
+    Comma,
+    Dot,
 
     Identifier,
     LetKeyword,
     FnKeyword,
+    ReturnKeyword,
+    IfKeyword,
+    ElseKeyword,
+    WhileKeyword,
+    ForKeyword,
+    InKeyword,
+    BreakKeyword,
+    ContinueKeyword,
+    ImportKeyword,
+    PubKeyword,
 
     StringLiteral, // literals
+    /// A string with at least one `{...}` interpolation, lexed as a single token
+    InterpolatedStringLiteral,
     IntLiteral,
     FloatLiteral,
     BoolLiteral,
     NullLiteral,
+
+    Bad, // lexemes that could not be lexed, see the accompanying diagnostic
+    /// An empty token holding the trivia at the end of the file, only found in syntax trees
+    EndOfFile,
+
+    Program, // nodes of the concrete syntax tree, see `parser::cst`
+    LetStatement,
+    ExpressionStatement,
+    BlockStatement,
+    FunctionDeclaration,
+    ParameterList,
+    Parameter,
+    ReturnStatement,
+    IfStatement,
+    WhileStatement,
+    ForStatement,
+    BreakStatement,
+    ContinueStatement,
+    ImportStatement,
+    NamedType,
+    FunctionType,
+    LiteralExpression,
+    InterpolationExpression,
+    BinaryExpression,
+    UnaryExpression,
+    GroupingExpression,
+    VariableExpression,
+    AssignmentExpression,
+    CallExpression,
+    ArgumentList,
+    /// Source that could not be parsed, or an empty node where something is missing
+    Error,
 }
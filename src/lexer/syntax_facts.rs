@@ -1,28 +1,155 @@
-use std::collections::HashMap;
-
 use super::syntax::SyntaxKind;
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Associativity {
+    Left,
+    Right,
+}
+
+/// Returns the precedence of the given kind when used as a prefix operator, or None if the kind
+/// is not a unary operator
+///
+/// Unary operators bind tighter than every binary operator except `^`, so `-2 ^ 2` is `-(2 ^ 2)`
+pub fn get_unary_operator_precedence(kind: &SyntaxKind) -> Option<u8> {
+    match kind {
+        SyntaxKind::Minus | SyntaxKind::Bang | SyntaxKind::Tilde => Some(10),
+        _ => None,
+    }
+}
+
+/// Returns the precedence and associativity of the given kind when used as an infix operator,
+/// or None if the kind is not a binary operator
+///
+/// | precedence | operators                | associativity |
+/// |------------|--------------------------|---------------|
+/// | 11         | `^`                      | right         |
+/// | 10         | unary `-` `!` `~`        | prefix        |
+/// | 9          | `*` `/` `~/` `%`         | left          |
+/// | 8          | `+` `-`                  | left          |
+/// | 7          | `&`                      | left          |
+/// | 6          | `~` (bitwise xor)        | left          |
+/// | 5          | `\|`                     | left          |
+/// | 4          | `<` `<=` `>` `>=`        | left          |
+/// | 3          | `==` `!=`                | left          |
+/// | 2          | `&&`                     | left          |
+/// | 1          | `\|\|`                   | left          |
+///
+/// Floor division is spelled `~/`. It used to be `//`, which now starts a line comment, so
+/// `7 // 2` reads as `7` followed by a comment; the parser hints at this when the comment
+/// causes a syntax error
+pub fn get_binary_operator_precedence(kind: &SyntaxKind) -> Option<(u8, Associativity)> {
+    let precedence = match kind {
+        SyntaxKind::Carat => return Some((11, Associativity::Right)),
+        SyntaxKind::Star | SyntaxKind::Slash | SyntaxKind::TildeSlash | SyntaxKind::Percent => 9,
+        SyntaxKind::Plus | SyntaxKind::Minus => 8,
+        SyntaxKind::Ampersand => 7,
+        SyntaxKind::Tilde => 6,
+        SyntaxKind::Pipe => 5,
+        SyntaxKind::LT | SyntaxKind::LTE | SyntaxKind::GT | SyntaxKind::GTE => 4,
+        SyntaxKind::EqualsEquals | SyntaxKind::BangEquals => 3,
+        SyntaxKind::AmpersandAmpersand => 2,
+        SyntaxKind::PipePipe => 1,
+        _ => return None,
+    };
+
+    Some((precedence, Associativity::Left))
+}
+
+/// Returns whether a token of the given kind can be the first token of an expression
+pub fn can_start_expression(kind: &SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::Identifier
+            | SyntaxKind::LeftParen
+            | SyntaxKind::StringLiteral
+            | SyntaxKind::InterpolatedStringLiteral
+            | SyntaxKind::IntLiteral
+            | SyntaxKind::FloatLiteral
+            | SyntaxKind::BoolLiteral
+            | SyntaxKind::NullLiteral
+    ) || get_unary_operator_precedence(kind).is_some()
+}
+
+/// Returns whether a token of the given kind always starts a statement
+pub fn can_start_statement(kind: &SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::LetKeyword
+            | SyntaxKind::FnKeyword
+            | SyntaxKind::PubKeyword
+            | SyntaxKind::ImportKeyword
+            | SyntaxKind::ReturnKeyword
+            | SyntaxKind::IfKeyword
+            | SyntaxKind::WhileKeyword
+            | SyntaxKind::ForKeyword
+            | SyntaxKind::BreakKeyword
+            | SyntaxKind::ContinueKeyword
+    )
+}
+
+/// Given an operator kind, returns its lexeme, or None if the kind is not an operator
+pub fn get_operator_lexeme(kind: &SyntaxKind) -> Option<&'static str> {
+    let lexeme = match kind {
+        SyntaxKind::Plus => "+",
+        SyntaxKind::Minus => "-",
+        SyntaxKind::Star => "*",
+        SyntaxKind::Slash => "/",
+        SyntaxKind::TildeSlash => "~/",
+        SyntaxKind::Percent => "%",
+        SyntaxKind::Carat => "^",
+        SyntaxKind::Ampersand => "&",
+        SyntaxKind::Pipe => "|",
+        SyntaxKind::Tilde => "~",
+        SyntaxKind::Bang => "!",
+        SyntaxKind::EqualsEquals => "==",
+        SyntaxKind::BangEquals => "!=",
+        SyntaxKind::LT => "<",
+        SyntaxKind::LTE => "<=",
+        SyntaxKind::GT => ">",
+        SyntaxKind::GTE => ">=",
+        SyntaxKind::AmpersandAmpersand => "&&",
+        SyntaxKind::PipePipe => "||",
+        _ => return None,
+    };
+
+    Some(lexeme)
+}
+
+/// Every keyword along with its kind, looked up by scanning since there are only a few of them
+const KEYWORDS: [(&str, SyntaxKind); 12] = [
+    ("let", SyntaxKind::LetKeyword),
+    ("fn", SyntaxKind::FnKeyword),
+    ("return", SyntaxKind::ReturnKeyword),
+    ("if", SyntaxKind::IfKeyword),
+    ("else", SyntaxKind::ElseKeyword),
+    ("while", SyntaxKind::WhileKeyword),
+    ("for", SyntaxKind::ForKeyword),
+    ("in", SyntaxKind::InKeyword),
+    ("break", SyntaxKind::BreakKeyword),
+    ("continue", SyntaxKind::ContinueKeyword),
+    ("import", SyntaxKind::ImportKeyword),
+    ("pub", SyntaxKind::PubKeyword),
+];
+
 /// Given a keyword string, returns the corresponding keyword syntax kind, or None if it was not found
 pub fn get_keyword_kind(keyword: &str) -> Option<SyntaxKind> {
-    let (map, _) = get_keyword_maps();
-    map.get(keyword).copied()
+    KEYWORDS
+        .iter()
+        .find(|(lexeme, _)| *lexeme == keyword)
+        .map(|(_, kind)| *kind)
 }
 
 /// Given a SyntaxKind, returns the corresponding keyword lexeme, or None if it was not found
-pub fn get_keyword_lexeme(kind: &SyntaxKind) -> Option<&str> {
-    let (_, reverse_map) = get_keyword_maps();
-    reverse_map.get(kind).copied()
+pub fn get_keyword_lexeme(kind: &SyntaxKind) -> Option<&'static str> {
+    KEYWORDS
+        .iter()
+        .find(|(_, keyword_kind)| keyword_kind == kind)
+        .map(|(lexeme, _)| *lexeme)
 }
 
-fn get_keyword_maps<'a>() -> (HashMap<&'a str, SyntaxKind>, HashMap<SyntaxKind, &'a str>) {
-    let mut map = HashMap::new();
-    let mut reverse_map = HashMap::new();
-    map.insert("let", SyntaxKind::LetKeyword);
-    map.insert("fn", SyntaxKind::FnKeyword);
-
-    for (key, value) in map.iter() {
-        reverse_map.insert(value.to_owned(), key.to_owned());
-    }
-
-    (map, reverse_map)
+/// Returns every keyword, sorted alphabetically
+pub fn get_keywords() -> Vec<&'static str> {
+    let mut keywords: Vec<&str> = KEYWORDS.iter().map(|(lexeme, _)| *lexeme).collect();
+    keywords.sort();
+    keywords
 }
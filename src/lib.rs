@@ -0,0 +1,22 @@
+#![allow(
+    clippy::unused_unit,
+    clippy::result_large_err,
+    clippy::large_enum_variant
+)]
+
+pub mod cli;
+pub mod compiler;
+pub mod diagnostics;
+pub mod formatter;
+pub mod interpreter;
+pub mod lexer;
+pub mod lsp;
+pub mod modules;
+pub mod parser;
+pub mod repl;
+pub mod resolver;
+pub mod tests;
+pub mod typeck;
+pub mod vm;
+
+pub mod source;
@@ -0,0 +1,420 @@
+use std::collections::HashSet;
+
+use serde_json::{json, Value};
+
+use super::path_to_uri;
+use crate::{
+    diagnostics::{Diagnostic, Severity},
+    lexer::{syntax::Span, syntax_facts},
+    modules::loader::{Loader, Reader},
+    parser::ast::{
+        base::Identifier,
+        statements::{Program, StatementNode},
+    },
+    resolver::{
+        symbols::{ScopeKind, SymbolKind, SymbolTable},
+        Resolver,
+    },
+    source::{FileId, SourceFile, SourceMap},
+    typeck::{types::Type, TypeChecker},
+};
+
+// the kinds of symbols and completion items defined by the protocol
+const SYMBOL_FUNCTION: u32 = 12;
+const SYMBOL_VARIABLE: u32 = 13;
+const COMPLETION_FUNCTION: u32 = 3;
+const COMPLETION_VARIABLE: u32 = 6;
+const COMPLETION_KEYWORD: u32 = 14;
+
+/// Everything the language server knows about an open document
+///
+/// The document is parsed with error recovery, so names and types are available even while it
+/// does not parse. Its imports are only loaded once it does, as the module loader requires
+pub struct Analysis {
+    uri: String,
+    file: SourceFile,
+    program: Program,
+    diagnostics: Vec<Diagnostic>,
+    symbols: SymbolTable,
+    checker: TypeChecker,
+    /// The files loaded along with the document, which declare the names it imports
+    sources: SourceMap,
+}
+
+impl Analysis {
+    /// Analyzes `file`, reading the modules it imports through `reader`
+    pub fn new(uri: &str, file: SourceFile, reader: Reader) -> Self {
+        let (program, mut diagnostics) = file.parse_partial();
+        let mut loader = Loader::with_reader(reader);
+        let mut resolver = Resolver::new();
+        let checker = TypeChecker::new();
+
+        if diagnostics.is_empty() {
+            let entry = SourceFile::from_string(file.absolute_path.clone(), file.source());
+            match loader.load(entry) {
+                Ok(graph) => {
+                    if let Err(errors) = graph.check() {
+                        diagnostics.extend(errors);
+                    }
+
+                    // the imported modules declare their globals before the document runs
+                    for module in graph.modules().iter().filter(|m| m.file != file.id()) {
+                        let _ = checker.check(&module.program);
+                    }
+                    resolver = graph.resolver_for(graph.entry());
+                }
+                Err(errors) => diagnostics.extend(errors),
+            }
+
+            // errors in imported files are reported when they are opened themselves
+            diagnostics.retain(|diagnostic| diagnostic.span.file == file.id());
+        }
+
+        // with syntax errors, only the syntax errors are reported, like `ion check` does
+        let _ = resolver.resolve(&program);
+        let _ = checker.check(&program);
+
+        Self {
+            uri: uri.to_string(),
+            file,
+            program,
+            diagnostics,
+            symbols: resolver.into_symbol_table(),
+            checker,
+            sources: loader.into_sources(),
+        }
+    }
+
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Converts a protocol position, counted in UTF-16 code units, into a byte offset
+    pub fn offset(&self, position: &Value) -> Option<usize> {
+        let line = position.get("line")?.as_u64()? as usize;
+        let character = position.get("character")?.as_u64()? as usize;
+        if line >= self.file.line_count() {
+            return None;
+        }
+
+        let start = self.file.line_start(line);
+        let mut units = 0;
+        let mut offset = start;
+        for char in self.file.source()[start..].chars() {
+            if units >= character || char == '\n' {
+                break;
+            }
+
+            units += char.len_utf16();
+            offset += char.len_utf8();
+        }
+
+        Some(offset)
+    }
+
+    /// Converts every diagnostic into the protocol's representation
+    pub fn diagnostics_json(&self) -> Value {
+        let diagnostics: Vec<Value> = self
+            .diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let mut message = diagnostic.message.clone();
+                for note in diagnostic.notes.iter() {
+                    message += &format!("\nnote: {}", note);
+                }
+                if let Some(help) = &diagnostic.help {
+                    message += &format!("\nhelp: {}", help);
+                }
+
+                let related: Vec<Value> = diagnostic
+                    .labels
+                    .iter()
+                    .filter_map(|label| {
+                        Some(json!({
+                            "location": self.location(label.span)?,
+                            "message": label.message,
+                        }))
+                    })
+                    .collect();
+
+                let severity = match diagnostic.severity {
+                    Severity::Error => 1,
+                    Severity::Warning => 2,
+                };
+
+                json!({
+                    "range": self.range(diagnostic.span),
+                    "severity": severity,
+                    "code": diagnostic.code.to_string(),
+                    "source": "ion",
+                    "message": message,
+                    "relatedInformation": related,
+                })
+            })
+            .collect();
+
+        Value::Array(diagnostics)
+    }
+
+    /// Describes the name at `offset` along with its type
+    pub fn hover(&self, offset: usize) -> Value {
+        let Some((span, id)) = self.symbols.name_at(self.file.id(), offset) else {
+            return Value::Null;
+        };
+
+        let symbol = self.symbols.symbol(id);
+        let ty = self.checker.type_of_name(&span).unwrap_or(Type::Unknown);
+        let signature = match symbol.kind {
+            SymbolKind::Variable => format!("let {}: {}", symbol.name, ty),
+            SymbolKind::Parameter => format!("{}: {}", symbol.name, ty),
+            SymbolKind::Function | SymbolKind::Native => match ty {
+                Type::Function(_) => {
+                    let ty = ty.to_string();
+                    format!("fn {}{}", symbol.name, ty.trim_start_matches("fn"))
+                }
+                _ => format!("fn {}", symbol.name),
+            },
+        };
+
+        json!({
+            "contents": {
+                "kind": "markdown",
+                "value": format!("```ion\n{}\n```", signature),
+            },
+            "range": self.range(span),
+        })
+    }
+
+    /// Returns the location of the declaration of the name at `offset`
+    pub fn definition(&self, offset: usize) -> Value {
+        self.symbols
+            .name_at(self.file.id(), offset)
+            .and_then(|(_, id)| self.symbols.symbol(id).span)
+            .and_then(|span| self.location(span))
+            .unwrap_or(Value::Null)
+    }
+
+    /// Returns the location of every use of the name at `offset`
+    pub fn references(&self, offset: usize, include_declaration: bool) -> Value {
+        let Some((_, id)) = self.symbols.name_at(self.file.id(), offset) else {
+            return Value::Null;
+        };
+
+        let mut spans = Vec::new();
+        if include_declaration {
+            spans.extend(self.symbols.symbol(id).span);
+        }
+        spans.extend(
+            self.symbols
+                .references_to(id)
+                .map(|reference| reference.span),
+        );
+
+        let locations = spans
+            .into_iter()
+            .filter_map(|span| self.location(span))
+            .collect();
+        Value::Array(locations)
+    }
+
+    /// Returns the functions and variables declared by the document, nested by function
+    pub fn document_symbols(&self) -> Value {
+        Value::Array(self.declarations(&self.program.statements))
+    }
+
+    /// Returns the keywords along with the names that are in scope at `offset`
+    pub fn completions(&self, offset: usize) -> Value {
+        let mut names = Vec::new();
+        for (_, symbol) in self.symbols.symbols() {
+            let scope = self.symbols.scope(symbol.scope).kind;
+            if matches!(scope, ScopeKind::Prelude | ScopeKind::Global) {
+                names.push((symbol.name.clone(), symbol.kind, symbol.span));
+            }
+        }
+        collect_locals(&self.program.statements, offset, &mut names);
+
+        let mut seen = HashSet::new();
+        let mut items = Vec::new();
+        for (name, kind, span) in names.into_iter().rev() {
+            if !seen.insert(name.clone()) {
+                continue;
+            }
+
+            let kind = match kind {
+                SymbolKind::Function | SymbolKind::Native => COMPLETION_FUNCTION,
+                SymbolKind::Variable | SymbolKind::Parameter => COMPLETION_VARIABLE,
+            };
+            let mut item = json!({ "label": name, "kind": kind });
+            if let Some(ty) = span.and_then(|span| self.checker.type_of_name(&span)) {
+                item["detail"] = json!(ty.to_string());
+            }
+            items.push(item);
+        }
+        items.sort_by(|a, b| a["label"].as_str().cmp(&b["label"].as_str()));
+
+        for keyword in syntax_facts::get_keywords() {
+            items.push(json!({ "label": keyword, "kind": COMPLETION_KEYWORD }));
+        }
+
+        Value::Array(items)
+    }
+
+    fn declarations(&self, statements: &[StatementNode]) -> Vec<Value> {
+        let mut symbols = Vec::new();
+        for statement in statements.iter() {
+            match statement {
+                StatementNode::Let(let_statement) => symbols.push(self.document_symbol(
+                    &let_statement.name,
+                    SYMBOL_VARIABLE,
+                    statement.span(),
+                    Vec::new(),
+                )),
+                StatementNode::Function(function) => {
+                    let children = self.declarations(&function.body.statements);
+                    symbols.push(self.document_symbol(
+                        &function.name,
+                        SYMBOL_FUNCTION,
+                        statement.span(),
+                        children,
+                    ));
+                }
+                StatementNode::Block(block) => {
+                    symbols.extend(self.declarations(&block.statements));
+                }
+                StatementNode::If(if_statement) => {
+                    symbols.extend(self.declarations(&if_statement.then_branch.statements));
+                    if let Some(else_branch) = &if_statement.else_branch {
+                        symbols.extend(self.declarations(std::slice::from_ref(else_branch)));
+                    }
+                }
+                StatementNode::While(while_statement) => {
+                    symbols.extend(self.declarations(&while_statement.body.statements));
+                }
+                StatementNode::For(for_statement) => {
+                    symbols.extend(self.declarations(&for_statement.body.statements));
+                }
+                _ => (),
+            }
+        }
+
+        symbols
+    }
+
+    fn document_symbol(
+        &self,
+        name: &Identifier,
+        kind: u32,
+        span: Span,
+        children: Vec<Value>,
+    ) -> Value {
+        let mut symbol = json!({
+            "name": name.text,
+            "kind": kind,
+            "range": self.range(span),
+            "selectionRange": self.range(name.span),
+            "children": children,
+        });
+        if let Some(ty) = self.checker.type_of_name(&name.span) {
+            symbol["detail"] = json!(ty.to_string());
+        }
+
+        symbol
+    }
+
+    /// Returns the file `file` refers to, which is either the document or one it imports
+    fn source(&self, file: FileId) -> Option<&SourceFile> {
+        if file == self.file.id() {
+            return Some(&self.file);
+        }
+
+        self.sources.files().find(|source| source.id() == file)
+    }
+
+    fn location(&self, span: Span) -> Option<Value> {
+        let source = self.source(span.file)?;
+        let uri = match span.file == self.file.id() {
+            true => self.uri.clone(),
+            false => path_to_uri(&source.absolute_path),
+        };
+
+        Some(json!({ "uri": uri, "range": range_in(source, span) }))
+    }
+
+    fn range(&self, span: Span) -> Value {
+        range_in(&self.file, span)
+    }
+}
+
+fn range_in(file: &SourceFile, span: Span) -> Value {
+    json!({
+        "start": position_in(file, span.start as usize),
+        "end": position_in(file, span.end as usize),
+    })
+}
+
+/// Converts a byte offset into a protocol position, counted in UTF-16 code units
+fn position_in(file: &SourceFile, offset: usize) -> Value {
+    let location = file.location(offset);
+    let start = file.line_start(location.line - 1);
+    let character: usize = file.source()[start..offset]
+        .chars()
+        .map(char::len_utf16)
+        .sum();
+
+    json!({ "line": location.line - 1, "character": character })
+}
+
+/// Collects the local names declared before `offset` by the functions and blocks enclosing it
+fn collect_locals(
+    statements: &[StatementNode],
+    offset: usize,
+    names: &mut Vec<(String, SymbolKind, Option<Span>)>,
+) -> () {
+    let mut declare = |name: &Identifier, kind: SymbolKind| {
+        names.push((name.text.clone(), kind, Some(name.span)));
+    };
+
+    for statement in statements.iter() {
+        let span = statement.span();
+        if (span.end as usize) <= offset {
+            match statement {
+                StatementNode::Let(let_statement) => {
+                    declare(&let_statement.name, SymbolKind::Variable)
+                }
+                StatementNode::Function(function) => declare(&function.name, SymbolKind::Function),
+                _ => (),
+            }
+            continue;
+        }
+
+        if !span.contains(offset) {
+            continue;
+        }
+
+        match statement {
+            StatementNode::Function(function) => {
+                declare(&function.name, SymbolKind::Function);
+                for parameter in function.parameters.iter() {
+                    declare(&parameter.name, SymbolKind::Parameter);
+                }
+                collect_locals(&function.body.statements, offset, names);
+            }
+            StatementNode::Block(block) => collect_locals(&block.statements, offset, names),
+            StatementNode::If(if_statement) => {
+                collect_locals(&if_statement.then_branch.statements, offset, names);
+                if let Some(else_branch) = &if_statement.else_branch {
+                    collect_locals(std::slice::from_ref(else_branch), offset, names);
+                }
+            }
+            StatementNode::While(while_statement) => {
+                collect_locals(&while_statement.body.statements, offset, names)
+            }
+            StatementNode::For(for_statement) => {
+                declare(&for_statement.variable, SymbolKind::Variable);
+                collect_locals(&for_statement.body.statements, offset, names);
+            }
+            _ => (),
+        }
+        break;
+    }
+}
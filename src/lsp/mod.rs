@@ -0,0 +1,271 @@
+pub mod analysis;
+pub mod transport;
+
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, Write},
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use serde_json::{json, Value};
+
+use crate::{
+    cli::{EXIT_FAILURE, EXIT_SUCCESS},
+    source::SourceFile,
+};
+use analysis::Analysis;
+
+// error codes defined by JSON-RPC and the protocol
+const INVALID_PARAMS: i64 = -32602;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_REQUEST: i64 = -32600;
+
+/// A language server speaking the Language Server Protocol over a pair of streams
+///
+/// Documents are synchronized in full on every change, after which they are analyzed again and
+/// their diagnostics are published. See `Analysis` for the requests that are answered
+pub struct Server<W: Write> {
+    output: W,
+    documents: HashMap<String, Document>,
+    shutdown_requested: bool,
+}
+
+struct Document {
+    text: String,
+    analysis: Analysis,
+}
+
+impl<W: Write> Server<W> {
+    pub fn new(output: W) -> Self {
+        Self {
+            output,
+            documents: HashMap::new(),
+            shutdown_requested: false,
+        }
+    }
+
+    /// Answers messages from `input` until the client exits, returning the exit code
+    pub fn run(mut self, input: &mut impl BufRead) -> i32 {
+        loop {
+            let message = match transport::read_message(input) {
+                Ok(Some(message)) => message,
+                Ok(None) => return EXIT_FAILURE,
+                Err(error) if error.kind() == io::ErrorKind::InvalidData => {
+                    let error = json!({ "code": INVALID_REQUEST, "message": error.to_string() });
+                    self.send(json!({ "jsonrpc": "2.0", "id": null, "error": error }));
+                    continue;
+                }
+                Err(_) => return EXIT_FAILURE,
+            };
+
+            let method = message["method"].as_str().unwrap_or_default();
+            if method == "exit" {
+                return match self.shutdown_requested {
+                    true => EXIT_SUCCESS,
+                    false => EXIT_FAILURE,
+                };
+            }
+
+            match message.get("id") {
+                Some(id) => {
+                    let response = match self.handle_request(method, &message["params"]) {
+                        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                        Err((code, error)) => json!({
+                            "jsonrpc": "2.0",
+                            "id": id,
+                            "error": { "code": code, "message": error },
+                        }),
+                    };
+                    self.send(response);
+                }
+                None => self.handle_notification(method, &message["params"]),
+            }
+        }
+    }
+
+    fn handle_request(&mut self, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+        match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                    "referencesProvider": true,
+                    "documentSymbolProvider": true,
+                    "completionProvider": {},
+                },
+                "serverInfo": { "name": "ion", "version": env!("CARGO_PKG_VERSION") },
+            })),
+            "shutdown" => {
+                self.shutdown_requested = true;
+                Ok(Value::Null)
+            }
+            "textDocument/hover" => {
+                let (analysis, offset) = self.locate(params)?;
+                Ok(analysis.hover(offset))
+            }
+            "textDocument/definition" => {
+                let (analysis, offset) = self.locate(params)?;
+                Ok(analysis.definition(offset))
+            }
+            "textDocument/references" => {
+                let (analysis, offset) = self.locate(params)?;
+                let include = params["context"]["includeDeclaration"].as_bool();
+                Ok(analysis.references(offset, include.unwrap_or(false)))
+            }
+            "textDocument/documentSymbol" => {
+                let analysis = self.analysis(params)?;
+                Ok(analysis.document_symbols())
+            }
+            "textDocument/completion" => {
+                let (analysis, offset) = self.locate(params)?;
+                Ok(analysis.completions(offset))
+            }
+            _ => Err((METHOD_NOT_FOUND, format!("unknown method '{}'", method))),
+        }
+    }
+
+    /// Handles a message that expects no response, ignoring the ones that are not supported
+    fn handle_notification(&mut self, method: &str, params: &Value) -> () {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        match method {
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                self.update(uri, text.to_string());
+            }
+            "textDocument/didChange" => {
+                // changes are synchronized in full, so the last one holds the whole text
+                let changes = params["contentChanges"].as_array();
+                let text = changes
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str());
+                if let Some(text) = text {
+                    self.update(uri, text.to_string());
+                }
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(uri);
+                self.publish_diagnostics(uri, json!([]));
+            }
+            _ => (),
+        }
+    }
+
+    /// Analyzes the new text of a document, along with every other open document since they
+    /// may import it
+    fn update(&mut self, uri: &str, text: String) -> () {
+        let analysis = self.analyze(uri, &text);
+        self.documents
+            .insert(uri.to_string(), Document { text, analysis });
+
+        let others: Vec<String> = self
+            .documents
+            .keys()
+            .filter(|other| *other != uri)
+            .cloned()
+            .collect();
+        for other in others.iter() {
+            let text = self.documents[other].text.clone();
+            let analysis = self.analyze(other, &text);
+            self.documents.get_mut(other).unwrap().analysis = analysis;
+        }
+
+        self.publish_diagnostics(uri, self.documents[uri].analysis.diagnostics_json());
+        for other in others.iter() {
+            self.publish_diagnostics(other, self.documents[other].analysis.diagnostics_json());
+        }
+    }
+
+    /// Analyzes a document, reading the modules it imports from the open documents first
+    fn analyze(&self, uri: &str, text: &str) -> Analysis {
+        let open: HashMap<PathBuf, String> = self
+            .documents
+            .iter()
+            .map(|(uri, document)| (uri_to_path(uri), document.text.clone()))
+            .collect();
+        let open = Rc::new(open);
+
+        let reader = Box::new(move |path: &Path| match open.get(path) {
+            Some(text) => Ok(SourceFile::from_string(
+                path.to_string_lossy(),
+                text.clone(),
+            )),
+            None => SourceFile::open(path),
+        });
+
+        let path = uri_to_path(uri).to_string_lossy().into_owned();
+        Analysis::new(uri, SourceFile::from_string(path, text), reader)
+    }
+
+    fn publish_diagnostics(&mut self, uri: &str, diagnostics: Value) -> () {
+        self.send(json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diagnostics },
+        }));
+    }
+
+    fn analysis(&self, params: &Value) -> Result<&Analysis, (i64, String)> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        match self.documents.get(uri) {
+            Some(document) => Ok(&document.analysis),
+            None => Err((INVALID_PARAMS, format!("'{}' is not open", uri))),
+        }
+    }
+
+    /// Returns the document a request is about, along with the offset it points at
+    fn locate(&self, params: &Value) -> Result<(&Analysis, usize), (i64, String)> {
+        let analysis = self.analysis(params)?;
+        match analysis.offset(&params["position"]) {
+            Some(offset) => Ok((analysis, offset)),
+            None => Err((INVALID_PARAMS, "invalid position".to_string())),
+        }
+    }
+
+    fn send(&mut self, message: Value) -> () {
+        // the client going away is noticed when reading its next message
+        let _ = transport::write_message(&mut self.output, &message);
+    }
+}
+
+/// Converts a `file://` URI into a path, decoding escaped characters
+pub fn uri_to_path(uri: &str) -> PathBuf {
+    let path = uri.strip_prefix("file://").unwrap_or(uri);
+
+    let mut bytes = Vec::new();
+    let mut rest = path.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let escaped = tail
+            .get(..2)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(decoded) if byte == b'%' => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            _ => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+
+    PathBuf::from(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Converts an absolute path into a `file://` URI, escaping the characters URIs reserve
+pub fn path_to_uri(path: &str) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => {
+                uri.push(byte as char)
+            }
+            _ => uri += &format!("%{:02X}", byte),
+        }
+    }
+
+    uri
+}
@@ -1,17 +1,8 @@
-pub mod lexer;
-pub mod parser;
-pub mod tests;
+use std::env;
 
-pub mod source;
-pub mod utility;
-
-use source::SourceFile;
+use ion::cli::Cli;
 
 fn main() {
-    let executable_dir = utility::get_executable_dir();
-    let file_path = executable_dir.join("test.ion");
-    let file = SourceFile::new(file_path.to_str().unwrap());
-    let ast = file.parse();
-
-    println!("{:#?}", ast);
+    let args: Vec<String> = env::args().skip(1).collect();
+    std::process::exit(Cli::new().run(&args));
 }
@@ -0,0 +1,184 @@
+use std::{
+    io,
+    path::{Component, Path, PathBuf},
+};
+
+use super::{Module, ModuleGraph};
+use crate::{
+    diagnostics::{Diagnostic, DiagnosticCode},
+    parser::ast::statements::{ImportStatement, StatementNode},
+    source::{FileId, SourceFile, SourceMap},
+};
+
+/// Reads the file at the given path, see `Loader::with_reader`
+pub type Reader = Box<dyn Fn(&Path) -> io::Result<SourceFile>>;
+
+/// Loads a file along with every module it transitively imports
+///
+/// Imported paths are resolved relative to the directory of the importing file, and every
+/// file is loaded once no matter how many modules import it
+pub struct Loader {
+    sources: SourceMap,
+    reader: Reader,
+    /// Loaded modules, each one after the modules it imports
+    modules: Vec<Module>,
+    /// The chain of imports leading to the module being loaded, used to detect cycles
+    stack: Vec<FileId>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Default for Loader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Loader {
+    /// Creates a loader reading imported files from disk
+    pub fn new() -> Self {
+        Self::with_reader(Box::new(|path| SourceFile::open(path)))
+    }
+
+    /// Creates a loader reading imported files through `reader`, e.g. from editor buffers
+    pub fn with_reader(reader: Reader) -> Self {
+        Self {
+            sources: SourceMap::new(),
+            reader,
+            modules: Vec::new(),
+            stack: Vec::new(),
+            diagnostics: Vec::new(),
+        }
+    }
+
+    /// Every file read so far, including the ones that failed to parse
+    pub fn sources(&self) -> &SourceMap {
+        &self.sources
+    }
+
+    pub fn into_sources(self) -> SourceMap {
+        self.sources
+    }
+
+    /// Loads `entry` and its imports, returning every diagnostic of every file on failure
+    pub fn load(&mut self, entry: SourceFile) -> Result<ModuleGraph, Vec<Diagnostic>> {
+        let entry = self.sources.add(entry);
+        self.load_module(entry);
+
+        let modules = std::mem::take(&mut self.modules);
+        let diagnostics = std::mem::take(&mut self.diagnostics);
+        if diagnostics.is_empty() {
+            Ok(ModuleGraph::new(modules))
+        } else {
+            Err(diagnostics)
+        }
+    }
+
+    fn load_module(&mut self, file: FileId) -> () {
+        let program = match self.sources.get(file).parse() {
+            Ok(program) => program,
+            Err(diagnostics) => return self.diagnostics.extend(diagnostics),
+        };
+
+        self.stack.push(file);
+        let mut imports = Vec::new();
+        for statement in program.statements.iter() {
+            let StatementNode::Import(import) = statement else {
+                continue;
+            };
+
+            if let Some(imported) = self.load_import(file, import) {
+                if !imports.contains(&imported) {
+                    imports.push(imported);
+                }
+            }
+        }
+        self.stack.pop();
+
+        self.modules.push(Module {
+            file,
+            program,
+            imports,
+        });
+    }
+
+    /// Loads the module imported by `import` unless it was loaded already, returning its id
+    fn load_import(&mut self, importer: FileId, import: &ImportStatement) -> Option<FileId> {
+        let directory = Path::new(&self.sources.get(importer).absolute_path)
+            .parent()
+            .unwrap_or(Path::new(""));
+        let path = normalize(&directory.join(import.path.to_path()));
+
+        if let Some(file) = self.sources.find(&path.to_string_lossy()) {
+            return self.check_cycle(file, import);
+        }
+
+        let file = match (self.reader)(&path) {
+            Ok(file) => file,
+            Err(error) => {
+                self.diagnostics.push(Diagnostic::error(
+                    DiagnosticCode::UnresolvedImport,
+                    format!("could not read '{}': {}", path.display(), error),
+                    import.path.span(),
+                ));
+                return None;
+            }
+        };
+
+        // the reader may name the file differently, e.g. by resolving symbolic links
+        if let Some(file) = self.sources.find(&file.absolute_path) {
+            return self.check_cycle(file, import);
+        }
+
+        let file = self.sources.add(file);
+        self.load_module(file);
+        Some(file)
+    }
+
+    /// Reports `import` if it refers back to a module whose imports are still being loaded
+    fn check_cycle(&mut self, file: FileId, import: &ImportStatement) -> Option<FileId> {
+        let Some(start) = self.stack.iter().position(|loading| *loading == file) else {
+            return Some(file);
+        };
+
+        let mut cycle: Vec<&str> = self.stack[start..]
+            .iter()
+            .map(|file| self.sources.get(*file).relative_path.as_str())
+            .collect();
+        cycle.push(&self.sources.get(file).relative_path);
+
+        let diagnostic = Diagnostic::error(
+            DiagnosticCode::ImportCycle,
+            format!("importing {} forms a cycle", import.path),
+            import.path.span(),
+        )
+        .with_note(format!("the cycle is {}", cycle.join(" -> ")));
+        self.diagnostics.push(diagnostic);
+
+        None
+    }
+}
+
+/// Removes `.` and `..` components without touching the file system, so the same file is
+/// always named the same way
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir => {
+                let can_pop = matches!(
+                    normalized.components().next_back(),
+                    Some(Component::Normal(_))
+                );
+                if can_pop {
+                    normalized.pop();
+                } else {
+                    normalized.push(component);
+                }
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    normalized
+}
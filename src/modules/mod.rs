@@ -0,0 +1,313 @@
+pub mod loader;
+
+use std::collections::HashMap;
+
+use crate::{
+    diagnostics::{Diagnostic, DiagnosticCode},
+    lexer::syntax::Span,
+    parser::ast::{
+        base::Identifier,
+        expressions::{ExpressionNode, InterpolationPart},
+        statements::{Program, StatementNode},
+    },
+    resolver::{
+        symbols::{ScopeKind, SymbolKind},
+        Resolver,
+    },
+    source::FileId,
+    typeck::TypeChecker,
+};
+
+/// A parsed file along with the modules it imports
+#[derive(Debug, Clone)]
+pub struct Module {
+    pub file: FileId,
+    pub program: Program,
+    /// The modules named by the imports of this one, without duplicates
+    pub imports: Vec<FileId>,
+}
+
+impl Module {
+    /// Returns the name, kind and visibility of every top-level declaration
+    pub fn declarations(&self) -> impl Iterator<Item = (&Identifier, SymbolKind, bool)> {
+        self.program
+            .statements
+            .iter()
+            .filter_map(|statement| match statement {
+                StatementNode::Let(let_statement) => Some((
+                    &let_statement.name,
+                    SymbolKind::Variable,
+                    let_statement.visibility.is_some(),
+                )),
+                StatementNode::Function(function) => Some((
+                    &function.name,
+                    SymbolKind::Function,
+                    function.visibility.is_some(),
+                )),
+                _ => None,
+            })
+    }
+}
+
+/// Every module of a program, ordered so that each module comes after the modules it imports
+///
+/// Modules share a single set of globals once linked, so an exported name may only be declared
+/// by one module, while private names are renamed apart by `link`. Visibility is enforced
+/// statically: a module only sees the `pub` declarations of the modules it imports directly
+#[derive(Debug, Clone)]
+pub struct ModuleGraph {
+    modules: Vec<Module>,
+}
+
+impl ModuleGraph {
+    pub fn new(modules: Vec<Module>) -> Self {
+        Self { modules }
+    }
+
+    pub fn modules(&self) -> &[Module] {
+        &self.modules
+    }
+
+    /// The module that was loaded first, which imports every other module
+    pub fn entry(&self) -> &Module {
+        self.modules.last().unwrap()
+    }
+
+    pub fn module(&self, file: FileId) -> Option<&Module> {
+        self.modules.iter().find(|module| module.file == file)
+    }
+
+    /// Resolves and type checks every module against the declarations it imports
+    pub fn check(&self) -> Result<(), Vec<Diagnostic>> {
+        let mut diagnostics = self.check_global_names();
+        if !diagnostics.is_empty() {
+            return Err(diagnostics);
+        }
+
+        let checker = TypeChecker::new();
+        for module in self.modules.iter() {
+            let resolver = self.resolver_for(module);
+            if let Err(errors) = resolver.resolve(&module.program) {
+                diagnostics.extend(errors);
+                continue;
+            }
+
+            if let Err(errors) = checker.check(&module.program) {
+                diagnostics.extend(errors);
+            }
+        }
+
+        if diagnostics.is_empty() {
+            Ok(())
+        } else {
+            Err(diagnostics)
+        }
+    }
+
+    /// Creates a resolver seeing the public declarations of the modules `module` imports
+    pub fn resolver_for(&self, module: &Module) -> Resolver {
+        let resolver = Resolver::new();
+        for import in module.imports.iter() {
+            let Some(imported) = self.module(*import) else {
+                continue;
+            };
+
+            for (name, kind, public) in imported.declarations() {
+                if public {
+                    resolver.import(name, kind);
+                } else {
+                    resolver.hide(name);
+                }
+            }
+        }
+
+        resolver
+    }
+
+    /// Joins every module into a single program, running the top-level code of each module
+    /// once, after the modules it imports
+    ///
+    /// The private top-level names of imported modules are suffixed with `@` and the index of
+    /// their file, which no identifier can contain, so that they cannot clash with the names of
+    /// other modules. Renamed identifiers keep their source text, which is what users see
+    pub fn link(&self) -> Program {
+        let entry = self.entry().file;
+        let statements = self
+            .modules
+            .iter()
+            .flat_map(|module| {
+                let renames = if module.file == entry {
+                    HashMap::new()
+                } else {
+                    self.private_names(module)
+                };
+                module
+                    .program
+                    .statements
+                    .iter()
+                    .filter(|statement| !matches!(statement, StatementNode::Import(_)))
+                    .cloned()
+                    .map(move |mut statement| {
+                        rename_statement(&mut statement, &renames);
+                        statement
+                    })
+            })
+            .collect();
+
+        Program { statements }
+    }
+
+    /// Maps the declarations of and references to the private top-level names of `module` to
+    /// their name once linked
+    fn private_names(&self, module: &Module) -> HashMap<Span, String> {
+        let private: Vec<Span> = module
+            .declarations()
+            .filter(|(_, _, public)| !public)
+            .map(|(name, _, _)| name.span)
+            .collect();
+
+        let resolver = self.resolver_for(module);
+        let _ = resolver.resolve(&module.program);
+        let table = resolver.into_symbol_table();
+
+        let mut renames = HashMap::new();
+        for (id, symbol) in table.symbols() {
+            let Some(span) = symbol.span.filter(|span| private.contains(span)) else {
+                continue;
+            };
+            if table.scope(symbol.scope).kind != ScopeKind::Global {
+                continue;
+            }
+
+            let name = format!("{}@{}", symbol.name, module.file.index());
+            for reference in table.references_to(id) {
+                renames.insert(reference.span, name.clone());
+            }
+            renames.insert(span, name);
+        }
+
+        renames
+    }
+
+    /// Reports exported names declared by more than one module
+    fn check_global_names(&self) -> Vec<Diagnostic> {
+        let mut declared: HashMap<&str, (FileId, &Identifier)> = HashMap::new();
+        let mut diagnostics = Vec::new();
+
+        for module in self.modules.iter() {
+            for (name, _, _) in module.declarations().filter(|(_, _, public)| *public) {
+                match declared.get(name.text.as_str()) {
+                    Some((file, previous)) if *file != module.file => diagnostics.push(
+                        Diagnostic::error(
+                            DiagnosticCode::DuplicateDeclaration,
+                            format!("'{}' is already declared by another module", name.text),
+                            name.span,
+                        )
+                        .with_label(previous.span, "previously declared here")
+                        .with_note("modules share their exported names once linked"),
+                    ),
+                    Some(_) => (),
+                    None => {
+                        declared.insert(&name.text, (module.file, name));
+                    }
+                }
+            }
+        }
+
+        diagnostics
+    }
+}
+
+fn rename_statement(statement: &mut StatementNode, renames: &HashMap<Span, String>) -> () {
+    if renames.is_empty() {
+        return;
+    }
+
+    match statement {
+        StatementNode::Let(let_statement) => {
+            rename(&mut let_statement.name, renames);
+            rename_expression(&mut let_statement.initializer, renames);
+        }
+        StatementNode::Expression(expression_statement) => {
+            rename_expression(&mut expression_statement.expression, renames)
+        }
+        StatementNode::Block(block) => {
+            for statement in block.statements.iter_mut() {
+                rename_statement(statement, renames);
+            }
+        }
+        StatementNode::Function(function) => {
+            rename(&mut function.name, renames);
+            for statement in function.body.statements.iter_mut() {
+                rename_statement(statement, renames);
+            }
+        }
+        StatementNode::Return(return_statement) => {
+            if let Some(value) = &mut return_statement.value {
+                rename_expression(value, renames);
+            }
+        }
+        StatementNode::If(if_statement) => {
+            rename_expression(&mut if_statement.condition, renames);
+            for statement in if_statement.then_branch.statements.iter_mut() {
+                rename_statement(statement, renames);
+            }
+            if let Some(else_branch) = &mut if_statement.else_branch {
+                rename_statement(else_branch, renames);
+            }
+        }
+        StatementNode::While(while_statement) => {
+            rename_expression(&mut while_statement.condition, renames);
+            for statement in while_statement.body.statements.iter_mut() {
+                rename_statement(statement, renames);
+            }
+        }
+        StatementNode::For(for_statement) => {
+            rename_expression(&mut for_statement.iterable, renames);
+            for statement in for_statement.body.statements.iter_mut() {
+                rename_statement(statement, renames);
+            }
+        }
+        StatementNode::Break(_)
+        | StatementNode::Continue(_)
+        | StatementNode::Import(_)
+        | StatementNode::Error(_) => (),
+    }
+}
+
+fn rename_expression(expression: &mut ExpressionNode, renames: &HashMap<Span, String>) -> () {
+    match expression {
+        ExpressionNode::Interpolation(interpolation) => {
+            for part in interpolation.parts.iter_mut() {
+                if let InterpolationPart::Expression(expression) = part {
+                    rename_expression(expression, renames);
+                }
+            }
+        }
+        ExpressionNode::Binary(binary) => {
+            rename_expression(&mut binary.left, renames);
+            rename_expression(&mut binary.right, renames);
+        }
+        ExpressionNode::Unary(unary) => rename_expression(&mut unary.operand, renames),
+        ExpressionNode::Grouping(grouping) => rename_expression(&mut grouping.expression, renames),
+        ExpressionNode::Variable(variable) => rename(&mut variable.name, renames),
+        ExpressionNode::Assignment(assignment) => {
+            rename(&mut assignment.name, renames);
+            rename_expression(&mut assignment.value, renames);
+        }
+        ExpressionNode::Call(call) => {
+            rename_expression(&mut call.callee, renames);
+            for argument in call.arguments.iter_mut() {
+                rename_expression(argument, renames);
+            }
+        }
+        ExpressionNode::Literal(_) | ExpressionNode::Error(_) => (),
+    }
+}
+
+fn rename(name: &mut Identifier, renames: &HashMap<Span, String>) -> () {
+    if let Some(renamed) = renames.get(&name.span) {
+        let text = std::mem::replace(&mut name.text, renamed.clone());
+        name.source_text = Some(text);
+    }
+}
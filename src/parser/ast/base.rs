@@ -1,11 +1,31 @@
 use std::fmt::Debug;
 
-use super::visitor::{ExpressionVisitor, StatementVisitor, SyntaxNodeVisitor};
+use super::visitor::{ExpressionVisitor, StatementVisitor};
+use crate::lexer::syntax::Span;
 
-pub trait SyntaxNode: Debug + Clone {
-    fn accept<R>(&self, visitor: &dyn SyntaxNodeVisitor<R>) -> R;
+/// A name in the syntax tree, such as a variable, a parameter or a type
+///
+/// Unlike tokens, names hold their text, since the tree outlives the source it was parsed from
+/// once functions are stored in values
+#[derive(Debug, Clone, PartialEq)]
+pub struct Identifier {
+    pub text: String,
+    pub span: Span,
+    /// The name as written in the source when linking renamed it, see `ModuleGraph::link`
+    pub source_text: Option<String>,
 }
 
+impl Identifier {
+    /// Returns the name to show to users, which is the one written in the source even once the
+    /// name was renamed by linking
+    pub fn display_name(&self) -> &str {
+        self.source_text.as_deref().unwrap_or(&self.text)
+    }
+}
+
+/// A node of the syntax tree, visited through `Expression::accept` or `Statement::accept`
+pub trait SyntaxNode: Debug + Clone {}
+
 pub trait Expression: SyntaxNode {
     fn accept<R>(&self, visitor: &dyn ExpressionVisitor<R>) -> R;
 }
@@ -1,23 +1,197 @@
-use crate::lexer::syntax::Token;
+use crate::lexer::syntax::{Span, Token};
 
 use super::{
-    base::{Expression, SyntaxNode},
-    visitor::{ExpressionVisitor, SyntaxNodeVisitor},
+    base::{Expression, Identifier, SyntaxNode},
+    visitor::ExpressionVisitor,
 };
 
+/// Any expression node, allowing expressions to nest without knowing each other's concrete type
 #[derive(Debug, Clone)]
-pub struct Literal {
-    pub token: Token,
+pub enum ExpressionNode {
+    Literal(Literal),
+    Interpolation(Interpolation),
+    Binary(Binary),
+    Unary(Unary),
+    Grouping(Grouping),
+    Variable(Variable),
+    Assignment(Assignment),
+    Call(Call),
+    Error(ErrorExpression),
 }
 
-impl SyntaxNode for Literal {
-    fn accept<R>(&self, _visitor: &dyn SyntaxNodeVisitor<R>) -> R {
-        todo!()
+impl ExpressionNode {
+    pub fn span(&self) -> Span {
+        match self {
+            ExpressionNode::Literal(literal) => literal.token.span,
+            ExpressionNode::Interpolation(interpolation) => interpolation.token.span,
+            ExpressionNode::Binary(binary) => binary.left.span().to(&binary.right.span()),
+            ExpressionNode::Unary(unary) => unary.operator.span.to(&unary.operand.span()),
+            ExpressionNode::Grouping(grouping) => grouping.span,
+            ExpressionNode::Variable(variable) => variable.name.span,
+            ExpressionNode::Assignment(assignment) => {
+                assignment.name.span.to(&assignment.value.span())
+            }
+            ExpressionNode::Call(call) => call.span,
+            ExpressionNode::Error(error) => error.span,
+        }
     }
 }
 
+impl SyntaxNode for ExpressionNode {}
+
+impl Expression for ExpressionNode {
+    fn accept<R>(&self, visitor: &dyn ExpressionVisitor<R>) -> R {
+        match self {
+            ExpressionNode::Literal(literal) => Expression::accept(literal, visitor),
+            ExpressionNode::Interpolation(interpolation) => {
+                Expression::accept(interpolation, visitor)
+            }
+            ExpressionNode::Binary(binary) => Expression::accept(binary, visitor),
+            ExpressionNode::Unary(unary) => Expression::accept(unary, visitor),
+            ExpressionNode::Grouping(grouping) => Expression::accept(grouping, visitor),
+            ExpressionNode::Variable(variable) => Expression::accept(variable, visitor),
+            ExpressionNode::Assignment(assignment) => Expression::accept(assignment, visitor),
+            ExpressionNode::Call(call) => Expression::accept(call, visitor),
+            ExpressionNode::Error(error) => Expression::accept(error, visitor),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Literal {
+    pub token: Token,
+}
+
+impl SyntaxNode for Literal {}
+
 impl Expression for Literal {
     fn accept<R>(&self, visitor: &dyn ExpressionVisitor<R>) -> R {
         visitor.visit_literal(self)
     }
 }
+
+/// A string with `{...}` interpolations, e.g. `"hello {name}"`, parsed from the parts of its
+/// token
+#[derive(Debug, Clone)]
+pub struct Interpolation {
+    pub token: Token,
+    pub parts: Vec<InterpolationPart>,
+}
+
+#[derive(Debug, Clone)]
+pub enum InterpolationPart {
+    Text(String),
+    Expression(ExpressionNode),
+}
+
+impl SyntaxNode for Interpolation {}
+
+impl Expression for Interpolation {
+    fn accept<R>(&self, visitor: &dyn ExpressionVisitor<R>) -> R {
+        visitor.visit_interpolation(self)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Binary {
+    pub left: Box<ExpressionNode>,
+    pub operator: Token,
+    pub right: Box<ExpressionNode>,
+}
+
+impl SyntaxNode for Binary {}
+
+impl Expression for Binary {
+    fn accept<R>(&self, visitor: &dyn ExpressionVisitor<R>) -> R {
+        visitor.visit_binary(self)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Unary {
+    pub operator: Token,
+    pub operand: Box<ExpressionNode>,
+}
+
+impl SyntaxNode for Unary {}
+
+impl Expression for Unary {
+    fn accept<R>(&self, visitor: &dyn ExpressionVisitor<R>) -> R {
+        visitor.visit_unary(self)
+    }
+}
+
+/// A parenthesized expression, kept as a node so tools can reproduce the original grouping
+#[derive(Debug, Clone)]
+pub struct Grouping {
+    pub expression: Box<ExpressionNode>,
+    pub span: Span,
+}
+
+impl SyntaxNode for Grouping {}
+
+impl Expression for Grouping {
+    fn accept<R>(&self, visitor: &dyn ExpressionVisitor<R>) -> R {
+        visitor.visit_grouping(self)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Variable {
+    pub name: Identifier,
+}
+
+impl SyntaxNode for Variable {}
+
+impl Expression for Variable {
+    fn accept<R>(&self, visitor: &dyn ExpressionVisitor<R>) -> R {
+        visitor.visit_variable(self)
+    }
+}
+
+/// Assigns a new value to an existing binding, evaluating to the assigned value
+#[derive(Debug, Clone)]
+pub struct Assignment {
+    pub name: Identifier,
+    pub value: Box<ExpressionNode>,
+}
+
+impl SyntaxNode for Assignment {}
+
+impl Expression for Assignment {
+    fn accept<R>(&self, visitor: &dyn ExpressionVisitor<R>) -> R {
+        visitor.visit_assignment(self)
+    }
+}
+
+/// Calls `callee` with the given arguments, e.g. `f(x, y)`
+#[derive(Debug, Clone)]
+pub struct Call {
+    pub callee: Box<ExpressionNode>,
+    pub arguments: Vec<ExpressionNode>,
+    pub span: Span,
+}
+
+impl SyntaxNode for Call {}
+
+impl Expression for Call {
+    fn accept<R>(&self, visitor: &dyn ExpressionVisitor<R>) -> R {
+        visitor.visit_call(self)
+    }
+}
+
+/// Stands in for an expression that could not be parsed, see `Parser::parse_partial`
+///
+/// The span is empty when the expression is missing altogether, e.g. in `let a =`
+#[derive(Debug, Clone)]
+pub struct ErrorExpression {
+    pub span: Span,
+}
+
+impl SyntaxNode for ErrorExpression {}
+
+impl Expression for ErrorExpression {
+    fn accept<R>(&self, visitor: &dyn ExpressionVisitor<R>) -> R {
+        visitor.visit_error_expression(self)
+    }
+}
@@ -1,4 +1,6 @@
 pub mod base;
 pub mod expressions;
+pub mod printer;
 pub mod statements;
+pub mod types;
 pub mod visitor;
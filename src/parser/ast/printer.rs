@@ -0,0 +1,223 @@
+use crate::{lexer::syntax::Token, source::SourceFile};
+
+use super::{
+    base::{Expression, Statement},
+    expressions::{
+        Assignment, Binary, Call, ErrorExpression, Grouping, Interpolation, InterpolationPart,
+        Literal, Unary, Variable,
+    },
+    statements::{
+        BlockStatement, BreakStatement, ContinueStatement, ErrorStatement, ExpressionStatement,
+        ForStatement, FunctionDeclaration, IfStatement, ImportStatement, LetStatement, Program,
+        ReturnStatement, WhileStatement,
+    },
+    visitor::{ExpressionVisitor, StatementVisitor, SyntaxNodeVisitor},
+};
+
+/// Prints nodes as fully parenthesized s-expressions, making precedence explicit
+///
+/// Literals and operators are printed as written in `file`, which the nodes were parsed from
+pub struct Printer<'a> {
+    file: &'a SourceFile,
+}
+
+impl<'a> Printer<'a> {
+    pub fn new(file: &'a SourceFile) -> Self {
+        Self { file }
+    }
+
+    /// Prints every statement of `program` on its own line
+    pub fn print_program(&self, program: &Program) -> String {
+        let statements: Vec<String> = program
+            .statements
+            .iter()
+            .map(|statement| Statement::accept(statement, self))
+            .collect();
+
+        statements.join("\n")
+    }
+}
+
+impl SyntaxNodeVisitor<String> for Printer<'_> {}
+
+impl ExpressionVisitor<String> for Printer<'_> {
+    fn visit_literal(&self, literal: &Literal) -> String {
+        self.file.text(literal.token.span).to_string()
+    }
+
+    fn visit_interpolation(&self, interpolation: &Interpolation) -> String {
+        let parts: Vec<String> = interpolation
+            .parts
+            .iter()
+            .map(|part| match part {
+                InterpolationPart::Text(text) => format!("{:?}", text),
+                InterpolationPart::Expression(expression) => Expression::accept(expression, self),
+            })
+            .collect();
+
+        format!("(interpolate {})", parts.join(" "))
+    }
+
+    fn visit_binary(&self, binary: &Binary) -> String {
+        format!(
+            "({} {} {})",
+            self.file.text(binary.operator.span),
+            Expression::accept(binary.left.as_ref(), self),
+            Expression::accept(binary.right.as_ref(), self)
+        )
+    }
+
+    fn visit_unary(&self, unary: &Unary) -> String {
+        format!(
+            "({} {})",
+            self.file.text(unary.operator.span),
+            Expression::accept(unary.operand.as_ref(), self)
+        )
+    }
+
+    fn visit_grouping(&self, grouping: &Grouping) -> String {
+        format!(
+            "(group {})",
+            Expression::accept(grouping.expression.as_ref(), self)
+        )
+    }
+
+    fn visit_variable(&self, variable: &Variable) -> String {
+        variable.name.text.clone()
+    }
+
+    fn visit_assignment(&self, assignment: &Assignment) -> String {
+        format!(
+            "(= {} {})",
+            assignment.name.text,
+            Expression::accept(assignment.value.as_ref(), self)
+        )
+    }
+
+    fn visit_call(&self, call: &Call) -> String {
+        let mut parts = vec![Expression::accept(call.callee.as_ref(), self)];
+        for argument in call.arguments.iter() {
+            parts.push(Expression::accept(argument, self));
+        }
+
+        format!("(call {})", parts.join(" "))
+    }
+
+    fn visit_error_expression(&self, _error: &ErrorExpression) -> String {
+        "(error)".to_string()
+    }
+}
+
+impl StatementVisitor<String> for Printer<'_> {
+    fn visit_let_statement(&self, let_statement: &LetStatement) -> String {
+        let type_annotation = match &let_statement.type_annotation {
+            Some(annotation) => format!(": {}", annotation),
+            None => String::new(),
+        };
+
+        format!(
+            "({}let {}{} {})",
+            visibility(&let_statement.visibility),
+            let_statement.name.text,
+            type_annotation,
+            Expression::accept(&let_statement.initializer, self)
+        )
+    }
+
+    fn visit_expression_statement(&self, expression_statement: &ExpressionStatement) -> String {
+        Expression::accept(&expression_statement.expression, self)
+    }
+
+    fn visit_block_statement(&self, block: &BlockStatement) -> String {
+        let statements: Vec<String> = block
+            .statements
+            .iter()
+            .map(|statement| Statement::accept(statement, self))
+            .collect();
+
+        format!("{{{}}}", statements.join(" "))
+    }
+
+    fn visit_function_declaration(&self, function: &FunctionDeclaration) -> String {
+        let parameters: Vec<String> = function
+            .parameters
+            .iter()
+            .map(|parameter| format!("{}: {}", parameter.name.text, parameter.type_annotation))
+            .collect();
+        let return_type = match &function.return_type {
+            Some(annotation) => format!(": {}", annotation),
+            None => String::new(),
+        };
+
+        format!(
+            "({}fn {}({}){} {})",
+            visibility(&function.visibility),
+            function.name.text,
+            parameters.join(", "),
+            return_type,
+            self.visit_block_statement(&function.body)
+        )
+    }
+
+    fn visit_return_statement(&self, return_statement: &ReturnStatement) -> String {
+        match &return_statement.value {
+            Some(value) => format!("(return {})", Expression::accept(value, self)),
+            None => "(return)".to_string(),
+        }
+    }
+
+    fn visit_if_statement(&self, if_statement: &IfStatement) -> String {
+        let condition = Expression::accept(&if_statement.condition, self);
+        let then_branch = self.visit_block_statement(&if_statement.then_branch);
+
+        match &if_statement.else_branch {
+            Some(else_branch) => format!(
+                "(if {} {} {})",
+                condition,
+                then_branch,
+                Statement::accept(else_branch.as_ref(), self)
+            ),
+            None => format!("(if {} {})", condition, then_branch),
+        }
+    }
+
+    fn visit_while_statement(&self, while_statement: &WhileStatement) -> String {
+        format!(
+            "(while {} {})",
+            Expression::accept(&while_statement.condition, self),
+            self.visit_block_statement(&while_statement.body)
+        )
+    }
+
+    fn visit_for_statement(&self, for_statement: &ForStatement) -> String {
+        format!(
+            "(for {} {} {})",
+            for_statement.variable.text,
+            Expression::accept(&for_statement.iterable, self),
+            self.visit_block_statement(&for_statement.body)
+        )
+    }
+
+    fn visit_break_statement(&self, _break_statement: &BreakStatement) -> String {
+        "(break)".to_string()
+    }
+
+    fn visit_continue_statement(&self, _continue_statement: &ContinueStatement) -> String {
+        "(continue)".to_string()
+    }
+
+    fn visit_import_statement(&self, import: &ImportStatement) -> String {
+        format!("(import {})", import.path)
+    }
+
+    fn visit_error_statement(&self, _error: &ErrorStatement) -> String {
+        "(error)".to_string()
+    }
+}
+
+fn visibility(visibility: &Option<Token>) -> &'static str {
+    match visibility {
+        Some(_) => "pub ",
+        None => "",
+    }
+}
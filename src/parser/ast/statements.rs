@@ -1 +1,343 @@
+use std::{fmt, path::PathBuf};
 
+use crate::lexer::syntax::{LiteralValue, Span, Token};
+
+use super::{
+    base::{Identifier, Statement, SyntaxNode},
+    expressions::ExpressionNode,
+    types::TypeAnnotation,
+    visitor::StatementVisitor,
+};
+
+/// The root of every parsed source file
+#[derive(Debug, Clone)]
+pub struct Program {
+    pub statements: Vec<StatementNode>,
+}
+
+/// Any statement node, allowing statements to nest without knowing each other's concrete type
+#[derive(Debug, Clone)]
+pub enum StatementNode {
+    Let(LetStatement),
+    Expression(ExpressionStatement),
+    Block(BlockStatement),
+    Function(FunctionDeclaration),
+    Return(ReturnStatement),
+    If(IfStatement),
+    While(WhileStatement),
+    For(ForStatement),
+    Break(BreakStatement),
+    Continue(ContinueStatement),
+    Import(ImportStatement),
+    Error(ErrorStatement),
+}
+
+impl StatementNode {
+    pub fn span(&self) -> Span {
+        match self {
+            StatementNode::Let(let_statement) => let_statement
+                .visibility
+                .as_ref()
+                .unwrap_or(&let_statement.keyword)
+                .span
+                .to(&let_statement.initializer.span()),
+            StatementNode::Expression(expression_statement) => {
+                expression_statement.expression.span()
+            }
+            StatementNode::Block(block) => block.span,
+            StatementNode::Function(function) => function
+                .visibility
+                .as_ref()
+                .unwrap_or(&function.keyword)
+                .span
+                .to(&function.body.span),
+            StatementNode::Return(return_statement) => match &return_statement.value {
+                Some(value) => return_statement.keyword.span.to(&value.span()),
+                None => return_statement.keyword.span,
+            },
+            StatementNode::If(if_statement) => match &if_statement.else_branch {
+                Some(else_branch) => if_statement.keyword.span.to(&else_branch.span()),
+                None => if_statement.keyword.span.to(&if_statement.then_branch.span),
+            },
+            StatementNode::While(while_statement) => {
+                while_statement.keyword.span.to(&while_statement.body.span)
+            }
+            StatementNode::For(for_statement) => {
+                for_statement.keyword.span.to(&for_statement.body.span)
+            }
+            StatementNode::Break(break_statement) => break_statement.keyword.span,
+            StatementNode::Continue(continue_statement) => continue_statement.keyword.span,
+            StatementNode::Import(import) => import.keyword.span.to(&import.path.span()),
+            StatementNode::Error(error) => error.span,
+        }
+    }
+}
+
+impl SyntaxNode for StatementNode {}
+
+impl Statement for StatementNode {
+    fn accept<R>(&self, visitor: &dyn StatementVisitor<R>) -> R {
+        match self {
+            StatementNode::Let(let_statement) => Statement::accept(let_statement, visitor),
+            StatementNode::Expression(expression_statement) => {
+                Statement::accept(expression_statement, visitor)
+            }
+            StatementNode::Block(block) => Statement::accept(block, visitor),
+            StatementNode::Function(function) => Statement::accept(function, visitor),
+            StatementNode::Return(return_statement) => Statement::accept(return_statement, visitor),
+            StatementNode::If(if_statement) => Statement::accept(if_statement, visitor),
+            StatementNode::While(while_statement) => Statement::accept(while_statement, visitor),
+            StatementNode::For(for_statement) => Statement::accept(for_statement, visitor),
+            StatementNode::Break(break_statement) => Statement::accept(break_statement, visitor),
+            StatementNode::Continue(continue_statement) => {
+                Statement::accept(continue_statement, visitor)
+            }
+            StatementNode::Import(import) => Statement::accept(import, visitor),
+            StatementNode::Error(error) => Statement::accept(error, visitor),
+        }
+    }
+}
+
+/// Declares a new binding, e.g. `let name: Type = expr`
+///
+/// Top-level bindings may be exported to importing files with `pub let`
+#[derive(Debug, Clone)]
+pub struct LetStatement {
+    pub visibility: Option<Token>,
+    pub keyword: Token,
+    pub name: Identifier,
+    pub type_annotation: Option<TypeAnnotation>,
+    pub initializer: ExpressionNode,
+}
+
+impl SyntaxNode for LetStatement {}
+
+impl Statement for LetStatement {
+    fn accept<R>(&self, visitor: &dyn StatementVisitor<R>) -> R {
+        visitor.visit_let_statement(self)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExpressionStatement {
+    pub expression: ExpressionNode,
+}
+
+impl SyntaxNode for ExpressionStatement {}
+
+impl Statement for ExpressionStatement {
+    fn accept<R>(&self, visitor: &dyn StatementVisitor<R>) -> R {
+        visitor.visit_expression_statement(self)
+    }
+}
+
+/// A braced list of statements introducing a new scope
+#[derive(Debug, Clone)]
+pub struct BlockStatement {
+    pub statements: Vec<StatementNode>,
+    pub span: Span,
+}
+
+impl SyntaxNode for BlockStatement {}
+
+impl Statement for BlockStatement {
+    fn accept<R>(&self, visitor: &dyn StatementVisitor<R>) -> R {
+        visitor.visit_block_statement(self)
+    }
+}
+
+/// Declares a named function, e.g. `fn add(a: int, b: int): int { return a + b }`
+///
+/// The return type may be omitted, in which case the function returns `null`. Top-level functions
+/// may be exported to importing files with `pub fn`
+#[derive(Debug, Clone)]
+pub struct FunctionDeclaration {
+    pub visibility: Option<Token>,
+    pub keyword: Token,
+    pub name: Identifier,
+    pub parameters: Vec<Parameter>,
+    pub return_type: Option<TypeAnnotation>,
+    pub body: BlockStatement,
+}
+
+impl SyntaxNode for FunctionDeclaration {}
+
+impl Statement for FunctionDeclaration {
+    fn accept<R>(&self, visitor: &dyn StatementVisitor<R>) -> R {
+        visitor.visit_function_declaration(self)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Parameter {
+    pub name: Identifier,
+    pub type_annotation: TypeAnnotation,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReturnStatement {
+    pub keyword: Token,
+    pub value: Option<ExpressionNode>,
+}
+
+impl SyntaxNode for ReturnStatement {}
+
+impl Statement for ReturnStatement {
+    fn accept<R>(&self, visitor: &dyn StatementVisitor<R>) -> R {
+        visitor.visit_return_statement(self)
+    }
+}
+
+/// `if condition { ... }`, optionally followed by `else { ... }` or `else if ...`
+///
+/// `else_branch` is either a `Block` or another `If`
+#[derive(Debug, Clone)]
+pub struct IfStatement {
+    pub keyword: Token,
+    pub condition: ExpressionNode,
+    pub then_branch: BlockStatement,
+    pub else_branch: Option<Box<StatementNode>>,
+}
+
+impl SyntaxNode for IfStatement {}
+
+impl Statement for IfStatement {
+    fn accept<R>(&self, visitor: &dyn StatementVisitor<R>) -> R {
+        visitor.visit_if_statement(self)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WhileStatement {
+    pub keyword: Token,
+    pub condition: ExpressionNode,
+    pub body: BlockStatement,
+}
+
+impl SyntaxNode for WhileStatement {}
+
+impl Statement for WhileStatement {
+    fn accept<R>(&self, visitor: &dyn StatementVisitor<R>) -> R {
+        visitor.visit_while_statement(self)
+    }
+}
+
+/// `for variable in iterable { ... }`, binding `variable` to each item in a fresh scope
+#[derive(Debug, Clone)]
+pub struct ForStatement {
+    pub keyword: Token,
+    pub variable: Identifier,
+    pub iterable: ExpressionNode,
+    pub body: BlockStatement,
+}
+
+impl SyntaxNode for ForStatement {}
+
+impl Statement for ForStatement {
+    fn accept<R>(&self, visitor: &dyn StatementVisitor<R>) -> R {
+        visitor.visit_for_statement(self)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BreakStatement {
+    pub keyword: Token,
+}
+
+impl SyntaxNode for BreakStatement {}
+
+impl Statement for BreakStatement {
+    fn accept<R>(&self, visitor: &dyn StatementVisitor<R>) -> R {
+        visitor.visit_break_statement(self)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ContinueStatement {
+    pub keyword: Token,
+}
+
+impl SyntaxNode for ContinueStatement {}
+
+impl Statement for ContinueStatement {
+    fn accept<R>(&self, visitor: &dyn StatementVisitor<R>) -> R {
+        visitor.visit_continue_statement(self)
+    }
+}
+
+/// `import "path/to/module.ion"` or `import module.sub`, making the public declarations of
+/// another file visible in the importing one
+#[derive(Debug, Clone)]
+pub struct ImportStatement {
+    pub keyword: Token,
+    pub path: ImportPath,
+}
+
+impl SyntaxNode for ImportStatement {}
+
+impl Statement for ImportStatement {
+    fn accept<R>(&self, visitor: &dyn StatementVisitor<R>) -> R {
+        visitor.visit_import_statement(self)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ImportPath {
+    /// A string literal holding a path relative to the importing file
+    File(Token),
+    /// Dot separated names, where `module.sub` refers to `module/sub.ion`
+    Module(Vec<Identifier>),
+}
+
+impl ImportPath {
+    pub fn span(&self) -> Span {
+        match self {
+            ImportPath::File(path) => path.span,
+            ImportPath::Module(names) => names[0].span.to(&names[names.len() - 1].span),
+        }
+    }
+
+    /// Returns the path of the imported file, relative to the directory of the importing file
+    pub fn to_path(&self) -> PathBuf {
+        match self {
+            ImportPath::File(path) => match &path.value {
+                Some(LiteralValue::Str(path)) => PathBuf::from(path),
+                _ => unreachable!("file imports are string literals"),
+            },
+            ImportPath::Module(names) => {
+                let mut path: PathBuf = names.iter().map(|name| name.text.as_str()).collect();
+                path.set_extension("ion");
+                path
+            }
+        }
+    }
+}
+
+impl fmt::Display for ImportPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImportPath::File(path) => match &path.value {
+                Some(LiteralValue::Str(path)) => write!(f, "\"{}\"", path),
+                _ => unreachable!("file imports are string literals"),
+            },
+            ImportPath::Module(names) => {
+                let names: Vec<&str> = names.iter().map(|name| name.text.as_str()).collect();
+                write!(f, "{}", names.join("."))
+            }
+        }
+    }
+}
+
+/// Covers the tokens skipped while recovering from a syntax error, see `Parser::parse_partial`
+#[derive(Debug, Clone)]
+pub struct ErrorStatement {
+    pub span: Span,
+}
+
+impl SyntaxNode for ErrorStatement {}
+
+impl Statement for ErrorStatement {
+    fn accept<R>(&self, visitor: &dyn StatementVisitor<R>) -> R {
+        visitor.visit_error_statement(self)
+    }
+}
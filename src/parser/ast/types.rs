@@ -0,0 +1,51 @@
+use std::fmt;
+
+use super::base::Identifier;
+use crate::lexer::syntax::{Span, Token};
+
+/// A type written in the source, e.g. the `int` in `let x: int = 1`
+#[derive(Debug, Clone)]
+pub enum TypeAnnotation {
+    /// A type referred to by its name, such as `int` or `null`
+    Named(Identifier),
+    /// `fn(int, string): bool`, where an omitted return type means `null`
+    Function(FunctionTypeAnnotation),
+}
+
+#[derive(Debug, Clone)]
+pub struct FunctionTypeAnnotation {
+    pub keyword: Token,
+    pub parameters: Vec<TypeAnnotation>,
+    pub return_type: Option<Box<TypeAnnotation>>,
+    pub span: Span,
+}
+
+impl TypeAnnotation {
+    pub fn span(&self) -> Span {
+        match self {
+            TypeAnnotation::Named(name) => name.span,
+            TypeAnnotation::Function(function) => function.span,
+        }
+    }
+}
+
+impl fmt::Display for TypeAnnotation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TypeAnnotation::Named(name) => write!(f, "{}", name.text),
+            TypeAnnotation::Function(function) => {
+                let parameters: Vec<String> = function
+                    .parameters
+                    .iter()
+                    .map(|parameter| parameter.to_string())
+                    .collect();
+                write!(f, "fn({})", parameters.join(", "))?;
+
+                match &function.return_type {
+                    Some(return_type) => write!(f, ": {}", return_type),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
@@ -1,9 +1,40 @@
-use super::expressions::Literal;
+use super::{
+    expressions::{
+        Assignment, Binary, Call, ErrorExpression, Grouping, Interpolation, Literal, Unary,
+        Variable,
+    },
+    statements::{
+        BlockStatement, BreakStatement, ContinueStatement, ErrorStatement, ExpressionStatement,
+        ForStatement, FunctionDeclaration, IfStatement, ImportStatement, LetStatement,
+        ReturnStatement, WhileStatement,
+    },
+};
 
 pub trait SyntaxNodeVisitor<R> {}
 
 pub trait ExpressionVisitor<R>: SyntaxNodeVisitor<R> {
     fn visit_literal(&self, literal: &Literal) -> R;
+    fn visit_interpolation(&self, interpolation: &Interpolation) -> R;
+    fn visit_binary(&self, binary: &Binary) -> R;
+    fn visit_unary(&self, unary: &Unary) -> R;
+    fn visit_grouping(&self, grouping: &Grouping) -> R;
+    fn visit_variable(&self, variable: &Variable) -> R;
+    fn visit_assignment(&self, assignment: &Assignment) -> R;
+    fn visit_call(&self, call: &Call) -> R;
+    fn visit_error_expression(&self, error: &ErrorExpression) -> R;
 }
 
-pub trait StatementVisitor<R>: SyntaxNodeVisitor<R> {}
+pub trait StatementVisitor<R>: SyntaxNodeVisitor<R> {
+    fn visit_let_statement(&self, let_statement: &LetStatement) -> R;
+    fn visit_expression_statement(&self, expression_statement: &ExpressionStatement) -> R;
+    fn visit_block_statement(&self, block: &BlockStatement) -> R;
+    fn visit_function_declaration(&self, function: &FunctionDeclaration) -> R;
+    fn visit_return_statement(&self, return_statement: &ReturnStatement) -> R;
+    fn visit_if_statement(&self, if_statement: &IfStatement) -> R;
+    fn visit_while_statement(&self, while_statement: &WhileStatement) -> R;
+    fn visit_for_statement(&self, for_statement: &ForStatement) -> R;
+    fn visit_break_statement(&self, break_statement: &BreakStatement) -> R;
+    fn visit_continue_statement(&self, continue_statement: &ContinueStatement) -> R;
+    fn visit_import_statement(&self, import: &ImportStatement) -> R;
+    fn visit_error_statement(&self, error: &ErrorStatement) -> R;
+}
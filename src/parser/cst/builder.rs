@@ -0,0 +1,287 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use super::{
+    green::{GreenElement, GreenNode, GreenToken},
+    red::CstNode,
+};
+use crate::{
+    lexer::syntax::{SyntaxKind, Token, TokenStream},
+    parser::ast::{
+        base::{Expression, Statement},
+        expressions::{
+            Assignment, Binary, Call, ErrorExpression, ExpressionNode, Grouping, Interpolation,
+            Literal, Unary, Variable,
+        },
+        statements::{
+            BlockStatement, BreakStatement, ContinueStatement, ErrorStatement, ExpressionStatement,
+            ForStatement, FunctionDeclaration, IfStatement, ImportStatement, LetStatement, Program,
+            ReturnStatement, StatementNode, WhileStatement,
+        },
+        types::TypeAnnotation,
+        visitor::{ExpressionVisitor, StatementVisitor, SyntaxNodeVisitor},
+    },
+    source::SourceFile,
+};
+
+/// Builds the concrete syntax tree of a file from its tokens and its syntax tree
+///
+/// `tokens` must be the tokens `program` was parsed from `file`. Nodes are delimited by the spans of
+/// the syntax tree, and every token goes to the innermost node being built when it is reached,
+/// so punctuation such as parentheses and commas ends up in the node it belongs to
+pub fn build(tokens: &TokenStream, program: &Program, file: &SourceFile) -> CstNode {
+    let builder = CstBuilder {
+        file,
+        tokens: &tokens.tokens,
+        position: Cell::new(0),
+        stack: RefCell::new(Vec::new()),
+    };
+
+    builder.start_node(SyntaxKind::Program, 0);
+    for statement in program.statements.iter() {
+        builder.statement(statement);
+    }
+    builder.tokens_before(u32::MAX);
+    builder.push(GreenElement::Token(Rc::new(GreenToken::end_of_file(
+        &tokens.end_trivia,
+        file,
+    ))));
+    let root = builder.finish_node(u32::MAX);
+
+    CstNode::new_root(root, file.id())
+}
+
+struct CstBuilder<'a> {
+    file: &'a SourceFile,
+    tokens: &'a [Token],
+    /// The index of the first token that was not added to the tree yet
+    position: Cell<usize>,
+    /// The nodes being built, each with the children it has so far
+    stack: RefCell<Vec<(SyntaxKind, Vec<GreenElement>)>>,
+}
+
+impl CstBuilder<'_> {
+    /// Adds the tokens before `start` to the current node, then starts a child node
+    fn start_node(&self, kind: SyntaxKind, start: u32) -> () {
+        self.tokens_before(start);
+        self.stack.borrow_mut().push((kind, Vec::new()));
+    }
+
+    /// Adds the tokens before `end` to the current node, then adds the node to its parent
+    fn finish_node(&self, end: u32) -> Rc<GreenNode> {
+        self.tokens_before(end);
+
+        let (kind, children) = self.stack.borrow_mut().pop().unwrap();
+        let node = Rc::new(GreenNode::new(kind, children));
+        if !self.stack.borrow().is_empty() {
+            self.push(GreenElement::Node(node.clone()));
+        }
+
+        node
+    }
+
+    /// Finishes the current node right after the next token of the given kind
+    fn finish_node_after(&self, kind: SyntaxKind) -> () {
+        while let Some(token) = self.tokens.get(self.position.get()) {
+            self.push_token(token);
+            if token.kind == kind {
+                break;
+            }
+        }
+
+        self.finish_node(0);
+    }
+
+    fn tokens_before(&self, position: u32) -> () {
+        while let Some(token) = self.tokens.get(self.position.get()) {
+            if token.span.start >= position {
+                break;
+            }
+
+            self.push_token(token);
+        }
+    }
+
+    fn push_token(&self, token: &Token) -> () {
+        self.push(GreenElement::Token(Rc::new(GreenToken::new(
+            token, self.file,
+        ))));
+        self.position.set(self.position.get() + 1);
+    }
+
+    fn push(&self, element: GreenElement) -> () {
+        self.stack.borrow_mut().last_mut().unwrap().1.push(element);
+    }
+
+    fn statement(&self, statement: &StatementNode) -> () {
+        let span = statement.span();
+        let kind = match statement {
+            StatementNode::Let(_) => SyntaxKind::LetStatement,
+            StatementNode::Expression(_) => SyntaxKind::ExpressionStatement,
+            StatementNode::Block(_) => SyntaxKind::BlockStatement,
+            StatementNode::Function(_) => SyntaxKind::FunctionDeclaration,
+            StatementNode::Return(_) => SyntaxKind::ReturnStatement,
+            StatementNode::If(_) => SyntaxKind::IfStatement,
+            StatementNode::While(_) => SyntaxKind::WhileStatement,
+            StatementNode::For(_) => SyntaxKind::ForStatement,
+            StatementNode::Break(_) => SyntaxKind::BreakStatement,
+            StatementNode::Continue(_) => SyntaxKind::ContinueStatement,
+            StatementNode::Import(_) => SyntaxKind::ImportStatement,
+            StatementNode::Error(_) => SyntaxKind::Error,
+        };
+
+        self.start_node(kind, span.start);
+        Statement::accept(statement, self);
+        self.finish_node(span.end);
+    }
+
+    fn block(&self, block: &BlockStatement) -> () {
+        self.start_node(SyntaxKind::BlockStatement, block.span.start);
+        self.visit_block_statement(block);
+        self.finish_node(block.span.end);
+    }
+
+    fn expression(&self, expression: &ExpressionNode) -> () {
+        let span = expression.span();
+        let kind = match expression {
+            ExpressionNode::Literal(_) => SyntaxKind::LiteralExpression,
+            ExpressionNode::Interpolation(_) => SyntaxKind::InterpolationExpression,
+            ExpressionNode::Binary(_) => SyntaxKind::BinaryExpression,
+            ExpressionNode::Unary(_) => SyntaxKind::UnaryExpression,
+            ExpressionNode::Grouping(_) => SyntaxKind::GroupingExpression,
+            ExpressionNode::Variable(_) => SyntaxKind::VariableExpression,
+            ExpressionNode::Assignment(_) => SyntaxKind::AssignmentExpression,
+            ExpressionNode::Call(_) => SyntaxKind::CallExpression,
+            ExpressionNode::Error(_) => SyntaxKind::Error,
+        };
+
+        self.start_node(kind, span.start);
+        Expression::accept(expression, self);
+        self.finish_node(span.end);
+    }
+
+    fn type_annotation(&self, annotation: &TypeAnnotation) -> () {
+        let span = annotation.span();
+        match annotation {
+            TypeAnnotation::Named(_) => self.start_node(SyntaxKind::NamedType, span.start),
+            TypeAnnotation::Function(function) => {
+                self.start_node(SyntaxKind::FunctionType, span.start);
+                for parameter in function.parameters.iter() {
+                    self.type_annotation(parameter);
+                }
+                if let Some(return_type) = &function.return_type {
+                    self.type_annotation(return_type);
+                }
+            }
+        }
+        self.finish_node(span.end);
+    }
+}
+
+impl SyntaxNodeVisitor<()> for CstBuilder<'_> {}
+
+impl ExpressionVisitor<()> for CstBuilder<'_> {
+    fn visit_literal(&self, _literal: &Literal) -> () {}
+
+    fn visit_interpolation(&self, _interpolation: &Interpolation) -> () {}
+
+    fn visit_binary(&self, binary: &Binary) -> () {
+        self.expression(&binary.left);
+        self.expression(&binary.right);
+    }
+
+    fn visit_unary(&self, unary: &Unary) -> () {
+        self.expression(&unary.operand);
+    }
+
+    fn visit_grouping(&self, grouping: &Grouping) -> () {
+        self.expression(&grouping.expression);
+    }
+
+    fn visit_variable(&self, _variable: &Variable) -> () {}
+
+    fn visit_assignment(&self, assignment: &Assignment) -> () {
+        self.expression(&assignment.value);
+    }
+
+    fn visit_call(&self, call: &Call) -> () {
+        self.expression(&call.callee);
+
+        self.start_node(SyntaxKind::ArgumentList, call.callee.span().end);
+        for argument in call.arguments.iter() {
+            self.expression(argument);
+        }
+        self.finish_node_after(SyntaxKind::RightParen);
+    }
+
+    fn visit_error_expression(&self, _error: &ErrorExpression) -> () {}
+}
+
+impl StatementVisitor<()> for CstBuilder<'_> {
+    fn visit_let_statement(&self, let_statement: &LetStatement) -> () {
+        if let Some(annotation) = &let_statement.type_annotation {
+            self.type_annotation(annotation);
+        }
+        self.expression(&let_statement.initializer);
+    }
+
+    fn visit_expression_statement(&self, expression_statement: &ExpressionStatement) -> () {
+        self.expression(&expression_statement.expression);
+    }
+
+    fn visit_block_statement(&self, block: &BlockStatement) -> () {
+        for statement in block.statements.iter() {
+            self.statement(statement);
+        }
+    }
+
+    fn visit_function_declaration(&self, function: &FunctionDeclaration) -> () {
+        self.start_node(SyntaxKind::ParameterList, function.name.span.end);
+        for parameter in function.parameters.iter() {
+            let end = parameter.type_annotation.span().end;
+            self.start_node(SyntaxKind::Parameter, parameter.name.span.start);
+            self.type_annotation(&parameter.type_annotation);
+            self.finish_node(end);
+        }
+        self.finish_node_after(SyntaxKind::RightParen);
+
+        if let Some(return_type) = &function.return_type {
+            self.type_annotation(return_type);
+        }
+        self.block(&function.body);
+    }
+
+    fn visit_return_statement(&self, return_statement: &ReturnStatement) -> () {
+        if let Some(value) = &return_statement.value {
+            self.expression(value);
+        }
+    }
+
+    fn visit_if_statement(&self, if_statement: &IfStatement) -> () {
+        self.expression(&if_statement.condition);
+        self.block(&if_statement.then_branch);
+        if let Some(else_branch) = &if_statement.else_branch {
+            self.statement(else_branch);
+        }
+    }
+
+    fn visit_while_statement(&self, while_statement: &WhileStatement) -> () {
+        self.expression(&while_statement.condition);
+        self.block(&while_statement.body);
+    }
+
+    fn visit_for_statement(&self, for_statement: &ForStatement) -> () {
+        self.expression(&for_statement.iterable);
+        self.block(&for_statement.body);
+    }
+
+    fn visit_break_statement(&self, _break_statement: &BreakStatement) -> () {}
+
+    fn visit_continue_statement(&self, _continue_statement: &ContinueStatement) -> () {}
+
+    fn visit_import_statement(&self, _import: &ImportStatement) -> () {}
+
+    fn visit_error_statement(&self, _error: &ErrorStatement) -> () {}
+}
@@ -0,0 +1,174 @@
+use std::rc::Rc;
+
+use crate::{
+    lexer::syntax::{SyntaxKind, Token, Trivia, TriviaKind},
+    source::SourceFile,
+};
+
+/// A piece of trivia without its position in the file
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GreenTrivia {
+    pub kind: TriviaKind,
+    pub text: String,
+}
+
+impl GreenTrivia {
+    /// Creates the green trivia of every piece of trivia lexed from `file`
+    fn all(trivia: &[Trivia], file: &SourceFile) -> Vec<Self> {
+        trivia
+            .iter()
+            .map(|trivia| Self {
+                kind: trivia.kind,
+                text: file.text(trivia.span).to_string(),
+            })
+            .collect()
+    }
+}
+
+/// A token along with its trivia, which does not know where it is in the file
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GreenToken {
+    kind: SyntaxKind,
+    text: String,
+    leading_trivia: Vec<GreenTrivia>,
+    trailing_trivia: Vec<GreenTrivia>,
+}
+
+impl GreenToken {
+    /// Creates the green token of a token lexed from `file`, copying its text out of the file
+    pub fn new(token: &Token, file: &SourceFile) -> Self {
+        Self {
+            kind: token.kind,
+            text: file.text(token.span).to_string(),
+            leading_trivia: GreenTrivia::all(&token.leading_trivia, file),
+            trailing_trivia: GreenTrivia::all(&token.trailing_trivia, file),
+        }
+    }
+
+    /// Creates the empty token holding the trivia after the last token of a file
+    pub fn end_of_file(trivia: &[Trivia], file: &SourceFile) -> Self {
+        Self {
+            kind: SyntaxKind::EndOfFile,
+            text: String::new(),
+            leading_trivia: GreenTrivia::all(trivia, file),
+            trailing_trivia: Vec::new(),
+        }
+    }
+
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn leading_trivia(&self) -> &[GreenTrivia] {
+        &self.leading_trivia
+    }
+
+    pub fn trailing_trivia(&self) -> &[GreenTrivia] {
+        &self.trailing_trivia
+    }
+
+    /// The number of bytes taken by the leading trivia
+    pub fn leading_width(&self) -> usize {
+        trivia_width(&self.leading_trivia)
+    }
+
+    /// The number of bytes taken by the token and its trivia
+    pub fn width(&self) -> usize {
+        self.leading_width() + self.text.len() + trivia_width(&self.trailing_trivia)
+    }
+
+    /// Returns the token's text surrounded by its trivia
+    pub fn full_text(&self) -> String {
+        let mut text = String::new();
+        for trivia in self.leading_trivia.iter() {
+            text.push_str(&trivia.text);
+        }
+        text.push_str(&self.text);
+        for trivia in self.trailing_trivia.iter() {
+            text.push_str(&trivia.text);
+        }
+
+        text
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GreenElement {
+    Node(Rc<GreenNode>),
+    Token(Rc<GreenToken>),
+}
+
+impl GreenElement {
+    pub fn kind(&self) -> SyntaxKind {
+        match self {
+            GreenElement::Node(node) => node.kind(),
+            GreenElement::Token(token) => token.kind(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        match self {
+            GreenElement::Node(node) => node.width(),
+            GreenElement::Token(token) => token.width(),
+        }
+    }
+}
+
+/// An immutable node of the concrete syntax tree, holding its children but neither its parent
+/// nor its position
+///
+/// Green nodes only depend on the text they cover, so identical subtrees can be shared between
+/// trees, e.g. across edits of the same file. See `CstNode` for navigating a tree
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GreenNode {
+    kind: SyntaxKind,
+    width: usize,
+    children: Vec<GreenElement>,
+}
+
+impl GreenNode {
+    pub fn new(kind: SyntaxKind, children: Vec<GreenElement>) -> Self {
+        Self {
+            kind,
+            width: children.iter().map(GreenElement::width).sum(),
+            children,
+        }
+    }
+
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    /// The number of bytes covered by the node, trivia included
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn children(&self) -> &[GreenElement] {
+        &self.children
+    }
+
+    /// Returns the exact source text covered by the node
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        self.write_text(&mut text);
+        text
+    }
+
+    fn write_text(&self, text: &mut String) -> () {
+        for child in self.children.iter() {
+            match child {
+                GreenElement::Node(node) => node.write_text(text),
+                GreenElement::Token(token) => text.push_str(&token.full_text()),
+            }
+        }
+    }
+}
+
+fn trivia_width(trivia: &[GreenTrivia]) -> usize {
+    trivia.iter().map(|trivia| trivia.text.len()).sum()
+}
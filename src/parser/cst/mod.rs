@@ -0,0 +1,4 @@
+pub mod builder;
+pub mod green;
+pub mod nodes;
+pub mod red;
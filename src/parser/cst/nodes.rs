@@ -0,0 +1,385 @@
+use super::red::{CstNode, CstToken};
+use crate::lexer::syntax::SyntaxKind;
+
+/// A typed view over a `CstNode` of a given kind
+///
+/// Accessors return `None` when the tree lacks the child they look for, so they never panic on
+/// incomplete trees
+pub trait TypedNode: Sized {
+    /// Returns the typed view of `node`, or None if the node is of another kind
+    fn cast(node: CstNode) -> Option<Self>;
+
+    fn syntax(&self) -> &CstNode;
+}
+
+/// Declares a typed node wrapping every `CstNode` of the given kind
+macro_rules! typed_nodes {
+    ($($(#[$attribute:meta])* $name:ident => $kind:ident,)*) => {
+        $(
+            $(#[$attribute])*
+            #[derive(Debug, Clone, PartialEq)]
+            pub struct $name(CstNode);
+
+            impl $name {
+                pub const KIND: SyntaxKind = SyntaxKind::$kind;
+            }
+
+            impl TypedNode for $name {
+                fn cast(node: CstNode) -> Option<Self> {
+                    (node.kind() == Self::KIND).then(|| Self(node))
+                }
+
+                fn syntax(&self) -> &CstNode {
+                    &self.0
+                }
+            }
+        )*
+    };
+}
+
+/// Declares an enum over typed nodes, casting a `CstNode` to whichever variant matches its kind
+macro_rules! typed_enum {
+    ($(#[$attribute:meta])* $name:ident { $($variant:ident($node:ident),)* }) => {
+        $(#[$attribute])*
+        #[derive(Debug, Clone, PartialEq)]
+        pub enum $name {
+            $($variant($node),)*
+        }
+
+        impl TypedNode for $name {
+            fn cast(node: CstNode) -> Option<Self> {
+                $(
+                    if node.kind() == $node::KIND {
+                        return Some($name::$variant($node(node)));
+                    }
+                )*
+                None
+            }
+
+            fn syntax(&self) -> &CstNode {
+                match self {
+                    $($name::$variant(node) => node.syntax(),)*
+                }
+            }
+        }
+    };
+}
+
+typed_nodes! {
+    ProgramSyntax => Program,
+    LetStatementSyntax => LetStatement,
+    ExpressionStatementSyntax => ExpressionStatement,
+    BlockStatementSyntax => BlockStatement,
+    FunctionDeclarationSyntax => FunctionDeclaration,
+    ParameterListSyntax => ParameterList,
+    ParameterSyntax => Parameter,
+    ReturnStatementSyntax => ReturnStatement,
+    /// `if condition { ... }`, where the else branch is either a block or another `if`
+    IfStatementSyntax => IfStatement,
+    WhileStatementSyntax => WhileStatement,
+    ForStatementSyntax => ForStatement,
+    BreakStatementSyntax => BreakStatement,
+    ContinueStatementSyntax => ContinueStatement,
+    ImportStatementSyntax => ImportStatement,
+    NamedTypeSyntax => NamedType,
+    FunctionTypeSyntax => FunctionType,
+    LiteralExpressionSyntax => LiteralExpression,
+    /// A string with `{...}` interpolations, which is a single token in the tree
+    InterpolationExpressionSyntax => InterpolationExpression,
+    BinaryExpressionSyntax => BinaryExpression,
+    UnaryExpressionSyntax => UnaryExpression,
+    GroupingExpressionSyntax => GroupingExpression,
+    VariableExpressionSyntax => VariableExpression,
+    AssignmentExpressionSyntax => AssignmentExpression,
+    CallExpressionSyntax => CallExpression,
+    ArgumentListSyntax => ArgumentList,
+    /// Tokens the parser skipped, or an empty node where an expression is missing
+    ErrorSyntax => Error,
+}
+
+typed_enum! {
+    StatementSyntax {
+        Let(LetStatementSyntax),
+        Expression(ExpressionStatementSyntax),
+        Block(BlockStatementSyntax),
+        Function(FunctionDeclarationSyntax),
+        Return(ReturnStatementSyntax),
+        If(IfStatementSyntax),
+        While(WhileStatementSyntax),
+        For(ForStatementSyntax),
+        Break(BreakStatementSyntax),
+        Continue(ContinueStatementSyntax),
+        Import(ImportStatementSyntax),
+        Error(ErrorSyntax),
+    }
+}
+
+typed_enum! {
+    ExpressionSyntax {
+        Literal(LiteralExpressionSyntax),
+        Interpolation(InterpolationExpressionSyntax),
+        Binary(BinaryExpressionSyntax),
+        Unary(UnaryExpressionSyntax),
+        Grouping(GroupingExpressionSyntax),
+        Variable(VariableExpressionSyntax),
+        Assignment(AssignmentExpressionSyntax),
+        Call(CallExpressionSyntax),
+        Error(ErrorSyntax),
+    }
+}
+
+typed_enum! {
+    TypeSyntax {
+        Named(NamedTypeSyntax),
+        Function(FunctionTypeSyntax),
+    }
+}
+
+/// Returns the first child of `node` that casts to `N`
+fn child<N: TypedNode>(node: &CstNode) -> Option<N> {
+    node.children().find_map(N::cast)
+}
+
+/// Returns every child of `node` that casts to `N`
+fn children<N: TypedNode>(node: &CstNode) -> Vec<N> {
+    node.children().filter_map(N::cast).collect()
+}
+
+impl ProgramSyntax {
+    pub fn statements(&self) -> Vec<StatementSyntax> {
+        children(&self.0)
+    }
+}
+
+impl LetStatementSyntax {
+    pub fn visibility(&self) -> Option<CstToken> {
+        self.0.token_of_kind(SyntaxKind::PubKeyword)
+    }
+
+    pub fn name(&self) -> Option<CstToken> {
+        self.0.token_of_kind(SyntaxKind::Identifier)
+    }
+
+    pub fn type_annotation(&self) -> Option<TypeSyntax> {
+        child(&self.0)
+    }
+
+    pub fn initializer(&self) -> Option<ExpressionSyntax> {
+        child(&self.0)
+    }
+}
+
+impl ExpressionStatementSyntax {
+    pub fn expression(&self) -> Option<ExpressionSyntax> {
+        child(&self.0)
+    }
+}
+
+impl BlockStatementSyntax {
+    pub fn statements(&self) -> Vec<StatementSyntax> {
+        children(&self.0)
+    }
+}
+
+impl FunctionDeclarationSyntax {
+    pub fn visibility(&self) -> Option<CstToken> {
+        self.0.token_of_kind(SyntaxKind::PubKeyword)
+    }
+
+    pub fn name(&self) -> Option<CstToken> {
+        self.0.token_of_kind(SyntaxKind::Identifier)
+    }
+
+    pub fn parameter_list(&self) -> Option<ParameterListSyntax> {
+        child(&self.0)
+    }
+
+    pub fn parameters(&self) -> Vec<ParameterSyntax> {
+        match self.parameter_list() {
+            Some(list) => list.parameters(),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn return_type(&self) -> Option<TypeSyntax> {
+        child(&self.0)
+    }
+
+    pub fn body(&self) -> Option<BlockStatementSyntax> {
+        child(&self.0)
+    }
+}
+
+impl ParameterListSyntax {
+    pub fn parameters(&self) -> Vec<ParameterSyntax> {
+        children(&self.0)
+    }
+}
+
+impl ParameterSyntax {
+    pub fn name(&self) -> Option<CstToken> {
+        self.0.token_of_kind(SyntaxKind::Identifier)
+    }
+
+    pub fn type_annotation(&self) -> Option<TypeSyntax> {
+        child(&self.0)
+    }
+}
+
+impl ReturnStatementSyntax {
+    pub fn value(&self) -> Option<ExpressionSyntax> {
+        child(&self.0)
+    }
+}
+
+impl IfStatementSyntax {
+    pub fn condition(&self) -> Option<ExpressionSyntax> {
+        child(&self.0)
+    }
+
+    pub fn then_branch(&self) -> Option<BlockStatementSyntax> {
+        child(&self.0)
+    }
+
+    pub fn else_branch(&self) -> Option<StatementSyntax> {
+        self.0
+            .children_after(SyntaxKind::ElseKeyword)
+            .find_map(StatementSyntax::cast)
+    }
+}
+
+impl WhileStatementSyntax {
+    pub fn condition(&self) -> Option<ExpressionSyntax> {
+        child(&self.0)
+    }
+
+    pub fn body(&self) -> Option<BlockStatementSyntax> {
+        child(&self.0)
+    }
+}
+
+impl ForStatementSyntax {
+    pub fn variable(&self) -> Option<CstToken> {
+        self.0.token_of_kind(SyntaxKind::Identifier)
+    }
+
+    pub fn iterable(&self) -> Option<ExpressionSyntax> {
+        child(&self.0)
+    }
+
+    pub fn body(&self) -> Option<BlockStatementSyntax> {
+        child(&self.0)
+    }
+}
+
+impl ImportStatementSyntax {
+    /// The string literal naming the imported file, for `import "path/to/module.ion"`
+    pub fn file(&self) -> Option<CstToken> {
+        self.0.token_of_kind(SyntaxKind::StringLiteral)
+    }
+
+    /// The dot separated names of the imported module, for `import module.sub`
+    pub fn module_names(&self) -> Vec<CstToken> {
+        self.0
+            .child_tokens()
+            .filter(|token| token.kind() == SyntaxKind::Identifier)
+            .collect()
+    }
+}
+
+impl NamedTypeSyntax {
+    pub fn name(&self) -> Option<CstToken> {
+        self.0.child_tokens().find(|token| !token.is_empty())
+    }
+}
+
+impl FunctionTypeSyntax {
+    pub fn parameters(&self) -> Vec<TypeSyntax> {
+        let return_type = self.return_type();
+        children(&self.0)
+            .into_iter()
+            .filter(|parameter| Some(parameter) != return_type.as_ref())
+            .collect()
+    }
+
+    pub fn return_type(&self) -> Option<TypeSyntax> {
+        self.0
+            .children_after(SyntaxKind::Colon)
+            .find_map(TypeSyntax::cast)
+    }
+}
+
+impl LiteralExpressionSyntax {
+    pub fn token(&self) -> Option<CstToken> {
+        self.0.child_tokens().next()
+    }
+}
+
+impl InterpolationExpressionSyntax {
+    pub fn token(&self) -> Option<CstToken> {
+        self.0.child_tokens().next()
+    }
+}
+
+impl BinaryExpressionSyntax {
+    pub fn left(&self) -> Option<ExpressionSyntax> {
+        child(&self.0)
+    }
+
+    pub fn operator(&self) -> Option<CstToken> {
+        self.0.child_tokens().next()
+    }
+
+    pub fn right(&self) -> Option<ExpressionSyntax> {
+        children(&self.0).into_iter().nth(1)
+    }
+}
+
+impl UnaryExpressionSyntax {
+    pub fn operator(&self) -> Option<CstToken> {
+        self.0.child_tokens().next()
+    }
+
+    pub fn operand(&self) -> Option<ExpressionSyntax> {
+        child(&self.0)
+    }
+}
+
+impl GroupingExpressionSyntax {
+    pub fn expression(&self) -> Option<ExpressionSyntax> {
+        child(&self.0)
+    }
+}
+
+impl VariableExpressionSyntax {
+    pub fn name(&self) -> Option<CstToken> {
+        self.0.token_of_kind(SyntaxKind::Identifier)
+    }
+}
+
+impl AssignmentExpressionSyntax {
+    pub fn name(&self) -> Option<CstToken> {
+        self.0.token_of_kind(SyntaxKind::Identifier)
+    }
+
+    pub fn value(&self) -> Option<ExpressionSyntax> {
+        child(&self.0)
+    }
+}
+
+impl CallExpressionSyntax {
+    pub fn callee(&self) -> Option<ExpressionSyntax> {
+        child(&self.0)
+    }
+
+    pub fn argument_list(&self) -> Option<ArgumentListSyntax> {
+        child(&self.0)
+    }
+
+    pub fn arguments(&self) -> Vec<ExpressionSyntax> {
+        match self.argument_list() {
+            Some(list) => children(&list.0),
+            None => Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,344 @@
+use std::{fmt, rc::Rc};
+
+use super::green::{GreenElement, GreenNode, GreenToken, GreenTrivia};
+use crate::{
+    lexer::syntax::{Span, SyntaxKind},
+    source::FileId,
+};
+
+struct NodeData {
+    green: Rc<GreenNode>,
+    parent: Option<CstNode>,
+    /// The index of the node among the children of its parent
+    index: usize,
+    /// The offset at which the node starts, trivia included
+    offset: usize,
+    file: FileId,
+}
+
+/// A node of the concrete syntax tree along with its position, which knows its parent
+///
+/// Red nodes are created on demand while walking down from the root, and are cheap to clone.
+/// Every character of the source belongs to exactly one token of the tree, so `text` gives back
+/// the exact source the tree was built from
+#[derive(Clone)]
+pub struct CstNode(Rc<NodeData>);
+
+impl CstNode {
+    pub fn new_root(green: Rc<GreenNode>, file: FileId) -> Self {
+        Self(Rc::new(NodeData {
+            green,
+            parent: None,
+            index: 0,
+            offset: 0,
+            file,
+        }))
+    }
+
+    pub fn kind(&self) -> SyntaxKind {
+        self.0.green.kind()
+    }
+
+    pub fn green(&self) -> &Rc<GreenNode> {
+        &self.0.green
+    }
+
+    pub fn file(&self) -> FileId {
+        self.0.file
+    }
+
+    pub fn parent(&self) -> Option<CstNode> {
+        self.0.parent.clone()
+    }
+
+    /// Returns the node itself followed by its parent, its parent's parent, and so on
+    pub fn ancestors(&self) -> impl Iterator<Item = CstNode> {
+        std::iter::successors(Some(self.clone()), CstNode::parent)
+    }
+
+    /// The span covered by the node, trivia included
+    pub fn full_span(&self) -> Span {
+        Span::new(
+            self.0.file,
+            self.0.offset,
+            self.0.offset + self.0.green.width(),
+        )
+    }
+
+    /// The span from the start of the first token to the end of the last one, trivia excluded
+    pub fn span(&self) -> Span {
+        let tokens: Vec<CstToken> = self.tokens().filter(|token| !token.is_empty()).collect();
+        match (tokens.first(), tokens.last()) {
+            (Some(first), Some(last)) => first.span().to(&last.span()),
+            _ => Span::new(self.0.file, self.0.offset, self.0.offset),
+        }
+    }
+
+    /// Returns the exact source text covered by the node, trivia included
+    pub fn text(&self) -> String {
+        self.0.green.text()
+    }
+
+    pub fn children_with_tokens(&self) -> impl Iterator<Item = CstElement> {
+        let node = self.clone();
+        let mut offset = self.0.offset;
+        (0..self.0.green.children().len()).map(move |index| {
+            let child = &node.0.green.children()[index];
+            let element = node.element(child, index, offset);
+            offset += child.width();
+            element
+        })
+    }
+
+    pub fn children(&self) -> impl Iterator<Item = CstNode> {
+        self.children_with_tokens()
+            .filter_map(CstElement::into_node)
+    }
+
+    /// Returns the tokens that are direct children of the node
+    pub fn child_tokens(&self) -> impl Iterator<Item = CstToken> {
+        self.children_with_tokens()
+            .filter_map(CstElement::into_token)
+    }
+
+    pub fn child_of_kind(&self, kind: SyntaxKind) -> Option<CstNode> {
+        self.children().find(|child| child.kind() == kind)
+    }
+
+    pub fn token_of_kind(&self, kind: SyntaxKind) -> Option<CstToken> {
+        self.child_tokens().find(|token| token.kind() == kind)
+    }
+
+    /// Returns the child nodes that come after the first direct token of the given kind
+    pub fn children_after(&self, kind: SyntaxKind) -> impl Iterator<Item = CstNode> {
+        self.children_with_tokens()
+            .skip_while(move |child| child.kind() != kind)
+            .filter_map(CstElement::into_node)
+    }
+
+    /// Returns the node and every node below it, in source order
+    pub fn descendants(&self) -> impl Iterator<Item = CstNode> {
+        let mut stack = vec![self.clone()];
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            let children: Vec<CstNode> = node.children().collect();
+            stack.extend(children.into_iter().rev());
+            Some(node)
+        })
+    }
+
+    /// Returns every token below the node, in source order
+    pub fn tokens(&self) -> impl Iterator<Item = CstToken> {
+        let mut tokens = Vec::new();
+        self.collect_tokens(&mut tokens);
+        tokens.into_iter()
+    }
+
+    fn collect_tokens(&self, tokens: &mut Vec<CstToken>) -> () {
+        for child in self.children_with_tokens() {
+            match child {
+                CstElement::Node(node) => node.collect_tokens(tokens),
+                CstElement::Token(token) => tokens.push(token),
+            }
+        }
+    }
+
+    pub fn first_token(&self) -> Option<CstToken> {
+        self.tokens().next()
+    }
+
+    pub fn last_token(&self) -> Option<CstToken> {
+        self.tokens().last()
+    }
+
+    pub fn next_sibling(&self) -> Option<CstNode> {
+        let index = self.0.index;
+        self.parent()?
+            .children()
+            .find(|child| child.0.index > index)
+    }
+
+    pub fn previous_sibling(&self) -> Option<CstNode> {
+        let index = self.0.index;
+        self.parent()?
+            .children()
+            .take_while(|child| child.0.index < index)
+            .last()
+    }
+
+    /// Returns the token whose text contains `position`, preferring the token starting at
+    /// `position` over the one ending there
+    pub fn token_at(&self, position: usize) -> Option<CstToken> {
+        let mut touching = None;
+        for token in self.tokens() {
+            let span = token.span();
+            if span.range().contains(&position) {
+                return Some(token);
+            }
+            if span.end as usize == position && !token.is_empty() {
+                touching = Some(token);
+            }
+        }
+
+        touching
+    }
+
+    /// Returns the innermost node whose span contains the given span
+    pub fn covering_node(&self, span: Span) -> CstNode {
+        let covers = |node: &CstNode| {
+            let node_span = node.span();
+            node_span.start <= span.start && span.end <= node_span.end
+        };
+
+        let mut node = self.clone();
+        while let Some(child) = node.children().find(covers) {
+            node = child;
+        }
+
+        node
+    }
+
+    /// Returns an indented dump of the tree, with one node or token per line
+    pub fn debug_tree(&self) -> String {
+        let mut output = String::new();
+        self.write_debug_tree(&mut output, 0);
+        output
+    }
+
+    fn write_debug_tree(&self, output: &mut String, depth: usize) -> () {
+        output.push_str(&format!("{}{:?}\n", "  ".repeat(depth), self));
+        for child in self.children_with_tokens() {
+            match child {
+                CstElement::Node(node) => node.write_debug_tree(output, depth + 1),
+                CstElement::Token(token) => {
+                    output.push_str(&format!("{}{:?}\n", "  ".repeat(depth + 1), token))
+                }
+            }
+        }
+    }
+
+    fn element(&self, green: &GreenElement, index: usize, offset: usize) -> CstElement {
+        match green {
+            GreenElement::Node(node) => CstElement::Node(CstNode(Rc::new(NodeData {
+                green: node.clone(),
+                parent: Some(self.clone()),
+                index,
+                offset,
+                file: self.0.file,
+            }))),
+            GreenElement::Token(token) => CstElement::Token(CstToken {
+                green: token.clone(),
+                parent: self.clone(),
+                offset,
+            }),
+        }
+    }
+}
+
+impl PartialEq for CstNode {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0.green, &other.0.green) && self.0.offset == other.0.offset
+    }
+}
+
+impl fmt::Debug for CstNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}@{}", self.kind(), self.full_span())
+    }
+}
+
+/// A token of the concrete syntax tree along with its position and parent
+#[derive(Clone)]
+pub struct CstToken {
+    green: Rc<GreenToken>,
+    parent: CstNode,
+    /// The offset at which the token starts, trivia included
+    offset: usize,
+}
+
+impl CstToken {
+    pub fn kind(&self) -> SyntaxKind {
+        self.green.kind()
+    }
+
+    pub fn text(&self) -> &str {
+        self.green.text()
+    }
+
+    pub fn green(&self) -> &Rc<GreenToken> {
+        &self.green
+    }
+
+    pub fn parent(&self) -> CstNode {
+        self.parent.clone()
+    }
+
+    /// The span of the token's text, trivia excluded
+    pub fn span(&self) -> Span {
+        let start = self.offset + self.green.leading_width();
+        Span::new(self.parent.file(), start, start + self.text().len())
+    }
+
+    /// The span of the token's text and trivia
+    pub fn full_span(&self) -> Span {
+        Span::new(
+            self.parent.file(),
+            self.offset,
+            self.offset + self.green.width(),
+        )
+    }
+
+    pub fn leading_trivia(&self) -> &[GreenTrivia] {
+        self.green.leading_trivia()
+    }
+
+    pub fn trailing_trivia(&self) -> &[GreenTrivia] {
+        self.green.trailing_trivia()
+    }
+
+    /// Whether the token has no text, which is only the case for `SyntaxKind::EndOfFile`
+    pub fn is_empty(&self) -> bool {
+        self.text().is_empty()
+    }
+}
+
+impl PartialEq for CstToken {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.green, &other.green) && self.offset == other.offset
+    }
+}
+
+impl fmt::Debug for CstToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}@{} {:?}", self.kind(), self.span(), self.text())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CstElement {
+    Node(CstNode),
+    Token(CstToken),
+}
+
+impl CstElement {
+    pub fn kind(&self) -> SyntaxKind {
+        match self {
+            CstElement::Node(node) => node.kind(),
+            CstElement::Token(token) => token.kind(),
+        }
+    }
+
+    pub fn into_node(self) -> Option<CstNode> {
+        match self {
+            CstElement::Node(node) => Some(node),
+            CstElement::Token(_) => None,
+        }
+    }
+
+    pub fn into_token(self) -> Option<CstToken> {
+        match self {
+            CstElement::Node(_) => None,
+            CstElement::Token(token) => Some(token),
+        }
+    }
+}
@@ -0,0 +1,726 @@
+use std::collections::HashMap;
+
+use super::{
+    ast::{
+        expressions::{ExpressionNode, InterpolationPart},
+        statements::{BlockStatement, ImportPath, Program, StatementNode},
+        types::TypeAnnotation,
+    },
+    Parser,
+};
+use crate::{
+    diagnostics::Diagnostic,
+    lexer::{
+        syntax::{LiteralValue, Span, StringPart, SyntaxKind, Token, TokenStream, Trivia},
+        Lexer,
+    },
+    source::{SourceFile, TextEdit},
+};
+
+/// What an edit caused to be lexed and parsed again
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reparse {
+    /// The number of tokens that were lexed again
+    pub relexed: usize,
+    /// The number of tokens that were kept, with their spans shifted
+    pub reused: usize,
+    /// The tokens of the statements that were parsed again, in the edited file
+    pub reparsed: Span,
+}
+
+/// A file along with its tokens and its syntax tree, which are kept up to date as it is edited
+///
+/// An edit only relexes the tokens it touches, up to the first token after it that ends where
+/// a token ended before the edit; every later token is reused. The statements covering the
+/// relexed tokens are then parsed again within the innermost block enclosing them, falling
+/// back to an enclosing block, and eventually the whole file, when the edit changes where the
+/// block ends. The result is always the same as parsing the edited file from scratch
+pub struct ParsedFile {
+    file: SourceFile,
+    tokens: Vec<Token>,
+    end_trivia: Vec<Trivia>,
+    lexical_diagnostics: Vec<Diagnostic>,
+    program: Program,
+    syntax_diagnostics: Vec<Diagnostic>,
+}
+
+/// The depths a statement is parsed at, see `Parser`
+#[derive(Debug, Clone, Copy, Default)]
+struct Context {
+    function_depth: usize,
+    loop_depth: usize,
+    block_depth: usize,
+}
+
+/// A list of statements that can be parsed again on its own: the statements of the program,
+/// or of a block reached by following `path`
+struct Unit {
+    /// The index of a statement along with the index of one of its blocks, see `child_blocks`
+    path: Vec<(usize, usize)>,
+    context: Context,
+}
+
+/// The statements parsed again by `ParsedFile::reparse`, replacing `range` of a unit
+struct Replacement {
+    range: std::ops::Range<usize>,
+    statements: Vec<StatementNode>,
+    diagnostics: Vec<Diagnostic>,
+    /// The old byte offsets between which diagnostics are replaced
+    old_start: u32,
+    old_end: u32,
+    /// The new token indices that were parsed
+    tokens: std::ops::Range<usize>,
+}
+
+impl ParsedFile {
+    pub fn new(file: SourceFile) -> Self {
+        let mut tokens = file.tokenize();
+        let lexical_diagnostics = std::mem::take(&mut tokens.diagnostics);
+        let end_trivia = std::mem::take(&mut tokens.end_trivia);
+
+        let mut parser = Parser::new(&file, tokens);
+        let (program, syntax_diagnostics) = parser.parse_partial();
+        let tokens = std::mem::take(&mut parser.tokens.tokens);
+
+        Self {
+            file,
+            tokens,
+            end_trivia,
+            lexical_diagnostics,
+            program,
+            syntax_diagnostics,
+        }
+    }
+
+    pub fn file(&self) -> &SourceFile {
+        &self.file
+    }
+
+    pub fn tokens(&self) -> &[Token] {
+        &self.tokens
+    }
+
+    /// The trivia after the last token, see `TokenStream::end_trivia`
+    pub fn end_trivia(&self) -> &[Trivia] {
+        &self.end_trivia
+    }
+
+    pub fn program(&self) -> &Program {
+        &self.program
+    }
+
+    /// Returns every lexical and syntax diagnostic, in the order a full parse reports them
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        let diagnostics = self.lexical_diagnostics.iter();
+        diagnostics
+            .chain(self.syntax_diagnostics.iter())
+            .cloned()
+            .collect()
+    }
+
+    /// Applies `edit` to the file, updating its tokens and syntax tree
+    pub fn edit(&mut self, edit: &TextEdit) -> Reparse {
+        let file = self.file.with_edit(edit);
+        let (start, end) = (edit.span.start, edit.span.end);
+        let delta = edit.delta();
+        let shift = move |position: u32| match position >= end {
+            true => (position as isize + delta) as u32,
+            false => position,
+        };
+
+        // the token before the first one whose text or trailing trivia reaches the edit, since
+        // its trailing trivia runs up to the next token, and everything after it up to a token
+        // ending where a token used to end, past the edit
+        let first = self
+            .tokens
+            .partition_point(|token| token.full_span().end < start)
+            .saturating_sub(1);
+        let relex_start = match first {
+            0 => 0,
+            _ => self.tokens[first - 1].full_span().end,
+        };
+        let old_ends: HashMap<u32, usize> = self.tokens[first..]
+            .iter()
+            .enumerate()
+            .map(|(index, token)| (token.full_span().end, first + index))
+            .collect();
+
+        let mut synced = None;
+        let edit_end = (start as isize + edit.text.len() as isize) as u32;
+        let mut relexed = Lexer::new(&file).tokenize_from(relex_start as usize, |position| {
+            let position = position as u32;
+            if position < edit_end {
+                return false;
+            }
+
+            synced = old_ends.get(&((position as isize - delta) as u32)).copied();
+            synced.is_some()
+        });
+
+        // the old tokens that were replaced by the relexed ones
+        let last_changed = synced.unwrap_or(self.tokens.len());
+        let relexed_count = relexed.tokens.len();
+        let old_tokens = std::mem::take(&mut self.tokens);
+        let mut reused: Vec<Token> = match synced {
+            Some(last) => old_tokens[last + 1..].to_vec(),
+            None => Vec::new(),
+        };
+        for token in reused.iter_mut() {
+            shift_token(token, &shift);
+        }
+        let reused_count = reused.len();
+
+        let reused_start = synced.map(|last| old_tokens[last].full_span().end);
+        self.lexical_diagnostics.retain(|diagnostic| {
+            diagnostic.span.start < relex_start
+                || reused_start.is_some_and(|reused| diagnostic.span.start >= reused)
+        });
+        for diagnostic in self.lexical_diagnostics.iter_mut() {
+            shift_diagnostic(diagnostic, &shift);
+        }
+        let after = self
+            .lexical_diagnostics
+            .partition_point(|diagnostic| diagnostic.span.start < relex_start);
+        self.lexical_diagnostics
+            .splice(after..after, relexed.diagnostics.drain(..));
+
+        self.end_trivia = match synced {
+            Some(_) => {
+                let mut trivia = std::mem::take(&mut self.end_trivia);
+                for trivia in trivia.iter_mut() {
+                    trivia.span = shift_span(trivia.span, &shift);
+                }
+                trivia
+            }
+            None => relexed.end_trivia,
+        };
+
+        // relexed tokens that came out the same as before only need parsing again if a later
+        // token changed the statement they are part of; tokens only hold spans, so a token
+        // edited in place is told apart by its text
+        let unchanged = relexed
+            .tokens
+            .iter()
+            .zip(&old_tokens[first..last_changed.min(old_tokens.len())])
+            .take_while(|(new, old)| {
+                new == old && new.full_text(&file) == old.full_text(&self.file)
+            })
+            .count();
+
+        let mut tokens = old_tokens[..first].to_vec();
+        tokens.extend(relexed.tokens);
+        tokens.extend(reused);
+
+        let changed = Changed {
+            first: first + unchanged,
+            last: last_changed,
+            relexed: relexed_count - unchanged,
+        };
+        let (unit, replacement, tokens) = self.reparse(&file, &old_tokens, tokens, &changed);
+
+        shift_program(&mut self.program, &shift);
+        let statements = statements_mut(&mut self.program, &unit.path);
+        statements.splice(replacement.range.clone(), replacement.statements);
+
+        let (old_start, old_end) = (replacement.old_start, replacement.old_end);
+        self.syntax_diagnostics.retain(|diagnostic| {
+            diagnostic.span.start < old_start || diagnostic.span.start >= old_end
+        });
+        for diagnostic in self.syntax_diagnostics.iter_mut() {
+            shift_diagnostic(diagnostic, &shift);
+        }
+        let after = self
+            .syntax_diagnostics
+            .partition_point(|diagnostic| diagnostic.span.start < old_start);
+        self.syntax_diagnostics
+            .splice(after..after, replacement.diagnostics);
+
+        let reparsed = match replacement.tokens.is_empty() {
+            true => {
+                let length = file.source().len();
+                let position = tokens
+                    .get(replacement.tokens.start)
+                    .map_or(Span::new(file.id(), length, length), |token| token.span);
+                Span {
+                    end: position.start,
+                    ..position
+                }
+            }
+            false => {
+                let first = tokens[replacement.tokens.start].span;
+                first.to(&tokens[replacement.tokens.end - 1].span)
+            }
+        };
+
+        self.tokens = tokens;
+        self.file = file;
+
+        Reparse {
+            relexed: relexed_count,
+            reused: reused_count,
+            reparsed,
+        }
+    }
+
+    /// Parses again the statements covering the changed tokens, within the innermost block that
+    /// still ends at the same token, returning the unit they belong to along with the tokens
+    fn reparse(
+        &self,
+        file: &SourceFile,
+        old_tokens: &[Token],
+        tokens: Vec<Token>,
+        changed: &Changed,
+    ) -> (Unit, Replacement, Vec<Token>) {
+        let mut units = vec![Unit {
+            path: Vec::new(),
+            context: Context::default(),
+        }];
+        let mut statements = &self.program.statements;
+        'descend: loop {
+            let context = units.last().unwrap().context;
+            for (index, statement) in statements.iter().enumerate() {
+                for (ordinal, (block, inner)) in
+                    child_blocks(statement, context).into_iter().enumerate()
+                {
+                    let Some((open, close)) = block_tokens(old_tokens, block) else {
+                        continue;
+                    };
+                    if open < changed.first && close > changed.last {
+                        let mut path = units.last().unwrap().path.clone();
+                        path.push((index, ordinal));
+                        units.push(Unit {
+                            path,
+                            context: inner,
+                        });
+                        statements = &block.statements;
+                        continue 'descend;
+                    }
+                }
+            }
+            break;
+        }
+
+        let mut parser = Parser::new(file, TokenStream::new(tokens));
+        while let Some(unit) = units.pop() {
+            let replacement = self.reparse_unit(&mut parser, old_tokens, &unit, changed);
+            if let Some(replacement) = replacement {
+                let tokens = std::mem::take(&mut parser.tokens.tokens);
+                return (unit, replacement, tokens);
+            }
+        }
+
+        unreachable!("the program itself can always be parsed again")
+    }
+
+    fn reparse_unit(
+        &self,
+        parser: &mut Parser,
+        old_tokens: &[Token],
+        unit: &Unit,
+        changed: &Changed,
+    ) -> Option<Replacement> {
+        let statements = statements(&self.program, &unit.path);
+        let ranges: Vec<(usize, usize)> = statements
+            .iter()
+            .map(|statement| token_range(old_tokens, statement.span()))
+            .collect();
+
+        // the bounds of the unit, in old token indices
+        let (list_start, list_end) = match unit.path.is_empty() {
+            true => (0, old_tokens.len()),
+            false => {
+                let block = block_at(&self.program, &unit.path);
+                let (open, close) = block_tokens(old_tokens, block)?;
+                if self.reported_near(old_tokens, close) {
+                    return None;
+                }
+                (open + 1, close)
+            }
+        };
+
+        // the statement before the changed ones is parsed again too, since the first changed
+        // token decided where it ends
+        let mut first = ranges
+            .iter()
+            .position(|(_, last)| *last >= changed.first)
+            .unwrap_or(statements.len());
+        first = first.saturating_sub(1);
+        while first > 0
+            && (matches!(statements[first], StatementNode::Error(_))
+                || self.reported_near(old_tokens, ranges[first].0))
+        {
+            first -= 1;
+        }
+        let start = ranges.get(first).map_or(list_start, |(start, _)| *start);
+
+        // a later statement is kept as is when parsing again reaches its first token, unless it
+        // is an error whose diagnostic may depend on the statement before it
+        let sync_points: HashMap<usize, usize> = (first..statements.len())
+            .filter(|index| ranges[*index].0 > changed.last)
+            .filter(|index| !matches!(statements[*index], StatementNode::Error(_)))
+            .filter(|index| !self.reported_near(old_tokens, ranges[*index].0))
+            .map(|index| (changed.map(ranges[index].0), index))
+            .collect();
+        let end = match unit.path.is_empty() {
+            true => parser.tokens.len(),
+            false => changed.map(list_end),
+        };
+
+        parser.tokens.seek(start);
+        parser.function_depth = unit.context.function_depth;
+        parser.loop_depth = unit.context.loop_depth;
+        parser.block_depth = unit.context.block_depth;
+
+        let mut reparsed = Vec::new();
+        let mut synced = None;
+        loop {
+            let position = parser.tokens.position();
+            if let Some(index) = sync_points.get(&position) {
+                synced = Some(*index);
+                break;
+            }
+
+            let block_ended =
+                !unit.path.is_empty() && parser.tokens.check_kind(SyntaxKind::RightBrace);
+            if position >= end || block_ended || parser.tokens.is_finished() {
+                break;
+            }
+
+            reparsed.push(parser.parse_statement_or_recover());
+        }
+
+        let position = parser.tokens.position();
+        if synced.is_none() && position != end {
+            // the block now ends somewhere else, so it has to be parsed as part of its parent
+            parser.diagnostics.clear();
+            return None;
+        }
+
+        let old_start = old_tokens
+            .get(start)
+            .map_or(u32::MAX, |token| token.span.start);
+        let old_end = match synced {
+            Some(index) => old_tokens[ranges[index].0].span.start,
+            None if unit.path.is_empty() => u32::MAX,
+            None => old_tokens[list_end].span.start,
+        };
+
+        Some(Replacement {
+            range: first..synced.unwrap_or(statements.len()),
+            statements: reparsed,
+            diagnostics: std::mem::take(&mut parser.diagnostics),
+            old_start: old_start.min(old_end),
+            old_end,
+            tokens: start..position,
+        })
+    }
+
+    /// Returns whether a syntax diagnostic points at the token at `index` or right before it,
+    /// in which case it may have been reported while parsing either the statement it starts or
+    /// the one before it, so parsing can neither start nor stop there
+    fn reported_near(&self, tokens: &[Token], index: usize) -> bool {
+        let start = match index {
+            0 => 0,
+            _ => tokens[index - 1].span.end,
+        };
+        let end = tokens.get(index).map_or(u32::MAX, |token| token.span.end);
+
+        let mut diagnostics = self.syntax_diagnostics.iter();
+        diagnostics.any(|diagnostic| (start..=end).contains(&diagnostic.span.start))
+    }
+}
+
+/// The old tokens `first..=last` that were replaced by `relexed` new tokens, where `last` is
+/// past the end if every token from `first` on was replaced
+struct Changed {
+    first: usize,
+    last: usize,
+    relexed: usize,
+}
+
+impl Changed {
+    /// Returns the new index of an old token that was not changed
+    fn map(&self, index: usize) -> usize {
+        match index < self.first {
+            true => index,
+            false => index - (self.last + 1) + self.first + self.relexed,
+        }
+    }
+}
+
+/// Returns the indices of the first and last tokens covered by `span`
+fn token_range(tokens: &[Token], span: Span) -> (usize, usize) {
+    let first = tokens.partition_point(|token| token.span.start < span.start);
+    let end = tokens.partition_point(|token| token.span.start < span.end);
+
+    (first, end.max(first + 1) - 1)
+}
+
+/// Returns the indices of the braces of `block`, unless its closing brace is missing
+fn block_tokens(tokens: &[Token], block: &BlockStatement) -> Option<(usize, usize)> {
+    let (open, close) = token_range(tokens, block.span);
+    let closed = tokens.get(close).is_some_and(|token| {
+        token.kind == SyntaxKind::RightBrace && token.span.end == block.span.end
+    });
+
+    closed.then_some((open, close))
+}
+
+/// Returns the blocks a statement directly contains, along with the context of their statements
+fn child_blocks(statement: &StatementNode, context: Context) -> Vec<(&BlockStatement, Context)> {
+    let block = Context {
+        block_depth: context.block_depth + 1,
+        ..context
+    };
+    let function = Context {
+        function_depth: context.function_depth + 1,
+        loop_depth: 0,
+        ..block
+    };
+    let body = Context {
+        loop_depth: context.loop_depth + 1,
+        ..block
+    };
+
+    match statement {
+        StatementNode::Block(statement) => vec![(statement, block)],
+        StatementNode::Function(function_declaration) => {
+            vec![(&function_declaration.body, function)]
+        }
+        StatementNode::If(if_statement) => {
+            let mut blocks = vec![(&if_statement.then_branch, block)];
+            if let Some(else_branch) = &if_statement.else_branch {
+                blocks.extend(child_blocks(else_branch, context));
+            }
+            blocks
+        }
+        StatementNode::While(while_statement) => vec![(&while_statement.body, body)],
+        StatementNode::For(for_statement) => vec![(&for_statement.body, body)],
+        _ => Vec::new(),
+    }
+}
+
+fn child_blocks_mut(statement: &mut StatementNode) -> Vec<&mut BlockStatement> {
+    match statement {
+        StatementNode::Block(block) => vec![block],
+        StatementNode::Function(function) => vec![&mut function.body],
+        StatementNode::If(if_statement) => {
+            let mut blocks = vec![&mut if_statement.then_branch];
+            if let Some(else_branch) = &mut if_statement.else_branch {
+                blocks.extend(child_blocks_mut(else_branch));
+            }
+            blocks
+        }
+        StatementNode::While(while_statement) => vec![&mut while_statement.body],
+        StatementNode::For(for_statement) => vec![&mut for_statement.body],
+        _ => Vec::new(),
+    }
+}
+
+fn block_at<'a>(program: &'a Program, path: &[(usize, usize)]) -> &'a BlockStatement {
+    let (last, parents) = path.split_last().unwrap();
+    let statements = statements(program, parents);
+    let blocks = child_blocks(&statements[last.0], Context::default());
+
+    blocks[last.1].0
+}
+
+fn statements<'a>(program: &'a Program, path: &[(usize, usize)]) -> &'a Vec<StatementNode> {
+    match path.is_empty() {
+        true => &program.statements,
+        false => &block_at(program, path).statements,
+    }
+}
+
+fn statements_mut<'a>(
+    program: &'a mut Program,
+    path: &[(usize, usize)],
+) -> &'a mut Vec<StatementNode> {
+    let mut statements = &mut program.statements;
+    for (index, ordinal) in path.iter() {
+        let block = child_blocks_mut(&mut statements[*index]).swap_remove(*ordinal);
+        statements = &mut block.statements;
+    }
+
+    statements
+}
+
+fn shift_span(span: Span, shift: &impl Fn(u32) -> u32) -> Span {
+    Span {
+        start: shift(span.start),
+        end: shift(span.end),
+        ..span
+    }
+}
+
+fn shift_diagnostic(diagnostic: &mut Diagnostic, shift: &impl Fn(u32) -> u32) -> () {
+    diagnostic.span = shift_span(diagnostic.span, shift);
+    for label in diagnostic.labels.iter_mut() {
+        label.span = shift_span(label.span, shift);
+    }
+}
+
+fn shift_token(token: &mut Token, shift: &impl Fn(u32) -> u32) -> () {
+    token.span = shift_span(token.span, shift);
+    let trivia = token.leading_trivia.iter_mut();
+    for trivia in trivia.chain(token.trailing_trivia.iter_mut()) {
+        trivia.span = shift_span(trivia.span, shift);
+    }
+    if let Some(LiteralValue::Interpolated(parts)) = &mut token.value {
+        for part in parts.iter_mut() {
+            if let StringPart::Expression { tokens, span } = part {
+                *span = shift_span(*span, shift);
+                for token in tokens.iter_mut() {
+                    shift_token(token, shift);
+                }
+            }
+        }
+    }
+}
+
+fn shift_program(program: &mut Program, shift: &impl Fn(u32) -> u32) -> () {
+    for statement in program.statements.iter_mut() {
+        shift_statement(statement, shift);
+    }
+}
+
+fn shift_block(block: &mut BlockStatement, shift: &impl Fn(u32) -> u32) -> () {
+    block.span = shift_span(block.span, shift);
+    for statement in block.statements.iter_mut() {
+        shift_statement(statement, shift);
+    }
+}
+
+fn shift_statement(statement: &mut StatementNode, shift: &impl Fn(u32) -> u32) -> () {
+    match statement {
+        StatementNode::Let(let_statement) => {
+            if let Some(visibility) = &mut let_statement.visibility {
+                shift_token(visibility, shift);
+            }
+            shift_token(&mut let_statement.keyword, shift);
+            let_statement.name.span = shift_span(let_statement.name.span, shift);
+            if let Some(annotation) = &mut let_statement.type_annotation {
+                shift_type(annotation, shift);
+            }
+            shift_expression(&mut let_statement.initializer, shift);
+        }
+        StatementNode::Expression(expression_statement) => {
+            shift_expression(&mut expression_statement.expression, shift)
+        }
+        StatementNode::Block(block) => shift_block(block, shift),
+        StatementNode::Function(function) => {
+            if let Some(visibility) = &mut function.visibility {
+                shift_token(visibility, shift);
+            }
+            shift_token(&mut function.keyword, shift);
+            function.name.span = shift_span(function.name.span, shift);
+            for parameter in function.parameters.iter_mut() {
+                parameter.name.span = shift_span(parameter.name.span, shift);
+                shift_type(&mut parameter.type_annotation, shift);
+            }
+            if let Some(return_type) = &mut function.return_type {
+                shift_type(return_type, shift);
+            }
+            shift_block(&mut function.body, shift);
+        }
+        StatementNode::Return(return_statement) => {
+            shift_token(&mut return_statement.keyword, shift);
+            if let Some(value) = &mut return_statement.value {
+                shift_expression(value, shift);
+            }
+        }
+        StatementNode::If(if_statement) => {
+            shift_token(&mut if_statement.keyword, shift);
+            shift_expression(&mut if_statement.condition, shift);
+            shift_block(&mut if_statement.then_branch, shift);
+            if let Some(else_branch) = &mut if_statement.else_branch {
+                shift_statement(else_branch, shift);
+            }
+        }
+        StatementNode::While(while_statement) => {
+            shift_token(&mut while_statement.keyword, shift);
+            shift_expression(&mut while_statement.condition, shift);
+            shift_block(&mut while_statement.body, shift);
+        }
+        StatementNode::For(for_statement) => {
+            shift_token(&mut for_statement.keyword, shift);
+            for_statement.variable.span = shift_span(for_statement.variable.span, shift);
+            shift_expression(&mut for_statement.iterable, shift);
+            shift_block(&mut for_statement.body, shift);
+        }
+        StatementNode::Break(break_statement) => shift_token(&mut break_statement.keyword, shift),
+        StatementNode::Continue(continue_statement) => {
+            shift_token(&mut continue_statement.keyword, shift)
+        }
+        StatementNode::Import(import) => {
+            shift_token(&mut import.keyword, shift);
+            match &mut import.path {
+                ImportPath::File(path) => shift_token(path, shift),
+                ImportPath::Module(names) => {
+                    for name in names.iter_mut() {
+                        name.span = shift_span(name.span, shift);
+                    }
+                }
+            }
+        }
+        StatementNode::Error(error) => error.span = shift_span(error.span, shift),
+    }
+}
+
+fn shift_expression(expression: &mut ExpressionNode, shift: &impl Fn(u32) -> u32) -> () {
+    match expression {
+        ExpressionNode::Literal(literal) => shift_token(&mut literal.token, shift),
+        ExpressionNode::Interpolation(interpolation) => {
+            shift_token(&mut interpolation.token, shift);
+            for part in interpolation.parts.iter_mut() {
+                if let InterpolationPart::Expression(expression) = part {
+                    shift_expression(expression, shift);
+                }
+            }
+        }
+        ExpressionNode::Binary(binary) => {
+            shift_expression(&mut binary.left, shift);
+            shift_token(&mut binary.operator, shift);
+            shift_expression(&mut binary.right, shift);
+        }
+        ExpressionNode::Unary(unary) => {
+            shift_token(&mut unary.operator, shift);
+            shift_expression(&mut unary.operand, shift);
+        }
+        ExpressionNode::Grouping(grouping) => {
+            grouping.span = shift_span(grouping.span, shift);
+            shift_expression(&mut grouping.expression, shift);
+        }
+        ExpressionNode::Variable(variable) => {
+            variable.name.span = shift_span(variable.name.span, shift)
+        }
+        ExpressionNode::Assignment(assignment) => {
+            assignment.name.span = shift_span(assignment.name.span, shift);
+            shift_expression(&mut assignment.value, shift);
+        }
+        ExpressionNode::Call(call) => {
+            call.span = shift_span(call.span, shift);
+            shift_expression(&mut call.callee, shift);
+            for argument in call.arguments.iter_mut() {
+                shift_expression(argument, shift);
+            }
+        }
+        ExpressionNode::Error(error) => error.span = shift_span(error.span, shift),
+    }
+}
+
+fn shift_type(annotation: &mut TypeAnnotation, shift: &impl Fn(u32) -> u32) -> () {
+    match annotation {
+        TypeAnnotation::Named(name) => name.span = shift_span(name.span, shift),
+        TypeAnnotation::Function(function) => {
+            shift_token(&mut function.keyword, shift);
+            function.span = shift_span(function.span, shift);
+            for parameter in function.parameters.iter_mut() {
+                shift_type(parameter, shift);
+            }
+            if let Some(return_type) = &mut function.return_type {
+                shift_type(return_type, shift);
+            }
+        }
+    }
+}
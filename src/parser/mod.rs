@@ -1,26 +1,650 @@
-use crate::lexer::syntax::{SyntaxKind, TokenStream};
-use ast::{base::Expression, expressions::Literal};
+use crate::{
+    diagnostics::{Diagnostic, DiagnosticCode},
+    lexer::{
+        syntax::{LiteralValue, Span, StringPart, SyntaxKind, Token, TokenStream, TriviaKind},
+        syntax_facts::{self, Associativity},
+        unnegated_minimum,
+    },
+    source::SourceFile,
+};
+use ast::{
+    base::Identifier,
+    expressions::{
+        Assignment, Binary, Call, ErrorExpression, ExpressionNode, Grouping, Interpolation,
+        InterpolationPart, Literal, Unary, Variable,
+    },
+    statements::{
+        BlockStatement, BreakStatement, ContinueStatement, ErrorStatement, ExpressionStatement,
+        ForStatement, FunctionDeclaration, IfStatement, ImportPath, ImportStatement, LetStatement,
+        Parameter, Program, ReturnStatement, StatementNode, WhileStatement,
+    },
+    types::{FunctionTypeAnnotation, TypeAnnotation},
+};
 
 pub mod ast;
+pub mod cst;
+pub mod incremental;
 
-pub struct Parser {
+pub struct Parser<'a> {
+    /// The file the tokens were lexed from, which holds their text
+    file: &'a SourceFile,
     tokens: TokenStream,
+    diagnostics: Vec<Diagnostic>,
+    function_depth: usize,
+    loop_depth: usize,
+    /// The number of blocks enclosing the current token, zero at the top level of the file
+    block_depth: usize,
 }
 
-impl Parser {
-    pub fn new(tokens: TokenStream) -> Self {
-        Parser { tokens }
+impl<'a> Parser<'a> {
+    pub fn new(file: &'a SourceFile, mut tokens: TokenStream) -> Self {
+        let diagnostics = std::mem::take(&mut tokens.diagnostics);
+        Parser {
+            file,
+            tokens,
+            diagnostics,
+            function_depth: 0,
+            loop_depth: 0,
+            block_depth: 0,
+        }
+    }
+
+    /// The tokens being parsed, along with their trivia
+    pub fn tokens(&self) -> &TokenStream {
+        &self.tokens
+    }
+
+    /// Parses the token stream, returning every lexical and syntax diagnostic on failure
+    pub fn parse(&mut self) -> Result<Program, Vec<Diagnostic>> {
+        let (program, diagnostics) = self.parse_partial();
+        if diagnostics.is_empty() {
+            Ok(program)
+        } else {
+            Err(diagnostics)
+        }
+    }
+
+    /// Parses the token stream into a tree even if it has syntax errors, returning the tree
+    /// along with every lexical and syntax diagnostic
+    ///
+    /// The parser recovers from an error by skipping to the start of the next statement, which
+    /// is the next line, statement keyword or closing brace. The skipped tokens become an
+    /// `ErrorStatement`, and expressions that are missing become an `ErrorExpression`
+    pub fn parse_partial(&mut self) -> (Program, Vec<Diagnostic>) {
+        let mut statements = Vec::new();
+        while !self.tokens.is_finished() {
+            statements.push(self.parse_statement_or_recover());
+        }
+
+        (
+            Program { statements },
+            std::mem::take(&mut self.diagnostics),
+        )
+    }
+
+    /// Parses a statement, skipping to the start of the next one if it cannot be parsed
+    fn parse_statement_or_recover(&mut self) -> StatementNode {
+        let start = self.tokens.position();
+        match self.parse_statement() {
+            Ok(statement) if self.tokens.position() > start => return statement,
+            // an expression was missing, which was already reported
+            Ok(_) => (),
+            Err(diagnostic) => self.report_syntax_error(diagnostic),
+        }
+
+        self.synchronize(start);
+        let first = self.tokens.at(start).span;
+        let last = self.tokens.peek_previous(1).span;
+        StatementNode::Error(ErrorStatement {
+            span: first.to(&last),
+        })
+    }
+
+    /// Skips tokens until the start of the next statement, skipping at least one token if none
+    /// were consumed since `start`
+    ///
+    /// Braced blocks are skipped whole, so the statements of a broken declaration's body are
+    /// not reported as being misplaced
+    fn synchronize(&mut self, start: usize) -> () {
+        let mut depth = 0;
+        if self.tokens.position() == start {
+            depth += self.brace_depth_change();
+            self.tokens.advance();
+        }
+
+        while !self.tokens.is_finished() {
+            let token = self.tokens.current();
+            let starts_line = token
+                .leading_trivia
+                .iter()
+                .any(|trivia| self.file.text(trivia.span).contains('\n'));
+            let closes_block = token.kind == SyntaxKind::RightBrace && self.block_depth > 0;
+            let starts_statement = starts_line || syntax_facts::can_start_statement(&token.kind);
+            if depth == 0 && (starts_statement || closes_block) {
+                break;
+            }
+
+            depth = (depth + self.brace_depth_change()).max(0);
+            self.tokens.advance();
+        }
+    }
+
+    fn brace_depth_change(&self) -> i32 {
+        match self.tokens.current().kind {
+            SyntaxKind::LeftBrace => 1,
+            SyntaxKind::RightBrace => -1,
+            _ => 0,
+        }
     }
 
-    pub fn parse(&mut self) -> impl Expression {
-        self.parse_expression()
+    /// Records a syntax error, unless it is about a token the lexer or a previous statement
+    /// already reported
+    fn report_syntax_error(&mut self, diagnostic: Diagnostic) -> () {
+        let reported = self
+            .diagnostics
+            .last()
+            .is_some_and(|last| last.span == diagnostic.span);
+        if !reported && !self.tokens.check_kind(SyntaxKind::Bad) {
+            let diagnostic = self.with_floor_division_help(diagnostic);
+            self.diagnostics.push(diagnostic);
+        }
     }
 
-    fn parse_expression(&mut self) -> impl Expression {
-        self.parse_primary()
+    /// Adds a hint to an error right after an operand followed by a line comment such as
+    /// `// 2`, which is likely a floor division written with `//`, its spelling before `~/`
+    fn with_floor_division_help(&self, diagnostic: Diagnostic) -> Diagnostic {
+        if self.tokens.position() == 0 || diagnostic.help.is_some() {
+            return diagnostic;
+        }
+
+        let operand = self.tokens.peek_previous(1);
+        let ends_operand = matches!(
+            operand.kind,
+            SyntaxKind::Identifier
+                | SyntaxKind::IntLiteral
+                | SyntaxKind::FloatLiteral
+                | SyntaxKind::StringLiteral
+                | SyntaxKind::InterpolatedStringLiteral
+                | SyntaxKind::BoolLiteral
+                | SyntaxKind::NullLiteral
+                | SyntaxKind::RightParen
+        );
+        let comment = operand.trailing_trivia.iter().find(|trivia| {
+            let text = self.file.text(trivia.span);
+            let rest = text.trim_start_matches('/').trim_start();
+            trivia.kind == TriviaKind::LineComment
+                && rest.starts_with(|char: char| char.is_alphanumeric() || char == '(')
+        });
+
+        match comment {
+            Some(comment) if ends_operand => diagnostic
+                .with_label(comment.span, "this is a comment")
+                .with_help("floor division is written '~/', since '//' starts a comment"),
+            _ => diagnostic,
+        }
+    }
+
+    fn parse_statement(&mut self) -> Result<StatementNode, Diagnostic> {
+        match self.tokens.current().kind {
+            SyntaxKind::LetKeyword => self.parse_let_statement(None),
+            SyntaxKind::LeftBrace => Ok(StatementNode::Block(self.parse_block()?)),
+            SyntaxKind::FnKeyword => self.parse_function_declaration(None),
+            SyntaxKind::PubKeyword => self.parse_public_declaration(),
+            SyntaxKind::ImportKeyword => self.parse_import_statement(),
+            SyntaxKind::ReturnKeyword => self.parse_return_statement(),
+            SyntaxKind::IfKeyword => self.parse_if_statement(),
+            SyntaxKind::WhileKeyword => self.parse_while_statement(),
+            SyntaxKind::ForKeyword => self.parse_for_statement(),
+            SyntaxKind::BreakKeyword => self.parse_break_statement(),
+            SyntaxKind::ContinueKeyword => self.parse_continue_statement(),
+            _ => {
+                let expression = self.parse_expression()?;
+                Ok(StatementNode::Expression(ExpressionStatement {
+                    expression,
+                }))
+            }
+        }
+    }
+
+    /// Parses `pub let ...` or `pub fn ...`, which may only appear at the top level of a file
+    fn parse_public_declaration(&mut self) -> Result<StatementNode, Diagnostic> {
+        let visibility = self.tokens.advance().clone();
+        if self.block_depth > 0 {
+            self.diagnostics.push(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidVisibility,
+                    "only top-level declarations can be public",
+                    visibility.span,
+                )
+                .with_help("remove the 'pub' keyword"),
+            );
+        }
+
+        if self.tokens.check_kind(SyntaxKind::LetKeyword) {
+            self.parse_let_statement(Some(visibility))
+        } else if self.tokens.check_kind(SyntaxKind::FnKeyword) {
+            self.parse_function_declaration(Some(visibility))
+        } else {
+            Err(self.unexpected_token())
+        }
+    }
+
+    /// Parses `import "path/to/module.ion"` or `import module.sub`
+    fn parse_import_statement(&mut self) -> Result<StatementNode, Diagnostic> {
+        let keyword = self.tokens.advance().clone();
+        if self.block_depth > 0 {
+            self.diagnostics.push(Diagnostic::error(
+                DiagnosticCode::MisplacedImport,
+                "imports are only allowed at the top level of a file",
+                keyword.span,
+            ));
+        }
+
+        let path = if self.tokens.check_kind(SyntaxKind::StringLiteral) {
+            ImportPath::File(self.tokens.advance().clone())
+        } else {
+            let mut names = vec![self.consume_identifier()?];
+            while self.tokens.match_kind(SyntaxKind::Dot) {
+                names.push(self.consume_identifier()?);
+            }
+
+            ImportPath::Module(names)
+        };
+
+        Ok(StatementNode::Import(ImportStatement { keyword, path }))
     }
 
-    fn parse_primary(&mut self) -> impl Expression {
+    /// Parses `let name = expr` or `let name: Type = expr`
+    fn parse_let_statement(
+        &mut self,
+        visibility: Option<Token>,
+    ) -> Result<StatementNode, Diagnostic> {
+        let keyword = self.tokens.advance().clone();
+        let name = self.consume_identifier()?;
+
+        let mut type_annotation = None;
+        if self.tokens.match_kind(SyntaxKind::Colon) {
+            type_annotation = Some(self.parse_type_annotation()?);
+        }
+
+        self.tokens.consume(SyntaxKind::Equals)?;
+        let initializer = self.parse_expression()?;
+
+        Ok(StatementNode::Let(LetStatement {
+            visibility,
+            keyword,
+            name,
+            type_annotation,
+            initializer,
+        }))
+    }
+
+    /// Parses `fn name(a: Type, ...): ReturnType { ... }`
+    fn parse_function_declaration(
+        &mut self,
+        visibility: Option<Token>,
+    ) -> Result<StatementNode, Diagnostic> {
+        let keyword = self.tokens.advance().clone();
+        let name = self.consume_identifier()?;
+
+        self.tokens.consume(SyntaxKind::LeftParen)?;
+        let mut parameters = Vec::new();
+        if !self.tokens.check_kind(SyntaxKind::RightParen) {
+            loop {
+                let name = self.consume_identifier()?;
+                self.tokens.consume(SyntaxKind::Colon)?;
+                let type_annotation = self.parse_type_annotation()?;
+                parameters.push(Parameter {
+                    name,
+                    type_annotation,
+                });
+
+                if !self.tokens.match_kind(SyntaxKind::Comma) {
+                    break;
+                }
+            }
+        }
+        self.tokens.consume(SyntaxKind::RightParen)?;
+
+        let mut return_type = None;
+        if self.tokens.match_kind(SyntaxKind::Colon) {
+            return_type = Some(self.parse_type_annotation()?);
+        }
+
+        // loops outside of the function cannot be exited from within its body
+        let enclosing_loop_depth = std::mem::take(&mut self.loop_depth);
+        self.function_depth += 1;
+        let body = self.parse_block();
+        self.function_depth -= 1;
+        self.loop_depth = enclosing_loop_depth;
+
+        Ok(StatementNode::Function(FunctionDeclaration {
+            visibility,
+            keyword,
+            name,
+            parameters,
+            return_type,
+            body: body?,
+        }))
+    }
+
+    /// Parses `return` with an optional value, which is only present when the next token can
+    /// start an expression
+    fn parse_return_statement(&mut self) -> Result<StatementNode, Diagnostic> {
+        let keyword = self.tokens.advance().clone();
+        if self.function_depth == 0 {
+            self.diagnostics.push(Diagnostic::error(
+                DiagnosticCode::ReturnOutsideFunction,
+                "cannot return outside of a function",
+                keyword.span,
+            ));
+        }
+
+        let has_value = !self.tokens.is_finished()
+            && syntax_facts::can_start_expression(&self.tokens.current().kind);
+        let value = if has_value {
+            Some(self.parse_expression()?)
+        } else {
+            None
+        };
+
+        Ok(StatementNode::Return(ReturnStatement { keyword, value }))
+    }
+
+    /// Parses `if condition { ... }` with any number of `else if` branches and an optional `else`
+    fn parse_if_statement(&mut self) -> Result<StatementNode, Diagnostic> {
+        let keyword = self.tokens.advance().clone();
+        let condition = self.parse_expression()?;
+        let then_branch = self.parse_block()?;
+
+        let mut else_branch = None;
+        if self.tokens.match_kind(SyntaxKind::ElseKeyword) {
+            let branch = if self.tokens.check_kind(SyntaxKind::IfKeyword) {
+                self.parse_if_statement()?
+            } else {
+                StatementNode::Block(self.parse_block()?)
+            };
+
+            else_branch = Some(Box::new(branch));
+        }
+
+        Ok(StatementNode::If(IfStatement {
+            keyword,
+            condition,
+            then_branch,
+            else_branch,
+        }))
+    }
+
+    fn parse_while_statement(&mut self) -> Result<StatementNode, Diagnostic> {
+        let keyword = self.tokens.advance().clone();
+        let condition = self.parse_expression()?;
+        let body = self.parse_loop_body()?;
+
+        Ok(StatementNode::While(WhileStatement {
+            keyword,
+            condition,
+            body,
+        }))
+    }
+
+    /// Parses `for variable in iterable { ... }`
+    fn parse_for_statement(&mut self) -> Result<StatementNode, Diagnostic> {
+        let keyword = self.tokens.advance().clone();
+        let variable = self.consume_identifier()?;
+        self.tokens.consume(SyntaxKind::InKeyword)?;
+        let iterable = self.parse_expression()?;
+        let body = self.parse_loop_body()?;
+
+        Ok(StatementNode::For(ForStatement {
+            keyword,
+            variable,
+            iterable,
+            body,
+        }))
+    }
+
+    fn parse_loop_body(&mut self) -> Result<BlockStatement, Diagnostic> {
+        self.loop_depth += 1;
+        let body = self.parse_block();
+        self.loop_depth -= 1;
+
+        body
+    }
+
+    fn parse_break_statement(&mut self) -> Result<StatementNode, Diagnostic> {
+        let keyword = self.tokens.advance().clone();
+        if self.loop_depth == 0 {
+            self.diagnostics.push(Diagnostic::error(
+                DiagnosticCode::BreakOutsideLoop,
+                "cannot break outside of a loop",
+                keyword.span,
+            ));
+        }
+
+        Ok(StatementNode::Break(BreakStatement { keyword }))
+    }
+
+    fn parse_continue_statement(&mut self) -> Result<StatementNode, Diagnostic> {
+        let keyword = self.tokens.advance().clone();
+        if self.loop_depth == 0 {
+            self.diagnostics.push(Diagnostic::error(
+                DiagnosticCode::ContinueOutsideLoop,
+                "cannot continue outside of a loop",
+                keyword.span,
+            ));
+        }
+
+        Ok(StatementNode::Continue(ContinueStatement { keyword }))
+    }
+
+    /// Parses a braced block, whose statements recover from errors on their own
+    ///
+    /// A missing closing brace is reported, and the block then runs to the end of the file
+    fn parse_block(&mut self) -> Result<BlockStatement, Diagnostic> {
+        let start = self.tokens.consume(SyntaxKind::LeftBrace)?.span;
+
+        self.block_depth += 1;
+        let mut statements = Vec::new();
+        while !self.tokens.is_finished() && !self.tokens.check_kind(SyntaxKind::RightBrace) {
+            statements.push(self.parse_statement_or_recover());
+        }
+        self.block_depth -= 1;
+
+        let end = match self.tokens.consume(SyntaxKind::RightBrace) {
+            Ok(end) => end.span,
+            Err(diagnostic) => {
+                self.diagnostics.push(diagnostic);
+                self.tokens.end_span()
+            }
+        };
+
+        Ok(BlockStatement {
+            statements,
+            span: start.to(&end),
+        })
+    }
+
+    /// Parses a type name or a function type such as `fn(int): bool`; `null` is lexed as a
+    /// literal but is also the name of the unit type
+    fn parse_type_annotation(&mut self) -> Result<TypeAnnotation, Diagnostic> {
+        if self
+            .tokens
+            .check_set(vec![SyntaxKind::Identifier, SyntaxKind::NullLiteral])
+        {
+            let name = self.tokens.advance().clone();
+            return Ok(TypeAnnotation::Named(self.identifier(&name)));
+        }
+
+        if !self.tokens.check_kind(SyntaxKind::FnKeyword) {
+            return Err(self.unexpected_token());
+        }
+
+        let keyword = self.tokens.advance().clone();
+        self.tokens.consume(SyntaxKind::LeftParen)?;
+        let mut parameters = Vec::new();
+        if !self.tokens.check_kind(SyntaxKind::RightParen) {
+            loop {
+                parameters.push(self.parse_type_annotation()?);
+                if !self.tokens.match_kind(SyntaxKind::Comma) {
+                    break;
+                }
+            }
+        }
+        let mut end = self.tokens.consume(SyntaxKind::RightParen)?.span;
+
+        let mut return_type = None;
+        if self.tokens.match_kind(SyntaxKind::Colon) {
+            let annotation = self.parse_type_annotation()?;
+            end = annotation.span();
+            return_type = Some(Box::new(annotation));
+        }
+
+        Ok(TypeAnnotation::Function(FunctionTypeAnnotation {
+            span: keyword.span.to(&end),
+            keyword,
+            parameters,
+            return_type,
+        }))
+    }
+
+    fn parse_expression(&mut self) -> Result<ExpressionNode, Diagnostic> {
+        self.parse_assignment()
+    }
+
+    /// Parses `name = value`, which is right associative and binds looser than every operator
+    fn parse_assignment(&mut self) -> Result<ExpressionNode, Diagnostic> {
+        let target = self.parse_binary_expression(0)?;
+        if !self.tokens.check_kind(SyntaxKind::Equals) {
+            return Ok(target);
+        }
+
+        let equals = self.tokens.advance().clone();
+        let value = self.parse_assignment()?;
+        match target {
+            ExpressionNode::Variable(variable) => Ok(ExpressionNode::Assignment(Assignment {
+                name: variable.name,
+                value: Box::new(value),
+            })),
+            _ => {
+                self.diagnostics.push(
+                    Diagnostic::error(
+                        DiagnosticCode::InvalidAssignmentTarget,
+                        "invalid assignment target",
+                        target.span(),
+                    )
+                    .with_label(equals.span, "cannot assign to this expression"),
+                );
+
+                Ok(ExpressionNode::Error(ErrorExpression {
+                    span: target.span().to(&value.span()),
+                }))
+            }
+        }
+    }
+
+    /// Parses an expression using precedence climbing, only consuming binary operators that
+    /// bind tighter than `parent_precedence`
+    ///
+    /// See `syntax_facts::get_binary_operator_precedence` for the precedence table
+    fn parse_binary_expression(
+        &mut self,
+        parent_precedence: u8,
+    ) -> Result<ExpressionNode, Diagnostic> {
+        let mut left = match self.current_unary_precedence() {
+            Some(precedence) if precedence >= parent_precedence && self.check_negated_minimum() => {
+                self.parse_negated_minimum()
+            }
+            Some(precedence) if precedence >= parent_precedence => {
+                let operator = self.tokens.advance().clone();
+                let operand = self.parse_binary_expression(precedence)?;
+
+                ExpressionNode::Unary(Unary {
+                    operator,
+                    operand: Box::new(operand),
+                })
+            }
+            _ => self.parse_call()?,
+        };
+
+        while let Some((precedence, associativity)) = self.current_binary_precedence() {
+            if precedence <= parent_precedence {
+                break;
+            }
+
+            let operator = self.tokens.advance().clone();
+            let right_precedence = match associativity {
+                Associativity::Left => precedence,
+                Associativity::Right => precedence - 1,
+            };
+            let right = self.parse_binary_expression(right_precedence)?;
+
+            left = ExpressionNode::Binary(Binary {
+                left: Box::new(left),
+                operator,
+                right: Box::new(right),
+            });
+        }
+
+        Ok(left)
+    }
+
+    /// Returns whether the current token is a `-` before the literal of a signed type's minimum,
+    /// which the lexer reads as the minimum itself, see `Lexer::integer_token`
+    ///
+    /// A `^` after the literal binds tighter than the `-`, which then does not negate the literal
+    fn check_negated_minimum(&mut self) -> bool {
+        let minimum =
+            |token: &Token| matches!(token.value, Some(LiteralValue::Int(value)) if value < 0);
+
+        self.tokens.check_kind(SyntaxKind::Minus)
+            && self.tokens.check_kind_at_offset(SyntaxKind::IntLiteral, 1)
+            && minimum(self.tokens.peek(1))
+            && !self.tokens.check_kind_at_offset(SyntaxKind::Carat, 2)
+    }
+
+    /// Folds a `-` and the literal of a signed type's minimum after it into a single literal,
+    /// since the literal only fits its type once negated, e.g. `-128i8`
+    fn parse_negated_minimum(&mut self) -> ExpressionNode {
+        let minus = self.tokens.advance().clone();
+        let literal = self.tokens.advance().clone();
+        let token = Token {
+            span: minus.span.to(&literal.span),
+            leading_trivia: minus.leading_trivia,
+            ..literal
+        };
+
+        ExpressionNode::Literal(Literal { token })
+    }
+
+    /// Parses a primary expression followed by any number of argument lists, e.g. `f(1)(2)`
+    fn parse_call(&mut self) -> Result<ExpressionNode, Diagnostic> {
+        let mut expression = self.parse_primary()?;
+
+        while self.tokens.match_kind(SyntaxKind::LeftParen) {
+            let mut arguments = Vec::new();
+            if !self.tokens.check_kind(SyntaxKind::RightParen) {
+                loop {
+                    arguments.push(self.parse_expression()?);
+                    if !self.tokens.match_kind(SyntaxKind::Comma) {
+                        break;
+                    }
+                }
+            }
+
+            let end = self.tokens.consume(SyntaxKind::RightParen)?;
+            let span = expression.span().to(&end.span);
+            expression = ExpressionNode::Call(Call {
+                callee: Box::new(expression),
+                arguments,
+                span,
+            });
+        }
+
+        Ok(expression)
+    }
+
+    fn parse_primary(&mut self) -> Result<ExpressionNode, Diagnostic> {
         if self.tokens.check_set(vec![
             SyntaxKind::IntLiteral,
             SyntaxKind::FloatLiteral,
@@ -28,11 +652,163 @@ impl Parser {
             SyntaxKind::BoolLiteral,
             SyntaxKind::NullLiteral,
         ]) {
-            let token_ref = self.tokens.advance();
-            let token = token_ref.clone();
-            return Literal { token };
+            let token = self.tokens.advance().clone();
+            if let Some(LiteralValue::Int(value)) = token.value {
+                if value < 0 {
+                    self.diagnostics.push(unnegated_minimum(value, token.span));
+                }
+            }
+            return Ok(ExpressionNode::Literal(Literal { token }));
+        }
+
+        if self
+            .tokens
+            .check_kind(SyntaxKind::InterpolatedStringLiteral)
+        {
+            return Ok(self.parse_interpolation());
+        }
+
+        if self.tokens.check_kind(SyntaxKind::Identifier) {
+            let name = self.tokens.advance().clone();
+            return Ok(ExpressionNode::Variable(Variable {
+                name: self.identifier(&name),
+            }));
+        }
+
+        if self.tokens.check_kind(SyntaxKind::LeftParen) {
+            return self.parse_grouping();
+        }
+
+        // leave the token to whatever comes next, so a missing operand does not swallow it
+        let diagnostic = self.unexpected_token();
+        self.report_syntax_error(diagnostic);
+        let span = match self.tokens.is_finished() {
+            true => self.tokens.end_span(),
+            false => {
+                let start = self.tokens.current().span;
+                Span {
+                    end: start.start,
+                    ..start
+                }
+            }
+        };
+
+        Ok(ExpressionNode::Error(ErrorExpression { span }))
+    }
+
+    fn parse_interpolation(&mut self) -> ExpressionNode {
+        let token = self.tokens.advance().clone();
+        let Some(LiteralValue::Interpolated(parts)) = &token.value else {
+            unreachable!("interpolated strings always hold their parts")
+        };
+
+        let parts = parts
+            .iter()
+            .map(|part| match part {
+                StringPart::Text(text) => InterpolationPart::Text(text.clone()),
+                StringPart::Expression { tokens, span } => {
+                    InterpolationPart::Expression(self.parse_interpolated_expression(tokens, *span))
+                }
+            })
+            .collect();
+
+        ExpressionNode::Interpolation(Interpolation { token, parts })
+    }
+
+    /// Parses the tokens between the braces of an interpolation, whose span is `span`, as a
+    /// single expression
+    fn parse_interpolated_expression(&mut self, tokens: &[Token], span: Span) -> ExpressionNode {
+        // the closing brace ends the expression, as it would a block
+        let mut tokens = tokens.to_vec();
+        tokens.push(Token {
+            kind: SyntaxKind::RightBrace,
+            span: Span {
+                start: span.end - 1,
+                ..span
+            },
+            ..Token::default()
+        });
+
+        let mut parser = Parser {
+            file: self.file,
+            tokens: TokenStream::new(tokens),
+            diagnostics: Vec::new(),
+            function_depth: self.function_depth,
+            loop_depth: self.loop_depth,
+            block_depth: self.block_depth,
+        };
+        let expression = match parser.parse_expression() {
+            Ok(expression) => expression,
+            Err(diagnostic) => {
+                parser.report_syntax_error(diagnostic);
+                ExpressionNode::Error(ErrorExpression { span })
+            }
+        };
+        if !parser.tokens.check_kind(SyntaxKind::RightBrace) {
+            let diagnostic = parser.unexpected_token();
+            parser.report_syntax_error(diagnostic);
+        }
+
+        self.diagnostics.append(&mut parser.diagnostics);
+        expression
+    }
+
+    fn parse_grouping(&mut self) -> Result<ExpressionNode, Diagnostic> {
+        let start = self.tokens.advance().span;
+        let expression = self.parse_expression()?;
+        let end = self.tokens.consume(SyntaxKind::RightParen)?;
+
+        Ok(ExpressionNode::Grouping(Grouping {
+            expression: Box::new(expression),
+            span: start.to(&end.span),
+        }))
+    }
+
+    fn current_unary_precedence(&self) -> Option<u8> {
+        if self.tokens.is_finished() {
+            return None;
+        }
+
+        syntax_facts::get_unary_operator_precedence(&self.tokens.current().kind)
+    }
+
+    fn current_binary_precedence(&self) -> Option<(u8, Associativity)> {
+        if self.tokens.is_finished() {
+            return None;
+        }
+
+        syntax_facts::get_binary_operator_precedence(&self.tokens.current().kind)
+    }
+
+    /// Consumes an identifier, reading its name from the source
+    fn consume_identifier(&mut self) -> Result<Identifier, Diagnostic> {
+        let token = self.tokens.consume(SyntaxKind::Identifier)?.clone();
+        Ok(self.identifier(&token))
+    }
+
+    fn identifier(&self, token: &Token) -> Identifier {
+        Identifier {
+            text: self.file.text(token.span).to_string(),
+            span: token.span,
+            source_text: None,
+        }
+    }
+
+    /// Returns a diagnostic for the current token, or for the end of file if there is none
+    fn unexpected_token(&self) -> Diagnostic {
+        if self.tokens.is_finished() {
+            return Diagnostic::error(
+                DiagnosticCode::UnexpectedEndOfFile,
+                "unexpected end of file",
+                self.tokens.end_span(),
+            );
         }
 
-        panic!("unexpected token {:?}", self.tokens.current().kind)
+        let token = self.tokens.current();
+        Diagnostic::error(
+            DiagnosticCode::UnexpectedToken,
+            format!("unexpected token {:?}", token.kind),
+            token.span,
+        )
     }
 }
@@ -0,0 +1,265 @@
+use std::{
+    cell::RefCell,
+    io::{self, BufRead, Write},
+    rc::Rc,
+};
+
+use crate::{
+    diagnostics::{renderer::Renderer, Diagnostic, DiagnosticCode},
+    interpreter::{value::Value, Interpreter},
+    parser::ast::{
+        printer::Printer,
+        statements::{Program, StatementNode},
+    },
+    source::{FileId, SourceFile, SourceMap},
+    typeck::TypeChecker,
+};
+
+const PROMPT: &str = ">> ";
+const CONTINUATION_PROMPT: &str = ".. ";
+
+const HELP: &str = "\
+:tokens <code>  print the tokens of <code>
+:ast <code>     print the syntax tree of <code>
+:type <expr>    print the type of <expr>
+:help           print this message
+:quit           exit the repl
+";
+
+/// What the repl expects after being fed a line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ready,
+    /// The input so far is incomplete, e.g. a brace or a string is still open
+    Continue,
+    Exit,
+}
+
+/// A writer shared between the repl and the interpreter, so printed values and the program's
+/// own output end up interleaved in order
+#[derive(Clone)]
+struct SharedOutput(Rc<RefCell<Box<dyn Write>>>);
+
+impl Write for SharedOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.borrow_mut().flush()
+    }
+}
+
+/// An interactive session keeping every binding alive between entries
+///
+/// Entries are run by the `Interpreter` after being type checked. Redeclaring a name replaces
+/// the previous binding, so entries are not passed through the `Resolver`
+pub struct Repl {
+    interpreter: Interpreter,
+    checker: TypeChecker,
+    output: SharedOutput,
+    errors: Box<dyn Write>,
+    renderer: Renderer,
+    /// Every entry so far, each in its own file, so a span into an earlier entry, e.g. in the
+    /// body of a function it declared, still resolves when a later entry runs it
+    sources: SourceMap,
+    /// Lines of the entry being typed, while it is incomplete
+    buffer: String,
+}
+
+impl Repl {
+    pub fn new(output: Box<dyn Write>, errors: Box<dyn Write>, renderer: Renderer) -> Self {
+        let output = SharedOutput(Rc::new(RefCell::new(output)));
+
+        Self {
+            interpreter: Interpreter::with_output(Box::new(output.clone())),
+            checker: TypeChecker::new(),
+            output,
+            errors,
+            renderer,
+            sources: SourceMap::new(),
+            buffer: String::new(),
+        }
+    }
+
+    /// Reads and evaluates lines from `input` until it is exhausted or `:quit` is entered
+    pub fn run(&mut self, input: &mut dyn BufRead) -> () {
+        let mut status = Status::Ready;
+        loop {
+            let prompt = match status {
+                Status::Continue => CONTINUATION_PROMPT,
+                _ => PROMPT,
+            };
+            self.write(prompt);
+            let _ = self.output.flush();
+
+            let mut line = String::new();
+            match input.read_line(&mut line) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => (),
+            }
+
+            status = self.feed_line(line.trim_end_matches(['\n', '\r']));
+            if status == Status::Exit {
+                break;
+            }
+        }
+
+        self.write("\n");
+    }
+
+    /// Feeds a single line of input, evaluating the entry once it is complete
+    ///
+    /// An empty line always completes the entry, so input that can never be completed still
+    /// gets its diagnostics reported
+    pub fn feed_line(&mut self, line: &str) -> Status {
+        if self.buffer.is_empty() {
+            if let Some(command) = line.trim_start().strip_prefix(':') {
+                return self.run_command(command);
+            }
+        }
+
+        if !self.buffer.is_empty() {
+            self.buffer.push('\n');
+        }
+        self.buffer += line;
+
+        match source_file(&self.buffer).parse() {
+            Err(diagnostics) if is_incomplete(&diagnostics) && !line.trim().is_empty() => {
+                return Status::Continue;
+            }
+            _ => (),
+        }
+
+        let entry = std::mem::take(&mut self.buffer);
+        let id = self.add_entry(&entry);
+        match self.sources.get(id).parse() {
+            Ok(program) => self.evaluate(&program),
+            Err(diagnostics) => self.report(&diagnostics),
+        }
+
+        Status::Ready
+    }
+
+    fn evaluate(&mut self, program: &Program) -> () {
+        if let Err(diagnostics) = self.checker.check(program) {
+            return self.report(&diagnostics);
+        }
+
+        for statement in program.statements.iter() {
+            let result = match statement {
+                StatementNode::Import(import) => {
+                    let diagnostic = Diagnostic::error(
+                        DiagnosticCode::MisplacedImport,
+                        "imports are not supported in the repl",
+                        import.keyword.span,
+                    );
+                    return self.report(&[diagnostic]);
+                }
+                StatementNode::Expression(statement) => self
+                    .interpreter
+                    .evaluate(&statement.expression)
+                    .map(|value| match value {
+                        Value::Null => (),
+                        value => self.write(&format!("{:?}\n", value)),
+                    }),
+                statement => self.interpreter.interpret(&Program {
+                    statements: vec![statement.clone()],
+                }),
+            };
+
+            if let Err(error) = result {
+                return self.report(&[error.to_diagnostic()]);
+            }
+        }
+    }
+
+    fn run_command(&mut self, command: &str) -> Status {
+        let (name, argument) = command.split_once(' ').unwrap_or((command, ""));
+        match name {
+            "tokens" => {
+                let id = self.add_entry(argument);
+                let file = self.sources.get(id);
+                let tokens = file.tokenize();
+                let text: String = tokens
+                    .iter()
+                    .map(|token| format!("{:?} {:?}\n", token.kind, file.text(token.span)))
+                    .collect();
+                self.write(&text);
+                self.report(&tokens.diagnostics);
+            }
+            "ast" => {
+                let id = self.add_entry(argument);
+                let file = self.sources.get(id);
+                match file.parse() {
+                    Ok(program) => {
+                        let tree = Printer::new(file).print_program(&program);
+                        self.write(&format!("{}\n", tree));
+                    }
+                    Err(diagnostics) => self.report(&diagnostics),
+                }
+            }
+            "type" => {
+                let id = self.add_entry(argument);
+                self.print_type(id);
+            }
+            "help" => self.write(HELP),
+            "quit" | "q" => return Status::Exit,
+            _ => {
+                let message = format!("unknown command ':{}', try :help\n", name);
+                let _ = self.errors.write_all(message.as_bytes());
+            }
+        }
+
+        Status::Ready
+    }
+
+    fn print_type(&mut self, id: FileId) -> () {
+        let program = match self.sources.get(id).parse() {
+            Ok(program) => program,
+            Err(diagnostics) => return self.report(&diagnostics),
+        };
+
+        match program.statements.as_slice() {
+            [StatementNode::Expression(statement)] => {
+                match self.checker.check_expression(&statement.expression) {
+                    Ok(ty) => self.write(&format!("{}\n", ty)),
+                    Err(diagnostics) => self.report(&diagnostics),
+                }
+            }
+            _ => {
+                let _ = self
+                    .errors
+                    .write_all(b":type expects a single expression\n");
+            }
+        }
+    }
+
+    /// Adds the text of an entry or command to the session's files, returning its id
+    fn add_entry(&mut self, source: &str) -> FileId {
+        self.sources.add(source_file(source))
+    }
+
+    fn report(&mut self, diagnostics: &[Diagnostic]) -> () {
+        let output = self.renderer.render_all_in(diagnostics, &self.sources);
+        let _ = self.errors.write_all(output.as_bytes());
+    }
+
+    fn write(&mut self, text: &str) -> () {
+        let _ = self.output.write_all(text.as_bytes());
+    }
+}
+
+/// Returns whether the diagnostics only show that the input ended too early
+fn is_incomplete(diagnostics: &[Diagnostic]) -> bool {
+    diagnostics.iter().any(|diagnostic| {
+        matches!(
+            diagnostic.code,
+            DiagnosticCode::UnexpectedEndOfFile | DiagnosticCode::UnterminatedString
+        )
+    })
+}
+
+fn source_file(source: &str) -> SourceFile {
+    SourceFile::from_string("<repl>", source)
+}
@@ -0,0 +1,360 @@
+pub mod symbols;
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+};
+
+use crate::{
+    diagnostics::{Diagnostic, DiagnosticCode},
+    interpreter::natives,
+    lexer::syntax::Span,
+    parser::ast::{
+        base::{Expression, Identifier, Statement},
+        expressions::{
+            Assignment, Binary, Call, ErrorExpression, Grouping, Interpolation, InterpolationPart,
+            Literal, Unary, Variable,
+        },
+        statements::{
+            BlockStatement, BreakStatement, ContinueStatement, ErrorStatement, ExpressionStatement,
+            ForStatement, FunctionDeclaration, IfStatement, ImportStatement, LetStatement, Program,
+            ReturnStatement, StatementNode, WhileStatement,
+        },
+        visitor::{ExpressionVisitor, StatementVisitor, SyntaxNodeVisitor},
+    },
+};
+use symbols::{ScopeId, ScopeKind, Symbol, SymbolKind, SymbolTable};
+
+/// A use of a name that was not declared yet when it was reached
+struct PendingUse {
+    name: String,
+    span: Span,
+    /// Uses inside of a function body may refer to globals declared after the function, as
+    /// long as the function is only called once they are defined
+    in_function: bool,
+}
+
+/// Binds every use of a name to its declaration, building a `SymbolTable`
+///
+/// Names are not hoisted: apart from functions referring to globals declared further down,
+/// every name must be declared before it is used. Like the `Interpreter`, the resolver keeps its
+/// global scope between calls to `resolve`
+pub struct Resolver {
+    table: RefCell<SymbolTable>,
+    /// The scopes enclosing the node being resolved, along with the number of pending uses at the
+    /// time each scope was entered
+    scopes: RefCell<Vec<(ScopeId, usize)>>,
+    pending: RefCell<Vec<PendingUse>>,
+    function_depth: Cell<usize>,
+    /// Private declarations of imported modules, only used to explain why a name is undefined
+    hidden: RefCell<HashMap<String, Span>>,
+    diagnostics: RefCell<Vec<Diagnostic>>,
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        let mut table = SymbolTable::default();
+        let prelude = table.add_scope(ScopeKind::Prelude, None);
+        for native in natives::get_natives() {
+            table.add_symbol(Symbol {
+                name: native.name.to_string(),
+                kind: SymbolKind::Native,
+                scope: prelude,
+                span: None,
+            });
+        }
+        let global = table.add_scope(ScopeKind::Global, Some(prelude));
+
+        Self {
+            table: RefCell::new(table),
+            scopes: RefCell::new(vec![(prelude, 0), (global, 0)]),
+            pending: RefCell::new(Vec::new()),
+            function_depth: Cell::new(0),
+            hidden: RefCell::new(HashMap::new()),
+            diagnostics: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn resolve(&self, program: &Program) -> Result<(), Vec<Diagnostic>> {
+        for statement in program.statements.iter() {
+            self.resolve_statement(statement);
+        }
+
+        let global = self.current_scope();
+        self.settle_pending_uses(global, 0);
+
+        for pending in self.pending.take() {
+            let diagnostic = match self.hidden.borrow().get(&pending.name) {
+                Some(declaration) => Diagnostic::error(
+                    DiagnosticCode::PrivateName,
+                    format!("'{}' is private to the module declaring it", pending.name),
+                    pending.span,
+                )
+                .with_label(*declaration, format!("'{}' is declared here", pending.name))
+                .with_help("mark the declaration with 'pub' to export it"),
+                None => Diagnostic::error(
+                    DiagnosticCode::UndefinedName,
+                    format!("cannot find '{}' in this scope", pending.name),
+                    pending.span,
+                ),
+            };
+            self.report(diagnostic);
+        }
+
+        let diagnostics = self.diagnostics.take();
+        if diagnostics.is_empty() {
+            Ok(())
+        } else {
+            Err(diagnostics)
+        }
+    }
+
+    /// Declares a name exported by an imported module in the global scope
+    pub fn import(&self, name: &Identifier, kind: SymbolKind) -> () {
+        self.declare(name, kind);
+    }
+
+    /// Records a private declaration of an imported module, which stays out of scope
+    pub fn hide(&self, name: &Identifier) -> () {
+        self.hidden
+            .borrow_mut()
+            .entry(name.text.clone())
+            .or_insert(name.span);
+    }
+
+    pub fn symbol_table(&self) -> SymbolTable {
+        self.table.borrow().clone()
+    }
+
+    pub fn into_symbol_table(self) -> SymbolTable {
+        self.table.into_inner()
+    }
+
+    fn resolve_statement(&self, statement: &StatementNode) -> () {
+        Statement::accept(statement, self)
+    }
+
+    fn resolve_expression(&self, expression: &impl Expression) -> () {
+        Expression::accept(expression, self)
+    }
+
+    fn report(&self, diagnostic: Diagnostic) -> () {
+        self.diagnostics.borrow_mut().push(diagnostic);
+    }
+
+    fn current_scope(&self) -> ScopeId {
+        self.scopes.borrow().last().unwrap().0
+    }
+
+    fn begin_scope(&self, kind: ScopeKind) -> () {
+        let parent = self.current_scope();
+        let scope = self.table.borrow_mut().add_scope(kind, Some(parent));
+        let pending = self.pending.borrow().len();
+        self.scopes.borrow_mut().push((scope, pending));
+    }
+
+    fn end_scope(&self) -> () {
+        let (scope, first_pending) = self.scopes.borrow_mut().pop().unwrap();
+        self.settle_pending_uses(scope, first_pending);
+    }
+
+    /// Checks the uses made since entering `scope` against the names it ended up declaring
+    fn settle_pending_uses(&self, scope: ScopeId, first_pending: usize) -> () {
+        let uses = self.pending.borrow_mut().split_off(first_pending);
+        let is_global = self.table.borrow().scope(scope).kind == ScopeKind::Global;
+
+        for pending in uses {
+            let Some(symbol) = self.table.borrow().scope(scope).get(&pending.name) else {
+                self.pending.borrow_mut().push(pending);
+                continue;
+            };
+
+            if is_global && pending.in_function {
+                self.table.borrow_mut().add_reference(pending.span, symbol);
+                continue;
+            }
+
+            let declaration = self.table.borrow().symbol(symbol).span;
+            let mut diagnostic = Diagnostic::error(
+                DiagnosticCode::UseBeforeDefinition,
+                format!("'{}' is used before its definition", pending.name),
+                pending.span,
+            );
+            if let Some(declaration) = declaration {
+                diagnostic = diagnostic
+                    .with_label(declaration, format!("'{}' is defined here", pending.name));
+            }
+            self.report(diagnostic);
+        }
+    }
+
+    fn declare(&self, name: &Identifier, kind: SymbolKind) -> () {
+        let scope = self.current_scope();
+        let previous = self.table.borrow().scope(scope).get(&name.text);
+
+        if let Some(previous) = previous {
+            let mut diagnostic = Diagnostic::error(
+                DiagnosticCode::DuplicateDeclaration,
+                format!("'{}' is already declared in this scope", name.text),
+                name.span,
+            );
+            if let Some(span) = self.table.borrow().symbol(previous).span {
+                diagnostic = diagnostic.with_label(span, "previously declared here");
+            }
+            self.report(diagnostic);
+        }
+
+        self.table.borrow_mut().add_symbol(Symbol {
+            name: name.text.clone(),
+            kind,
+            scope,
+            span: Some(name.span),
+        });
+    }
+
+    /// Binds a use of `name` to the innermost declaration visible from the current scope
+    fn reference(&self, name: &Identifier) -> () {
+        let symbol = self
+            .scopes
+            .borrow()
+            .iter()
+            .rev()
+            .find_map(|(scope, _)| self.table.borrow().scope(*scope).get(&name.text));
+
+        match symbol {
+            Some(symbol) => self.table.borrow_mut().add_reference(name.span, symbol),
+            None => self.pending.borrow_mut().push(PendingUse {
+                name: name.text.clone(),
+                span: name.span,
+                in_function: self.function_depth.get() > 0,
+            }),
+        }
+    }
+
+    fn resolve_block(&self, block: &BlockStatement) -> () {
+        self.begin_scope(ScopeKind::Block);
+        for statement in block.statements.iter() {
+            self.resolve_statement(statement);
+        }
+        self.end_scope();
+    }
+}
+
+impl SyntaxNodeVisitor<()> for Resolver {}
+
+impl ExpressionVisitor<()> for Resolver {
+    fn visit_literal(&self, _literal: &Literal) -> () {}
+
+    fn visit_interpolation(&self, interpolation: &Interpolation) -> () {
+        for part in interpolation.parts.iter() {
+            if let InterpolationPart::Expression(expression) = part {
+                self.resolve_expression(expression);
+            }
+        }
+    }
+
+    fn visit_binary(&self, binary: &Binary) -> () {
+        self.resolve_expression(binary.left.as_ref());
+        self.resolve_expression(binary.right.as_ref());
+    }
+
+    fn visit_unary(&self, unary: &Unary) -> () {
+        self.resolve_expression(unary.operand.as_ref());
+    }
+
+    fn visit_grouping(&self, grouping: &Grouping) -> () {
+        self.resolve_expression(grouping.expression.as_ref());
+    }
+
+    fn visit_variable(&self, variable: &Variable) -> () {
+        self.reference(&variable.name);
+    }
+
+    fn visit_assignment(&self, assignment: &Assignment) -> () {
+        self.resolve_expression(assignment.value.as_ref());
+        self.reference(&assignment.name);
+    }
+
+    fn visit_call(&self, call: &Call) -> () {
+        self.resolve_expression(call.callee.as_ref());
+        for argument in call.arguments.iter() {
+            self.resolve_expression(argument);
+        }
+    }
+
+    fn visit_error_expression(&self, _error: &ErrorExpression) -> () {}
+}
+
+impl StatementVisitor<()> for Resolver {
+    fn visit_let_statement(&self, let_statement: &LetStatement) -> () {
+        self.resolve_expression(&let_statement.initializer);
+        self.declare(&let_statement.name, SymbolKind::Variable);
+    }
+
+    fn visit_expression_statement(&self, expression_statement: &ExpressionStatement) -> () {
+        self.resolve_expression(&expression_statement.expression);
+    }
+
+    fn visit_block_statement(&self, block: &BlockStatement) -> () {
+        self.resolve_block(block);
+    }
+
+    fn visit_function_declaration(&self, function: &FunctionDeclaration) -> () {
+        // declared before the body so the function can call itself
+        self.declare(&function.name, SymbolKind::Function);
+
+        self.begin_scope(ScopeKind::Function);
+        self.function_depth.set(self.function_depth.get() + 1);
+        for parameter in function.parameters.iter() {
+            self.declare(&parameter.name, SymbolKind::Parameter);
+        }
+        for statement in function.body.statements.iter() {
+            self.resolve_statement(statement);
+        }
+        self.function_depth.set(self.function_depth.get() - 1);
+        self.end_scope();
+    }
+
+    fn visit_return_statement(&self, return_statement: &ReturnStatement) -> () {
+        if let Some(value) = &return_statement.value {
+            self.resolve_expression(value);
+        }
+    }
+
+    fn visit_if_statement(&self, if_statement: &IfStatement) -> () {
+        self.resolve_expression(&if_statement.condition);
+        self.resolve_block(&if_statement.then_branch);
+        if let Some(else_branch) = &if_statement.else_branch {
+            self.resolve_statement(else_branch);
+        }
+    }
+
+    fn visit_while_statement(&self, while_statement: &WhileStatement) -> () {
+        self.resolve_expression(&while_statement.condition);
+        self.resolve_block(&while_statement.body);
+    }
+
+    fn visit_for_statement(&self, for_statement: &ForStatement) -> () {
+        self.resolve_expression(&for_statement.iterable);
+
+        self.begin_scope(ScopeKind::Block);
+        self.declare(&for_statement.variable, SymbolKind::Variable);
+        self.resolve_block(&for_statement.body);
+        self.end_scope();
+    }
+
+    fn visit_break_statement(&self, _break_statement: &BreakStatement) -> () {}
+
+    fn visit_continue_statement(&self, _continue_statement: &ContinueStatement) -> () {}
+
+    /// The names exported by an imported module are declared up front through `Resolver::import`
+    fn visit_import_statement(&self, _import: &ImportStatement) -> () {}
+
+    fn visit_error_statement(&self, _error: &ErrorStatement) -> () {}
+}
@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use crate::{lexer::syntax::Span, source::FileId};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SymbolId(pub usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScopeId(pub usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    /// A function provided by the runtime, see `interpreter::natives`
+    Native,
+    Variable,
+    Function,
+    Parameter,
+}
+
+/// A declared name
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub scope: ScopeId,
+    /// The span of the declared name, or None for natives
+    pub span: Option<Span>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeKind {
+    /// The scope holding the natives, enclosing the global scope
+    Prelude,
+    Global,
+    /// The parameters and body of a function
+    Function,
+    Block,
+}
+
+#[derive(Debug, Clone)]
+pub struct Scope {
+    pub kind: ScopeKind,
+    pub parent: Option<ScopeId>,
+    /// Names declared in this scope, bound to their latest declaration
+    names: HashMap<String, SymbolId>,
+}
+
+impl Scope {
+    pub fn get(&self, name: &str) -> Option<SymbolId> {
+        self.names.get(name).copied()
+    }
+}
+
+/// A use of a name, bound to the declaration it refers to
+#[derive(Debug, Clone)]
+pub struct Reference {
+    pub span: Span,
+    pub symbol: SymbolId,
+}
+
+/// Every declaration and every use of a name in a program, as found by the `Resolver`
+#[derive(Debug, Clone, Default)]
+pub struct SymbolTable {
+    symbols: Vec<Symbol>,
+    scopes: Vec<Scope>,
+    references: Vec<Reference>,
+}
+
+impl SymbolTable {
+    pub fn symbol(&self, id: SymbolId) -> &Symbol {
+        &self.symbols[id.0]
+    }
+
+    pub fn scope(&self, id: ScopeId) -> &Scope {
+        &self.scopes[id.0]
+    }
+
+    pub fn symbols(&self) -> impl Iterator<Item = (SymbolId, &Symbol)> {
+        self.symbols
+            .iter()
+            .enumerate()
+            .map(|(index, symbol)| (SymbolId(index), symbol))
+    }
+
+    pub fn references(&self) -> &[Reference] {
+        &self.references
+    }
+
+    pub fn references_to(&self, symbol: SymbolId) -> impl Iterator<Item = &Reference> {
+        self.references
+            .iter()
+            .filter(move |reference| reference.symbol == symbol)
+    }
+
+    /// Returns the symbol declared or referenced at the given byte offset, if any
+    pub fn symbol_at(&self, position: usize) -> Option<SymbolId> {
+        let contains = |span: &Span| span.contains(position);
+
+        self.symbols()
+            .find(|(_, symbol)| symbol.span.as_ref().is_some_and(contains))
+            .map(|(id, _)| id)
+            .or_else(|| {
+                self.references
+                    .iter()
+                    .find(|reference| contains(&reference.span))
+                    .map(|reference| reference.symbol)
+            })
+    }
+
+    /// Like `symbol_at`, for names in `file`, also returning the span of the name
+    pub fn name_at(&self, file: FileId, position: usize) -> Option<(Span, SymbolId)> {
+        let contains = |span: &Span| span.file == file && span.contains(position);
+
+        self.symbols()
+            .find_map(|(id, symbol)| symbol.span.filter(contains).map(|span| (span, id)))
+            .or_else(|| {
+                self.references
+                    .iter()
+                    .find(|reference| contains(&reference.span))
+                    .map(|reference| (reference.span, reference.symbol))
+            })
+    }
+
+    pub(super) fn add_scope(&mut self, kind: ScopeKind, parent: Option<ScopeId>) -> ScopeId {
+        self.scopes.push(Scope {
+            kind,
+            parent,
+            names: HashMap::new(),
+        });
+        ScopeId(self.scopes.len() - 1)
+    }
+
+    pub(super) fn add_symbol(&mut self, symbol: Symbol) -> SymbolId {
+        let id = SymbolId(self.symbols.len());
+        self.scopes[symbol.scope.0]
+            .names
+            .insert(symbol.name.clone(), id);
+        self.symbols.push(symbol);
+        id
+    }
+
+    pub(super) fn add_reference(&mut self, span: Span, symbol: SymbolId) -> () {
+        self.references.push(Reference { span, symbol });
+    }
+}
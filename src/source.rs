@@ -1,43 +1,242 @@
 use pathdiff::diff_paths;
-use std::{fs, path::PathBuf, str::FromStr};
+use std::{
+    env, fs, io,
+    path::{Component, Path, PathBuf},
+};
 
 use crate::{
-    lexer::{syntax::TokenStream, Lexer},
-    parser::{ast::base::Expression, Parser},
-    utility,
+    diagnostics::Diagnostic,
+    lexer::{
+        syntax::{Location, Span, TokenStream},
+        Lexer,
+    },
+    parser::{
+        ast::statements::Program,
+        cst::{
+            self,
+            nodes::{ProgramSyntax, TypedNode},
+        },
+        Parser,
+    },
 };
 
+/// Identifies a file loaded into a `SourceMap`
+///
+/// Files that were never added to a map all share the default id
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FileId(u32);
+
+impl FileId {
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Owns every file of a program, so spans only need to carry a `FileId`
+#[derive(Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes ownership of `file`, handing out the id its spans will refer to
+    pub fn add(&mut self, mut file: SourceFile) -> FileId {
+        let id = FileId(self.files.len() as u32);
+        file.id = id;
+        self.files.push(file);
+
+        id
+    }
+
+    pub fn get(&self, id: FileId) -> &SourceFile {
+        &self.files[id.index()]
+    }
+
+    /// Returns the id of the file loaded from `absolute_path`, if there is one
+    pub fn find(&self, absolute_path: &str) -> Option<FileId> {
+        self.files
+            .iter()
+            .find(|file| file.absolute_path == absolute_path)
+            .map(|file| file.id)
+    }
+
+    pub fn files(&self) -> impl Iterator<Item = &SourceFile> {
+        self.files.iter()
+    }
+
+    /// Resolves the start of `span` in the file it points into
+    pub fn location(&self, span: &Span) -> Location {
+        self.get(span.file).location(span.start as usize)
+    }
+}
+
+/// A change to the text of a file, replacing the characters covered by `span` with `text`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextEdit {
+    pub span: Span,
+    pub text: String,
+}
+
+impl TextEdit {
+    pub fn new(span: Span, text: impl Into<String>) -> Self {
+        Self {
+            span,
+            text: text.into(),
+        }
+    }
+
+    /// The change in length of the text, in bytes
+    pub fn delta(&self) -> isize {
+        self.text.len() as isize - self.span.len() as isize
+    }
+}
+
 pub struct SourceFile {
     pub absolute_path: String,
+    /// The name shown in diagnostics
     pub relative_path: String,
-    pub source: String,
+    id: FileId,
+    source: String,
+    /// The byte offset at which every line starts
+    line_starts: Vec<u32>,
 }
 
 impl SourceFile {
-    pub fn new(path: &str) -> Self {
-        let source = fs::read_to_string(path).unwrap();
-        let executable_dir = utility::get_executable_dir();
-        let absolute_path = path.to_string();
+    /// Creates a file that only lives in memory, e.g. an editor buffer or stdin
+    ///
+    /// `name` is used as both paths, since there is nothing on disk to point at
+    pub fn from_string(name: impl Into<String>, source: impl Into<String>) -> Self {
+        let name = name.into();
+
+        Self::with_paths(name.clone(), name, source.into())
+    }
+
+    /// Reads the file at `path` from disk
+    ///
+    /// Files inside the current directory are named relative to it, any other file is named by
+    /// the path it was opened with
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)?;
+
+        let current_dir = env::current_dir().ok();
+        let absolute_path = fs::canonicalize(path).unwrap_or_else(|_| match &current_dir {
+            Some(dir) => dir.join(path),
+            None => path.to_path_buf(),
+        });
+        let relative_path = current_dir
+            .and_then(|dir| diff_paths(&absolute_path, fs::canonicalize(&dir).unwrap_or(dir)))
+            .filter(|relative| {
+                let parent = relative.components().next() == Some(Component::ParentDir);
+                !relative.as_os_str().is_empty() && !parent
+            })
+            .unwrap_or_else(|| PathBuf::from(path));
+
+        Ok(Self::with_paths(
+            absolute_path.to_string_lossy().into_owned(),
+            relative_path.to_string_lossy().into_owned(),
+            source,
+        ))
+    }
+
+    fn with_paths(absolute_path: String, relative_path: String, source: String) -> Self {
+        let mut line_starts = vec![0];
+        for (index, byte) in source.bytes().enumerate() {
+            if byte == b'\n' {
+                line_starts.push(index as u32 + 1);
+            }
+        }
 
         Self {
             absolute_path,
-            relative_path: diff_paths(PathBuf::from_str(path).unwrap(), executable_dir)
-                .expect("could not compute relative path")
-                .to_str()
-                .unwrap()
-                .to_owned(), // hell.
+            relative_path,
+            id: FileId::default(),
             source,
+            line_starts,
         }
     }
 
-    pub fn tokenize(self) -> TokenStream {
+    /// Returns the file with `edit` applied, keeping its paths and id
+    pub fn with_edit(&self, edit: &TextEdit) -> SourceFile {
+        let mut source = self.source.clone();
+        source.replace_range(edit.span.range(), &edit.text);
+
+        let mut file = Self::with_paths(
+            self.absolute_path.clone(),
+            self.relative_path.clone(),
+            source,
+        );
+        file.id = self.id;
+        file
+    }
+
+    pub fn id(&self) -> FileId {
+        self.id
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns the text covered by `span`
+    pub fn text(&self, span: Span) -> &str {
+        &self.source[span.range()]
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Returns the byte offset at which the zero-based `line` starts
+    pub fn line_start(&self, line: usize) -> usize {
+        self.line_starts[line] as usize
+    }
+
+    /// Resolves a byte offset into a line and column, the column counting characters rather
+    /// than bytes
+    pub fn location(&self, position: usize) -> Location {
+        let line = match self.line_starts.binary_search(&(position as u32)) {
+            Ok(line) => line,
+            Err(next_line) => next_line - 1,
+        };
+
+        Location {
+            line: line + 1,
+            column: self.source[self.line_start(line)..position].chars().count(),
+        }
+    }
+
+    pub fn tokenize(&self) -> TokenStream {
         let mut lexer = Lexer::new(self);
         lexer.tokenize()
     }
 
-    pub fn parse(self) -> impl Expression {
+    pub fn parse(&self) -> Result<Program, Vec<Diagnostic>> {
         let tokens = self.tokenize();
-        let mut parser = Parser::new(tokens);
+        let mut parser = Parser::new(self, tokens);
         parser.parse()
     }
+
+    /// Parses the file even if it has syntax errors, see `Parser::parse_partial`
+    pub fn parse_partial(&self) -> (Program, Vec<Diagnostic>) {
+        let mut parser = Parser::new(self, self.tokenize());
+        parser.parse_partial()
+    }
+
+    /// Parses the file into a lossless concrete syntax tree, see `parser::cst`, returning it
+    /// along with every lexical and syntax diagnostic
+    ///
+    /// Like `parse_partial`, a tree is built even if the file has syntax errors, with the
+    /// source that could not be parsed in `Error` nodes
+    pub fn syntax_tree(&self) -> (ProgramSyntax, Vec<Diagnostic>) {
+        let mut parser = Parser::new(self, self.tokenize());
+        let (program, diagnostics) = parser.parse_partial();
+        let root = cst::builder::build(parser.tokens(), &program, self);
+
+        (ProgramSyntax::cast(root).unwrap(), diagnostics)
+    }
 }
@@ -0,0 +1,157 @@
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, io::Write, rc::Rc};
+
+    use crate::cli::{Cli, EXIT_FAILURE, EXIT_SUCCESS, EXIT_USAGE};
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    struct Output {
+        code: i32,
+        stdout: String,
+        stderr: String,
+    }
+
+    #[test]
+    fn runs_programs_from_stdin() -> () {
+        let output = run(&["run", "-"], "let a = 1\nprint(a + 2)");
+
+        assert_eq!(EXIT_SUCCESS, output.code);
+        assert_eq!("3\n", output.stdout);
+        assert_eq!("", output.stderr);
+    }
+
+    #[test]
+    fn reports_diagnostics_with_exit_code_one() -> () {
+        let cases = [
+            (vec!["run", "-"], "print(1 +)", "error[E0100]"),
+            (vec!["run", "-"], "print(x)", "error[E0400]"),
+            (vec!["run", "-"], "let a: int = true", "error[E0501]"),
+            (vec!["run", "-"], "print(1 ~/ 0)", "error[E0302]"),
+            (vec!["check", "-"], "let a = 1 let a = 2", "error[E0401]"),
+            (vec!["tokens", "-"], "let a = `", "error[E0001]"),
+            (vec!["ast", "-"], "let = 1", "error[E0100]"),
+        ];
+
+        for (args, input, expected) in cases {
+            let output = run(&args, input);
+
+            assert_eq!(EXIT_FAILURE, output.code, "{}", input);
+            assert!(output.stderr.starts_with(expected), "{}", output.stderr);
+            assert!(
+                output.stderr.contains("--> <stdin>:1:"),
+                "{}",
+                output.stderr
+            );
+        }
+    }
+
+    #[test]
+    fn checks_without_running() -> () {
+        let output = run(&["check", "-"], "print(1)");
+
+        assert_eq!(EXIT_SUCCESS, output.code);
+        assert_eq!("", output.stdout);
+    }
+
+    #[test]
+    fn reports_every_syntax_error() -> () {
+        let output = run(&["check", "-"], "let a = )\nprint(a +)\nlet = 2");
+
+        assert_eq!(EXIT_FAILURE, output.code);
+        assert_eq!(
+            3,
+            output.stderr.matches("error[E0").count(),
+            "{}",
+            output.stderr
+        );
+        for line in 1..=3 {
+            let location = format!("--> <stdin>:{}:", line);
+            assert!(output.stderr.contains(&location), "{}", output.stderr);
+        }
+    }
+
+    #[test]
+    fn prints_tokens_and_syntax_trees() -> () {
+        let tokens = run(&["tokens", "-"], "let a =\n  1");
+        assert_eq!(
+            "1:1 LetKeyword \"let\"\n1:5 Identifier \"a\"\n1:7 Equals \"=\"\n2:3 IntLiteral \"1\"\n",
+            tokens.stdout
+        );
+
+        let ast = run(&["ast", "-"], "let a = 1 + 2 * 3\nprint(a)");
+        assert_eq!("(let a (+ 1 (* 2 3)))\n(call print a)\n", ast.stdout);
+    }
+
+    #[test]
+    fn formats_programs() -> () {
+        let output = run(&["fmt", "-"], "let a=1+2\nprint( a )");
+        assert_eq!(EXIT_SUCCESS, output.code);
+        assert_eq!("let a = 1 + 2\nprint(a)\n", output.stdout);
+
+        let output = run(&["fmt", "--check", "-"], "let a=1");
+        assert_eq!(EXIT_FAILURE, output.code);
+        assert_eq!("<stdin> is not formatted\n", output.stderr);
+
+        let output = run(&["fmt", "--check", "-"], "let a = 1\n");
+        assert_eq!(EXIT_SUCCESS, output.code);
+        assert_eq!("", output.stderr);
+    }
+
+    #[test]
+    fn rejects_invalid_usage_with_exit_code_two() -> () {
+        let cases: [&[&str]; 5] = [
+            &[],
+            &["run"],
+            &["frob", "-"],
+            &["run", "-", "extra"],
+            &["fmt", "--frob", "-"],
+        ];
+        for args in cases {
+            let output = run(args, "");
+
+            assert_eq!(EXIT_USAGE, output.code, "{:?}", args);
+            assert!(output.stderr.contains("usage: ion"), "{:?}", args);
+        }
+
+        let output = run(&["run", "does/not/exist.ion"], "");
+        assert_eq!(EXIT_USAGE, output.code);
+        assert!(output
+            .stderr
+            .starts_with("error: could not read 'does/not/exist.ion'"));
+
+        let output = run(&["--help"], "");
+        assert_eq!(EXIT_SUCCESS, output.code);
+        assert!(output.stdout.starts_with("usage: ion"));
+    }
+
+    fn run(args: &[&str], stdin: &str) -> Output {
+        let stdout = SharedBuffer::default();
+        let stderr = SharedBuffer::default();
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+
+        let cli = Cli::with_io(
+            Box::new(std::io::Cursor::new(stdin.to_string())),
+            Box::new(stdout.clone()),
+            Box::new(stderr.clone()),
+        );
+        let code = cli.run(&args);
+
+        Output {
+            code,
+            stdout: String::from_utf8(stdout.0.take()).unwrap(),
+            stderr: String::from_utf8(stderr.0.take()).unwrap(),
+        }
+    }
+}
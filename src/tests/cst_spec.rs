@@ -0,0 +1,251 @@
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::diagnostics::DiagnosticCode;
+    use crate::lexer::syntax::{SyntaxKind, TriviaKind};
+    use crate::parser::cst::nodes::{
+        ExpressionSyntax, ProgramSyntax, StatementSyntax, TypeSyntax, TypedNode,
+    };
+    use crate::source::SourceFile;
+
+    #[test]
+    fn reproduces_the_source_exactly() -> () {
+        let cases = vec![
+            "",
+            "  // only trivia\n",
+            "let a=1+2*-3",
+            "/// docs\npub fn add(a: int,b :int): int { return a + b } // trailing\n\n",
+            "if a { b } else if c { d } else { while e { for x in \"s\" { break } } }",
+            "import \"lib.ion\"\nimport lib . math /* a /* nested */ comment */\r\n",
+            "let f: fn(int, fn(): bool): null = g\nprint(f((1)) (2), h = !i)\t",
+        ];
+
+        for source in cases {
+            let file = SourceFile::from_string("test.ion", source);
+            let (root, diagnostics) = file.syntax_tree();
+
+            assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+            assert_eq!(source, root.syntax().text());
+            for token in root.syntax().tokens() {
+                let span = token.span();
+                let text: String = source
+                    .chars()
+                    .skip(span.start as usize)
+                    .take(span.len())
+                    .collect();
+                assert_eq!(text, token.text());
+            }
+        }
+    }
+
+    #[test]
+    fn groups_tokens_into_nodes() -> () {
+        let root = syntax_tree("/// docs\nlet a: int = f(1, -(2)) // c\n");
+
+        assert_eq!(
+            "Program@0..38
+  LetStatement@0..37
+    LetKeyword@9..12 \"let\"
+    Identifier@13..14 \"a\"
+    Colon@14..15 \":\"
+    NamedType@16..20
+      Identifier@16..19 \"int\"
+    Equals@20..21 \"=\"
+    CallExpression@22..37
+      VariableExpression@22..23
+        Identifier@22..23 \"f\"
+      ArgumentList@23..37
+        LeftParen@23..24 \"(\"
+        LiteralExpression@24..25
+          IntLiteral@24..25 \"1\"
+        Comma@25..26 \",\"
+        UnaryExpression@27..31
+          Minus@27..28 \"-\"
+          GroupingExpression@28..31
+            LeftParen@28..29 \"(\"
+            LiteralExpression@29..30
+              IntLiteral@29..30 \"2\"
+            RightParen@30..31 \")\"
+        RightParen@31..32 \")\"
+  EndOfFile@38..38 \"\"
+",
+            root.syntax().debug_tree()
+        );
+
+        let keyword = root.syntax().first_token().unwrap();
+        assert_eq!(TriviaKind::DocComment, keyword.leading_trivia()[0].kind);
+        let parenthesis = root.syntax().token_at(31).unwrap();
+        assert_eq!(
+            " // c",
+            parenthesis.green().full_text().trim_start_matches(')')
+        );
+    }
+
+    #[test]
+    fn builds_a_tree_for_a_file_with_syntax_errors() -> () {
+        let source = "let a = 1 +\nlet b = ) 2\nprint(b)\n";
+        let (root, diagnostics) = SourceFile::from_string("test.ion", source).syntax_tree();
+
+        assert_eq!(source, root.syntax().text());
+        let codes: Vec<DiagnosticCode> = diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.code)
+            .collect();
+        assert_eq!(
+            vec![
+                DiagnosticCode::UnexpectedToken,
+                DiagnosticCode::UnexpectedToken
+            ],
+            codes
+        );
+
+        let kinds: Vec<SyntaxKind> = root.syntax().children().map(|node| node.kind()).collect();
+        assert_eq!(
+            vec![
+                SyntaxKind::LetStatement,
+                SyntaxKind::LetStatement,
+                SyntaxKind::Error,
+                SyntaxKind::ExpressionStatement,
+            ],
+            kinds
+        );
+        let error = root.syntax().child_of_kind(SyntaxKind::Error).unwrap();
+        assert_eq!(") 2", error.text());
+        let statements = root.statements();
+        assert!(matches!(statements[2], StatementSyntax::Error(_)));
+        let StatementSyntax::Expression(statement) = &statements[3] else {
+            panic!("expected an expression statement");
+        };
+        assert!(matches!(
+            statement.expression(),
+            Some(ExpressionSyntax::Call(_))
+        ));
+    }
+
+    #[test]
+    fn navigates_up_and_down_the_tree() -> () {
+        let source = "fn f(a: int) { if a { print(a + 1) } }";
+        let root = syntax_tree(source);
+
+        let one = root.syntax().token_at(source.find('1').unwrap()).unwrap();
+        let kinds: Vec<SyntaxKind> = one.parent().ancestors().map(|node| node.kind()).collect();
+        assert_eq!(
+            vec![
+                SyntaxKind::LiteralExpression,
+                SyntaxKind::BinaryExpression,
+                SyntaxKind::ArgumentList,
+                SyntaxKind::CallExpression,
+                SyntaxKind::ExpressionStatement,
+                SyntaxKind::BlockStatement,
+                SyntaxKind::IfStatement,
+                SyntaxKind::BlockStatement,
+                SyntaxKind::FunctionDeclaration,
+                SyntaxKind::Program,
+            ],
+            kinds
+        );
+
+        let covering = root.syntax().covering_node(one.span().to(&one.span()));
+        assert_eq!(SyntaxKind::LiteralExpression, covering.kind());
+        let binary = covering.parent().unwrap();
+        assert_eq!("a + 1", binary.text());
+        assert_eq!(Some(covering.clone()), binary.children().nth(1));
+        assert_eq!(None, covering.next_sibling());
+        assert_eq!("a", covering.previous_sibling().unwrap().text().trim());
+
+        let function = root
+            .syntax()
+            .child_of_kind(SyntaxKind::FunctionDeclaration)
+            .unwrap();
+        let names: Vec<String> = function
+            .descendants()
+            .filter(|node| node.kind() == SyntaxKind::VariableExpression)
+            .map(|node| node.text().trim().to_string())
+            .collect();
+        assert_eq!(vec!["a", "print", "a"], names);
+        assert_eq!(
+            Some(SyntaxKind::Identifier),
+            function
+                .token_of_kind(SyntaxKind::Identifier)
+                .map(|token| token.kind())
+        );
+    }
+
+    #[test]
+    fn exposes_typed_nodes() -> () {
+        let root = syntax_tree(
+            "pub fn f(a: int, g: fn(int): bool): bool { return g(a) }\nif f(1, g) { x = 2 } else { -3 }",
+        );
+        let statements = root.statements();
+
+        let StatementSyntax::Function(function) = &statements[0] else {
+            panic!("expected a function, got {:?}", statements[0]);
+        };
+        assert!(function.visibility().is_some());
+        assert_eq!("f", function.name().unwrap().text());
+        let parameters: Vec<String> = function
+            .parameters()
+            .iter()
+            .map(|parameter| parameter.name().unwrap().text().to_string())
+            .collect();
+        assert_eq!(vec!["a", "g"], parameters);
+        let Some(TypeSyntax::Function(function_type)) = function.parameters()[1].type_annotation()
+        else {
+            panic!("expected a function type");
+        };
+        assert_eq!(1, function_type.parameters().len());
+        assert_eq!("bool", function_type.return_type().unwrap().syntax().text());
+        assert_eq!("bool ", function.return_type().unwrap().syntax().text());
+        assert_eq!(1, function.body().unwrap().statements().len());
+
+        let StatementSyntax::If(if_statement) = &statements[1] else {
+            panic!("expected an if statement, got {:?}", statements[1]);
+        };
+        let Some(ExpressionSyntax::Call(call)) = if_statement.condition() else {
+            panic!("expected a call");
+        };
+        assert_eq!(2, call.arguments().len());
+        let then_branch = if_statement.then_branch().unwrap().statements();
+        let StatementSyntax::Expression(assignment) = &then_branch[0] else {
+            panic!("expected an expression statement");
+        };
+        let Some(ExpressionSyntax::Assignment(assignment)) = assignment.expression() else {
+            panic!("expected an assignment");
+        };
+        assert_eq!("x", assignment.name().unwrap().text());
+        assert!(matches!(
+            if_statement.else_branch(),
+            Some(StatementSyntax::Block(_))
+        ));
+        assert!(StatementSyntax::cast(call.syntax().clone()).is_none());
+    }
+
+    #[test]
+    fn shares_green_nodes_between_red_nodes() -> () {
+        let root = syntax_tree("let a = 1 + 2\nlet b = 1 + 2");
+        let statements = root.statements();
+
+        let first = statements[0]
+            .syntax()
+            .child_of_kind(SyntaxKind::BinaryExpression);
+        let second = statements[1]
+            .syntax()
+            .child_of_kind(SyntaxKind::BinaryExpression);
+        let (first, second) = (first.unwrap(), second.unwrap());
+        assert_eq!(first.green(), second.green());
+        assert_ne!(first, second);
+
+        let again = statements[0]
+            .syntax()
+            .child_of_kind(SyntaxKind::BinaryExpression);
+        assert!(Rc::ptr_eq(first.green(), again.unwrap().green()));
+    }
+
+    fn syntax_tree(source: &str) -> ProgramSyntax {
+        let (root, diagnostics) = SourceFile::from_string("test.ion", source).syntax_tree();
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+
+        root
+    }
+}
@@ -0,0 +1,102 @@
+#[cfg(test)]
+mod tests {
+    use crate::diagnostics::{renderer::Renderer, Diagnostic, DiagnosticCode};
+    use crate::lexer::syntax::Span;
+    use crate::source::SourceFile;
+
+    #[test]
+    fn renders_caret_under_span() -> () {
+        let file = source_file("let x = `\n");
+        let diagnostics = file.tokenize().diagnostics;
+
+        assert_eq!(
+            "error[E0001]: unexpected character '`'\n \
+             --> test.ion:1:9\n  \
+             |\n\
+             1 | let x = `\n  \
+             |         ^\n",
+            Renderer::plain().render(&diagnostics[0], &file)
+        );
+    }
+
+    #[test]
+    fn renders_caret_after_multibyte_characters() -> () {
+        let file = source_file("let café = \"☕\" `\n");
+        let diagnostics = file.tokenize().diagnostics;
+
+        assert_eq!(
+            "error[E0001]: unexpected character '`'\n \
+             --> test.ion:1:16\n  \
+             |\n\
+             1 | let café = \"☕\" `\n  \
+             |                ^\n",
+            Renderer::plain().render(&diagnostics[0], &file)
+        );
+    }
+
+    #[test]
+    fn renders_labels_notes_and_help() -> () {
+        let file = source_file("let a = 1\n\n\n\nlet a = 2\n");
+        let diagnostic = Diagnostic::error(
+            DiagnosticCode::UnexpectedToken,
+            "duplicate declaration",
+            span(&file, 17, 18),
+        )
+        .with_label(span(&file, 4, 5), "first declared here")
+        .with_note("names must be unique within a scope")
+        .with_help("rename one of the bindings");
+
+        assert_eq!(
+            "error[E0100]: duplicate declaration\n \
+             --> test.ion:5:5\n  \
+             |\n\
+             1 | let a = 1\n  \
+             |     - first declared here\n\
+             ...\n\
+             5 | let a = 2\n  \
+             |     ^\n  \
+             |\n  \
+             = note: names must be unique within a scope\n  \
+             = help: rename one of the bindings\n",
+            Renderer::plain().render(&diagnostic, &file)
+        );
+    }
+
+    #[test]
+    fn renders_spans_across_multiple_lines() -> () {
+        let file = source_file("1 + \"abc\ndef");
+        let diagnostics = file.tokenize().diagnostics;
+
+        assert_eq!(
+            "error[E0002]: unterminated string literal\n \
+             --> test.ion:1:5\n  \
+             |\n\
+             1 | 1 + \"abc\n  \
+             |     ^^^^\n\
+             2 | def\n  \
+             | ^^^\n",
+            Renderer::plain().render(&diagnostics[0], &file)
+        );
+    }
+
+    #[test]
+    fn renders_ansi_colors_only_when_enabled() -> () {
+        let file = source_file("`");
+        let diagnostics = file.tokenize().diagnostics;
+
+        assert!(Renderer::new(true)
+            .render(&diagnostics[0], &file)
+            .contains("\x1b[1;31merror[E0001]\x1b[0m"));
+        assert!(!Renderer::plain()
+            .render(&diagnostics[0], &file)
+            .contains('\x1b'));
+    }
+
+    fn span(file: &SourceFile, start: usize, end: usize) -> Span {
+        Span::new(file.id(), start, end)
+    }
+
+    fn source_file(source: &str) -> SourceFile {
+        SourceFile::from_string("test.ion", source)
+    }
+}
@@ -0,0 +1,161 @@
+#[cfg(test)]
+mod tests {
+    use crate::formatter;
+    use crate::parser::ast::printer::Printer;
+    use crate::source::SourceFile;
+
+    #[test]
+    fn normalizes_spacing() -> () {
+        assert_formats(vec![
+            ("let a=1+2*-3", "let a = 1 + 2 * -3\n"),
+            ("let  b :int=( a )~/2", "let b: int = (a) ~/ 2\n"),
+            (
+                "pub let f:fn(int,int):bool=g",
+                "pub let f: fn(int, int): bool = g\n",
+            ),
+            ("print( a ,b(c) ) a=b=!c", "print(a, b(c))\na = b = !c\n"),
+            (
+                "import  \"lib.ion\" import lib . math",
+                "import \"lib.ion\"\nimport lib.math\n",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn indents_blocks() -> () {
+        assert_formats(vec![
+            ("fn f(){}", "fn f() {}\n"),
+            (
+                "pub fn add(a:int,b:int):int{return a+b}",
+                "pub fn add(a: int, b: int): int {\n    return a + b\n}\n",
+            ),
+            (
+                "if a{b}else if c{d}else{while e{if f{break}continue}}",
+                "if a {\n    b\n} else if c {\n    d\n} else {\n    while e {\n        if f {\n            \
+                 break\n        }\n        continue\n    }\n}\n",
+            ),
+            (
+                "for c in \"abc\" {{print(c)}}",
+                "for c in \"abc\" {\n    {\n        print(c)\n    }\n}\n",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn keeps_single_blank_lines_between_statements() -> () {
+        assert_formats(vec![
+            ("a\n\n\n\nb\nc", "a\n\nb\nc\n"),
+            (
+                "fn f() {\n\n  a\n\n  b\n\n}\n\n\n",
+                "fn f() {\n    a\n\n    b\n}\n",
+            ),
+            ("\n\nlet a = 1", "let a = 1\n"),
+        ]);
+    }
+
+    #[test]
+    fn preserves_comments() -> () {
+        assert_formats(vec![
+            (
+                "// header\n\n/// docs\nfn f(){ // opening\n/* a /* b */ */\na\n\n\n  // closing\n}",
+                "// header\n\n/// docs\nfn f() { // opening\n    /* a /* b */ */\n    a\n\n    \
+                 // closing\n}\n",
+            ),
+            ("let a=1   // one\nfn g(){// empty\n}", "let a = 1 // one\nfn g() { // empty\n}\n"),
+            ("let a = /* inner */ 1\n// end", "/* inner */\nlet a = 1\n// end\n"),
+        ]);
+    }
+
+    #[test]
+    fn writes_comments_inside_statements_before_them() -> () {
+        assert_formats(vec![
+            (
+                "let x = /* inline */ add(1, // between args\n2)\nlet y = 3",
+                "/* inline */\n// between args\nlet x = add(1, 2)\nlet y = 3\n",
+            ),
+            (
+                "let a = 1\n\nfn g(a: int, /* p */ b: int) {\n  // body\n  return a\n}",
+                "let a = 1\n\n/* p */\nfn g(a: int, b: int) {\n    // body\n    return a\n}\n",
+            ),
+            (
+                "if a { // then\nb\n} else /* e */ { c }",
+                "/* e */\nif a { // then\n    b\n} else {\n    c\n}\n",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn keeps_comments_after_one_line_blocks_after_the_block() -> () {
+        assert_formats(vec![
+            (
+                "fn f() { return 1 } // trailing\nprint(f())",
+                "fn f() {\n    return 1\n} // trailing\nprint(f())\n",
+            ),
+            (
+                "while a { b /* inner */ } // after",
+                "while a {\n    b /* inner */\n} // after\n",
+            ),
+            (
+                "if a { let b = 1 /* why b */ let c = 2 /* why c */ }",
+                "if a {\n    let b = 1 /* why b */\n    let c = 2 /* why c */\n}\n",
+            ),
+            ("if a { /* first */ b }", "if a { /* first */\n    b\n}\n"),
+        ]);
+    }
+
+    #[test]
+    fn does_not_add_blank_lines_around_moved_comments() -> () {
+        assert_formats(vec![
+            (
+                "let x = add(1, // c\n2,\n3)\nlet y = 3",
+                "// c\nlet x = add(1, 2, 3)\nlet y = 3\n",
+            ),
+            (
+                "fn f() {\n  let x = g(1, // c\n  2)\n  return x\n}",
+                "fn f() {\n    // c\n    let x = g(1, 2)\n    return x\n}\n",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn wraps_long_argument_and_parameter_lists() -> () {
+        let long = "x".repeat(60);
+        let input = format!(
+            "fn f(a: int, {long}: int, b: fn(int): bool): int {{ print({long}, g({long}, {long}), 1) }}"
+        );
+        let expected = format!(
+            "fn f(\n    a: int,\n    {long}: int,\n    b: fn(int): bool\n): int {{\n    print(\n        \
+             {long},\n        g(\n            {long},\n            {long}\n        ),\n        1\n    )\n}}\n"
+        );
+
+        assert_formats(vec![(input.as_str(), expected.as_str())]);
+    }
+
+    #[test]
+    fn preserves_the_syntax_tree() -> () {
+        let input =
+            "let a = -(1 + 2) ^ --3\nfn f(g: fn(): int) { return g() }\nprint(f(\"s\"), a = 2)";
+
+        let formatted = format(input);
+        assert_eq!(print(input), print(&formatted));
+    }
+
+    fn assert_formats(cases: Vec<(&str, &str)>) -> () {
+        for (input, expected) in cases {
+            let formatted = format(input);
+            assert_eq!(expected, formatted, "{}", input);
+            assert_eq!(formatted, format(&formatted), "not idempotent: {}", input);
+            assert_eq!(print(input), print(&formatted), "{}", input);
+        }
+    }
+
+    fn format(input: &str) -> String {
+        formatter::format(&SourceFile::from_string("test.ion", input)).expect("failed to parse")
+    }
+
+    fn print(input: &str) -> String {
+        let file = SourceFile::from_string("test.ion", input);
+        let program = file.parse().unwrap();
+        Printer::new(&file).print_program(&program)
+    }
+}
@@ -0,0 +1,154 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        lexer::syntax::Span,
+        parser::incremental::ParsedFile,
+        source::{SourceFile, TextEdit},
+    };
+
+    const SOURCE: &str = "let total = 0
+fn add(value: int): int {
+  let sum = value + total // keep a running sum
+  if sum > 10 {
+    print(\"big\")
+  } else {
+    print(sum)
+  }
+  return sum
+}
+while total < 3 {
+  total = add(1)
+}
+print(total)
+";
+
+    #[test]
+    fn matches_a_full_parse_after_every_edit() -> () {
+        assert_edits(
+            SOURCE,
+            vec![
+                ("value + total", "value * 2"),
+                ("sum > 10", "sum >= 100"),
+                ("print(sum)", "print(sum)\n    print(total)"),
+                ("// keep a running sum", "// comment"),
+                ("total = add(1)", "total = add(1) + add(2)"),
+                ("print(total)\n", "print(total)\nlet done = true\n"),
+                ("let total = 0", "let total = 1"),
+                ("print(\"big\")", "print(\"big {sum + total}\")"),
+                ("{sum + total}", "{sum}"),
+                ("let total = 1", "let total = 10"),
+                ("print(\"big {sum}\")", "print(\"bïg ✓ {sum}\") // ñ"),
+                ("total = add(1)", "total = add(1) // ü"),
+                ("print(total)\nlet", "print(totel)\nlet"),
+            ],
+        );
+    }
+
+    #[test]
+    fn matches_a_full_parse_when_blocks_open_or_close() -> () {
+        assert_edits(
+            SOURCE,
+            vec![
+                ("  } else {", "  } else"),
+                ("  } else", "  } else {"),
+                ("  return sum\n}", "  return sum\n"),
+                ("while total", "}\nwhile total"),
+                ("{\n  total", "{{\n  total"),
+            ],
+        );
+    }
+
+    #[test]
+    fn matches_a_full_parse_when_errors_come_and_go() -> () {
+        assert_edits(
+            SOURCE,
+            vec![
+                ("value + total", "value +"),
+                ("let sum = value +", "let sum = value + total"),
+                ("print(\"big\")", "print(\"big)"),
+                ("print(\"big)", "print(\"big\")"),
+                ("return sum", "return )"),
+                ("return )", "break"),
+                ("print(total)", "print(total"),
+                ("let total = 0", "let total = "),
+                ("let total = ", "let total = 0 /* open"),
+            ],
+        );
+    }
+
+    #[test]
+    fn reparses_only_the_enclosing_block() -> () {
+        let mut parsed = ParsedFile::new(SourceFile::from_string("test.ion", SOURCE));
+        let count = parsed.tokens().len();
+
+        // the `{` before `print` through `)` are lexed again, 5 old tokens being replaced by 7 new ones
+        let reparse = parsed.edit(&edit(parsed.file(), "print(sum)", "print(sum + 1)"));
+        assert_eq!(7, reparse.relexed);
+        assert_eq!(count - 5 - 31, reparse.reused);
+        assert_eq!("print(sum + 1)", text(&parsed, reparse.reparsed));
+
+        let reparse = parsed.edit(&edit(parsed.file(), "add(1)", "add(2)"));
+        assert_eq!("total = add(2)", text(&parsed, reparse.reparsed));
+
+        let reparse = parsed.edit(&edit(parsed.file(), "print(total)", "print(total, 2)"));
+        let reparsed = text(&parsed, reparse.reparsed);
+        assert!(reparsed.starts_with("while total < 3 {"), "{}", reparsed);
+        assert!(reparsed.ends_with("print(total, 2)"), "{}", reparsed);
+    }
+
+    /// Applies each edit in turn, replacing the first occurrence of a text, and compares the
+    /// file with one parsed from scratch after each of them
+    fn assert_edits(source: &str, edits: Vec<(&str, &str)>) -> () {
+        let mut parsed = ParsedFile::new(SourceFile::from_string("test.ion", source));
+        for (old, new) in edits {
+            parsed.edit(&edit(parsed.file(), old, new));
+
+            let file = parsed.file();
+            let expected = ParsedFile::new(SourceFile::from_string("test.ion", file.source()));
+            let context = format!("after replacing {:?} with {:?}", old, new);
+            assert_eq!(expected.file().source(), file.source(), "{}", context);
+            assert_eq!(
+                format!("{:?}", expected.tokens()),
+                format!("{:?}", parsed.tokens()),
+                "{}",
+                context
+            );
+            for (expected, actual) in expected.tokens().iter().zip(parsed.tokens()) {
+                assert_eq!(
+                    expected.leading_trivia, actual.leading_trivia,
+                    "{}",
+                    context
+                );
+                assert_eq!(
+                    expected.trailing_trivia, actual.trailing_trivia,
+                    "{}",
+                    context
+                );
+            }
+            assert_eq!(expected.end_trivia(), parsed.end_trivia(), "{}", context);
+            assert_eq!(
+                format!("{:?}", expected.program()),
+                format!("{:?}", parsed.program()),
+                "{}",
+                context
+            );
+            assert_eq!(
+                format!("{:?}", expected.diagnostics()),
+                format!("{:?}", parsed.diagnostics()),
+                "{}",
+                context
+            );
+        }
+    }
+
+    fn edit(file: &SourceFile, old: &str, new: &str) -> TextEdit {
+        let start = file.source().find(old).expect("the text to replace exists");
+        let span = Span::new(file.id(), start, start + old.len());
+
+        TextEdit::new(span, new)
+    }
+
+    fn text(parsed: &ParsedFile, span: Span) -> &str {
+        parsed.file().text(span)
+    }
+}
@@ -0,0 +1,277 @@
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, io::Write, rc::Rc};
+
+    use crate::diagnostics::DiagnosticCode;
+    use crate::interpreter::{error::RuntimeErrorKind, Interpreter};
+    use crate::source::SourceFile;
+
+    /// Collects everything the program prints, so tests can inspect it after running
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn evaluates_arithmetic_operators() -> () {
+        assert_prints(vec![
+            ("print(1 + 2 * 3)", "7"),
+            ("print(7 - 10)", "-3"),
+            ("print(1 / 2, 4 / 2)", "0.5 2.0"),
+            ("print(7 ~/ 2, -7 ~/ 2, 7 ~/ -2)", "3 -4 -4"),
+            ("print(7 % 3, -7 % 3, 7 % -3)", "1 2 -2"),
+            ("print(2 ^ 3 ^ 2, 2 ^ -1.0)", "512 0.5"),
+            ("print(1 + 0.5, 7.5 ~/ 2)", "1.5 3.0"),
+            ("print(-(1 + 2), --3)", "-3 3"),
+            ("print(\"ab\" + \"cd\")", "abcd"),
+            (
+                "print(0xff + 1_000, 0b11 * 0o10, 1e3 + 5u8)",
+                "1255 24 1005.0",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn interpolates_strings() -> () {
+        assert_prints(vec![
+            ("let name = \"ion\" print(\"hello {name}!\")", "hello ion!"),
+            ("print(\"{1 + 1} {2.5} {null} {true}\")", "2 2.5 null true"),
+            (
+                "print('tab\\tquote\\\" \\u{1F600} \\{}')",
+                "tab\tquote\" \u{1F600} {}",
+            ),
+            ("print(r\"{a}\\n\")", "{a}\\n"),
+        ]);
+    }
+
+    #[test]
+    fn evaluates_bitwise_and_logical_operators() -> () {
+        assert_prints(vec![
+            ("print(6 & 3, 6 | 3, 6 ~ 3, ~0)", "2 7 5 -1"),
+            (
+                "print(true & false, true | false, true ~ true)",
+                "false true false",
+            ),
+            (
+                "print(!true, true && false, false || true)",
+                "false false true",
+            ),
+            ("print(false && 1 / 0 == 0, true || x)", "false true"),
+        ]);
+    }
+
+    #[test]
+    fn evaluates_comparisons() -> () {
+        assert_prints(vec![
+            (
+                "print(1 < 2, 2 <= 2, 3 > 4, 4 >= 5)",
+                "true true false false",
+            ),
+            ("print(1 == 1.0, 1 != 2, \"a\" == \"a\")", "true true true"),
+            (
+                "print(\"a\" < \"b\", null == null, 1 == \"1\")",
+                "true true false",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn scopes_let_bindings_lexically() -> () {
+        assert_prints(vec![
+            ("let a = 1 { let a = 2 print(a) } print(a)", "2\n1"),
+            ("let a = 1 { a = 2 } print(a)", "2"),
+            ("let a = 1 let a = a + 1 print(a)", "2"),
+            ("let a = 1 print(a = 5, a)", "5 5"),
+        ]);
+    }
+
+    #[test]
+    fn calls_functions_and_closures() -> () {
+        assert_prints(vec![
+            (
+                "fn add(a: int, b: int): int { return a + b } print(add(1, 2))",
+                "3",
+            ),
+            ("fn f() {} print(f())", "null"),
+            (
+                "fn fib(n: int): int { if n < 2 { return n } return fib(n - 1) + fib(n - 2) } \
+                 print(fib(15))",
+                "610",
+            ),
+            (
+                "fn counter() { let count = 0 \
+                 fn next(): int { count = count + 1 return count } return next } \
+                 let next = counter() next() print(next(), counter()())",
+                "2 1",
+            ),
+            ("print(print)", "<native fn print>"),
+        ]);
+    }
+
+    #[test]
+    fn runs_control_flow() -> () {
+        assert_prints(vec![
+            (
+                "if 1 > 2 { print(1) } else if 2 > 1 { print(2) } else { print(3) }",
+                "2",
+            ),
+            ("let i = 0 while i < 3 { i = i + 1 } print(i)", "3"),
+            ("for i in range(0, 3) { print(i) }", "0\n1\n2"),
+            ("for c in \"ab\" { print(c) }", "a\nb"),
+            ("for i in range(0, 300000000) { print(i) break }", "0"),
+            (
+                "for i in range(0, 10) { if i == 1 { continue } if i == 3 { break } print(i) }",
+                "0\n2",
+            ),
+            (
+                "let i = 0 while true { i = i + 1 if i == 5 { break } } print(i)",
+                "5",
+            ),
+            (
+                "fn first(): int { for i in range(5, 10) { return i } return 0 } print(first())",
+                "5",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn reports_runtime_errors_at_failing_node() -> () {
+        let cases = vec![
+            (
+                "x",
+                RuntimeErrorKind::UndefinedVariable("x".to_string()),
+                "x",
+            ),
+            (
+                "x = 1",
+                RuntimeErrorKind::UndefinedVariable("x".to_string()),
+                "x",
+            ),
+            ("1 + 1 / 0", RuntimeErrorKind::DivisionByZero, "1 / 0"),
+            ("1 % 0.0", RuntimeErrorKind::DivisionByZero, "1 % 0.0"),
+            ("2 ^ 63", RuntimeErrorKind::IntegerOverflow, "2 ^ 63"),
+            ("1 + 2 ^ -1", RuntimeErrorKind::NegativeExponent, "2 ^ -1"),
+            (
+                "-(-9223372036854775807 - 1)",
+                RuntimeErrorKind::IntegerOverflow,
+                "-(-9223372036854775807 - 1)",
+            ),
+            ("1(2)", RuntimeErrorKind::NotCallable("int"), "1(2)"),
+            (
+                "fn f(a: int) {} f()",
+                RuntimeErrorKind::ArityMismatch {
+                    expected: 1,
+                    found: 0,
+                },
+                "f()",
+            ),
+            (
+                "range(1)",
+                RuntimeErrorKind::ArityMismatch {
+                    expected: 2,
+                    found: 1,
+                },
+                "range(1)",
+            ),
+            ("for i in 1 {}", RuntimeErrorKind::NotIterable("int"), "1"),
+            ("if 1 {}", RuntimeErrorKind::InvalidCondition("int"), "1"),
+            ("fn f() { f() } f()", RuntimeErrorKind::StackOverflow, "f()"),
+            (
+                "1 && true",
+                RuntimeErrorKind::InvalidOperand {
+                    operator: "&&".to_string(),
+                    operand: "int",
+                },
+                "1",
+            ),
+            (
+                "true - 1",
+                RuntimeErrorKind::InvalidOperands {
+                    operator: "-".to_string(),
+                    left: "bool",
+                    right: "int",
+                },
+                "true - 1",
+            ),
+            (
+                "!1",
+                RuntimeErrorKind::InvalidOperand {
+                    operator: "!".to_string(),
+                    operand: "int",
+                },
+                "!1",
+            ),
+        ];
+
+        for (input, kind, failing_source) in cases {
+            let file = source_file(input);
+            let program = file.parse().expect("failed to parse");
+            let error = Interpreter::with_output(Box::new(SharedBuffer::default()))
+                .interpret(&program)
+                .unwrap_err();
+
+            let span = &error.span;
+            let text: String = input
+                .chars()
+                .skip(span.start as usize)
+                .take(span.len())
+                .collect();
+
+            assert_eq!(kind, error.kind, "{}", input);
+            assert_eq!(failing_source, text, "{}", input);
+        }
+    }
+
+    #[test]
+    fn converts_runtime_errors_to_diagnostics() -> () {
+        let file = source_file("1 / 0");
+        let program = file.parse().unwrap();
+        let error = Interpreter::new().interpret(&program).unwrap_err();
+        let diagnostic = error.to_diagnostic();
+
+        assert_eq!(DiagnosticCode::DivisionByZero, diagnostic.code);
+        assert_eq!("division by zero", diagnostic.message);
+    }
+
+    #[test]
+    fn keeps_global_state_between_programs() -> () {
+        let buffer = SharedBuffer::default();
+        let interpreter = Interpreter::with_output(Box::new(buffer.clone()));
+
+        for input in ["let a = 1", "fn f(): int { return a + 1 }", "print(f())"] {
+            let program = source_file(input).parse().unwrap();
+            interpreter.interpret(&program).unwrap();
+        }
+
+        assert_eq!("2\n", String::from_utf8(buffer.0.take()).unwrap());
+    }
+
+    fn assert_prints(cases: Vec<(&str, &str)>) -> () {
+        for (input, expected) in cases {
+            assert_eq!(format!("{}\n", expected), run(input), "{}", input);
+        }
+    }
+
+    fn run(input: &str) -> String {
+        let program = source_file(input).parse().expect("failed to parse");
+        let buffer = SharedBuffer::default();
+
+        Interpreter::with_output(Box::new(buffer.clone()))
+            .interpret(&program)
+            .expect("failed to run");
+
+        String::from_utf8(buffer.0.take()).unwrap()
+    }
+
+    fn source_file(source: &str) -> SourceFile {
+        SourceFile::from_string("test.ion", source)
+    }
+}
@@ -1,36 +1,216 @@
 #[cfg(test)]
 mod tests {
-    use std::env;
-    use std::fs;
-    use std::sync::atomic::AtomicUsize;
-    use std::sync::atomic::Ordering;
+    use crate::diagnostics::DiagnosticCode;
+    use crate::lexer::syntax::{
+        LiteralValue, Location, StringPart, Token, TokenStream, Trivia, TriviaKind,
+    };
+    use crate::{
+        lexer::{syntax::SyntaxKind, Lexer},
+        source::SourceFile,
+    };
 
-    use crate::lexer::syntax::TokenStream;
-    use crate::{lexer::syntax::SyntaxKind, source::SourceFile};
+    #[test]
+    fn reports_unexpected_character() -> () {
+        let tokens = tokenize("`");
+        let token = tokens.first();
 
-    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        assert_eq!(SyntaxKind::Bad, token.kind);
+        assert_diagnostics(&tokens, vec![DiagnosticCode::UnexpectedCharacter]);
+        assert_eq!("unexpected character '`'", tokens.diagnostics[0].message);
+    }
 
     #[test]
-    #[should_panic(expected = "unexpected character")]
-    fn panics_when_unexpected_character() -> () {
-        tokenize("`");
+    fn reports_unterminated_string_literal() -> () {
+        let file = SourceFile::from_string("test.ion", "'abc");
+        let tokens = file.tokenize();
+        let token = tokens.first();
+
+        assert_eq!(SyntaxKind::Bad, token.kind);
+        assert_eq!("'abc", file.text(token.span));
+        assert_diagnostics(&tokens, vec![DiagnosticCode::UnterminatedString]);
     }
 
     #[test]
-    #[should_panic(expected = "unterminated string literal")]
-    fn panics_when_unterminated_string_literal() -> () {
-        tokenize("'abc");
+    fn reports_invalid_escapes() -> () {
+        let file = SourceFile::from_string("test.ion", "\"first\nline\n\\q \\u{110000} \\u{}\"");
+        let tokens = file.tokenize();
+
+        assert_eq!(SyntaxKind::StringLiteral, tokens.first().kind);
+        assert_diagnostics(
+            &tokens,
+            vec![
+                DiagnosticCode::InvalidEscape,
+                DiagnosticCode::InvalidEscape,
+                DiagnosticCode::InvalidEscape,
+            ],
+        );
+
+        let diagnostics = &tokens.diagnostics;
+        assert_eq!("unknown escape sequence '\\q'", diagnostics[0].message);
+        assert_eq!("invalid unicode escape", diagnostics[1].message);
+        assert_eq!(10, diagnostics[1].span.len());
+        assert_eq!(
+            Location { line: 3, column: 0 },
+            file.location(diagnostics[0].span.start as usize)
+        );
     }
 
     #[test]
-    #[should_panic(expected = "malformed number literal")]
-    fn panics_when_malformed_number_literal() -> () {
-        tokenize("1.2.3");
+    fn reports_malformed_number_literal() -> () {
+        let file = SourceFile::from_string("test.ion", "1.2.3");
+        let tokens = file.tokenize();
+        let token = tokens.first();
+
+        assert_eq!(SyntaxKind::Bad, token.kind);
+        assert_eq!("1.2.3", file.text(token.span));
+        assert_eq!(5, tokens.diagnostics[0].span.len());
+        assert_diagnostics(&tokens, vec![DiagnosticCode::MalformedNumber]);
+    }
+
+    #[test]
+    fn reports_invalid_number_literals() -> () {
+        let cases = vec![
+            (
+                "0x",
+                DiagnosticCode::MalformedNumber,
+                "missing digits after '0x'",
+            ),
+            (
+                "0b102",
+                DiagnosticCode::MalformedNumber,
+                "invalid digit '2' in binary literal",
+            ),
+            (
+                "0o8",
+                DiagnosticCode::MalformedNumber,
+                "invalid digit '8' in octal literal",
+            ),
+            (
+                "9223372036854775809",
+                DiagnosticCode::IntegerOutOfRange,
+                "integer literal is too large for 'i64'",
+            ),
+            (
+                "0xffff_ffff_ffff_ffff",
+                DiagnosticCode::IntegerOutOfRange,
+                "integer literal is too large for 'i64'",
+            ),
+            (
+                "256u8",
+                DiagnosticCode::IntegerOutOfRange,
+                "integer literal is too large for 'u8'",
+            ),
+            (
+                "1e400",
+                DiagnosticCode::FloatOutOfRange,
+                "float literal is too large for 'f64'",
+            ),
+            (
+                "1e40f32",
+                DiagnosticCode::FloatOutOfRange,
+                "float literal is too large for 'f32'",
+            ),
+            (
+                "1_000e306f64",
+                DiagnosticCode::FloatOutOfRange,
+                "float literal is too large for 'f64'",
+            ),
+            (
+                "10abc",
+                DiagnosticCode::InvalidNumberSuffix,
+                "invalid suffix 'abc' for number literal",
+            ),
+            (
+                "1e",
+                DiagnosticCode::InvalidNumberSuffix,
+                "invalid suffix 'e' for number literal",
+            ),
+            (
+                "1.5u8",
+                DiagnosticCode::InvalidNumberSuffix,
+                "invalid suffix 'u8' for float literal",
+            ),
+            (
+                "0x1p",
+                DiagnosticCode::InvalidNumberSuffix,
+                "invalid suffix 'p' for hexadecimal literal",
+            ),
+        ];
+
+        for (input, code, message) in cases {
+            let tokens = tokenize(input);
+            let token = tokens.first();
+
+            assert_eq!(SyntaxKind::Bad, token.kind, "{}", input);
+            assert_eq!(input, &input[token.span.range()]);
+            assert_eq!(1, tokens.len(), "{}", input);
+            assert_diagnostics(&tokens, vec![code]);
+            assert_eq!(message, tokens.diagnostics[0].message);
+        }
+
+        let tokens = tokenize("129i8");
+        assert_eq!(
+            vec!["'i8' literals range from -128 to 127".to_string()],
+            tokens.diagnostics[0].notes
+        );
+        let tokens = tokenize("1e40f32");
+        assert_eq!(
+            vec!["the largest 'f32' is 3.4028235e38".to_string()],
+            tokens.diagnostics[0].notes
+        );
+
+        let tokens = tokenize("1e308 3.4e38f32");
+        assert_diagnostics(&tokens, vec![]);
+    }
+
+    #[test]
+    fn reads_the_magnitude_of_a_minimum_as_the_minimum() -> () {
+        let cases = vec![
+            ("9223372036854775808", i64::MIN),
+            ("0x8000_0000_0000_0000", i64::MIN),
+            ("128i8", i8::MIN as i64),
+            ("32_768i16", i16::MIN as i64),
+            ("2147483648i32", i32::MIN as i64),
+        ];
+
+        for (input, value) in cases {
+            let tokens = tokenize(input);
+
+            assert_eq!(SyntaxKind::IntLiteral, tokens.first().kind, "{}", input);
+            assert_eq!(Some(LiteralValue::Int(value)), tokens.first().value);
+            assert_diagnostics(&tokens, vec![]);
+        }
+    }
+
+    #[test]
+    fn continues_lexing_after_errors() -> () {
+        let tokens = tokenize("` + 1.2.3 $ 4");
+
+        let kinds: Vec<SyntaxKind> = tokens.iter().map(|token| token.kind).collect();
+        assert_eq!(
+            vec![
+                SyntaxKind::Bad,
+                SyntaxKind::Plus,
+                SyntaxKind::Bad,
+                SyntaxKind::Bad,
+                SyntaxKind::IntLiteral
+            ],
+            kinds
+        );
+        assert_diagnostics(
+            &tokens,
+            vec![
+                DiagnosticCode::UnexpectedCharacter,
+                DiagnosticCode::MalformedNumber,
+                DiagnosticCode::UnexpectedCharacter,
+            ],
+        );
     }
 
     #[test]
     fn skips_whitespaces_and_newlines() -> () {
-        let tokens = tokenize("+    - \n  *  ");
+        let file = SourceFile::from_string("test.ion", "+    - \n  *  ");
+        let tokens = file.tokenize();
         let plus = &tokens.first();
         let minus = &tokens.at(1);
         let star = &tokens.at(2);
@@ -40,15 +220,19 @@ mod tests {
         }
 
         assert_eq!(SyntaxKind::Plus, plus.kind);
-        assert_eq!("+", plus.text);
+        assert_eq!("+", file.text(plus.span));
         assert_eq!(SyntaxKind::Minus, minus.kind);
-        assert_eq!("-", minus.text);
+        assert_eq!("-", file.text(minus.span));
         assert_eq!(SyntaxKind::Star, star.kind);
-        assert_eq!("*", star.text);
-        assert_eq!(2, star.span.start.line);
-        assert_eq!(2, star.span.start.column);
-        assert_eq!(2, star.span.end.line);
-        assert_eq!(3, star.span.end.column);
+        assert_eq!("*", file.text(star.span));
+        assert_eq!(
+            Location { line: 2, column: 2 },
+            file.location(star.span.start as usize)
+        );
+        assert_eq!(
+            Location { line: 2, column: 3 },
+            file.location(star.span.end as usize)
+        );
         assert_eq!(3, tokens.len());
     }
 
@@ -61,7 +245,7 @@ mod tests {
             let token = tokens.first();
 
             assert_eq!(SyntaxKind::Identifier, token.kind);
-            assert_eq!(input, token.text);
+            assert_eq!(input, &input[token.span.range()]);
         }
     }
 
@@ -71,7 +255,7 @@ mod tests {
         let token = tokens.first();
 
         assert_eq!(SyntaxKind::NullLiteral, token.kind);
-        assert_eq!(true, token.value.is_none());
+        assert!(token.value.is_none());
     }
 
     #[test]
@@ -83,44 +267,173 @@ mod tests {
             let token = tokens.first();
 
             assert_eq!(SyntaxKind::BoolLiteral, token.kind);
-            assert_eq!(value, *token.downcast_value::<bool>().unwrap());
+            assert_eq!(Some(LiteralValue::Bool(value)), token.value);
         }
     }
 
     #[test]
     fn lexes_number_literals() -> () {
         let values = vec![
-            ("123", SyntaxKind::IntLiteral, 123.0),
-            ("123.456", SyntaxKind::FloatLiteral, 123.456),
+            ("123", SyntaxKind::IntLiteral, LiteralValue::Int(123)),
+            (
+                "1_000_000",
+                SyntaxKind::IntLiteral,
+                LiteralValue::Int(1_000_000),
+            ),
+            ("0xFF_ff", SyntaxKind::IntLiteral, LiteralValue::Int(0xffff)),
+            ("0o17", SyntaxKind::IntLiteral, LiteralValue::Int(0o17)),
+            (
+                "0b1010_0101",
+                SyntaxKind::IntLiteral,
+                LiteralValue::Int(0b1010_0101),
+            ),
+            ("10u8", SyntaxKind::IntLiteral, LiteralValue::Int(10)),
+            ("0x7fi8", SyntaxKind::IntLiteral, LiteralValue::Int(127)),
+            (
+                "9223372036854775807",
+                SyntaxKind::IntLiteral,
+                LiteralValue::Int(i64::MAX),
+            ),
+            (
+                "123.456",
+                SyntaxKind::FloatLiteral,
+                LiteralValue::Float(123.456),
+            ),
+            (".5", SyntaxKind::FloatLiteral, LiteralValue::Float(0.5)),
+            ("1e10", SyntaxKind::FloatLiteral, LiteralValue::Float(1e10)),
+            (
+                "2.5e-3",
+                SyntaxKind::FloatLiteral,
+                LiteralValue::Float(2.5e-3),
+            ),
+            (
+                "1_0E+2",
+                SyntaxKind::FloatLiteral,
+                LiteralValue::Float(1000.0),
+            ),
+            ("1f64", SyntaxKind::FloatLiteral, LiteralValue::Float(1.0)),
+            (
+                "0.1f32",
+                SyntaxKind::FloatLiteral,
+                LiteralValue::Float(0.1f32 as f64),
+            ),
         ];
 
         for (input, kind, value) in values {
             let tokens = tokenize(input);
             let token = tokens.first();
 
-            assert_eq!(kind, token.kind);
-            assert_eq!(value, *token.downcast_value::<f64>().unwrap());
+            assert_eq!(kind, token.kind, "{}", input);
+            assert_eq!(Some(value), token.value);
         }
     }
 
     #[test]
     fn lexes_string_literals() -> () {
-        let values = vec![("\"abc123\"", "abc123")];
+        let values = vec![
+            ("\"abc123\"", "abc123"),
+            ("'single \"quoted\"'", "single \"quoted\""),
+            ("\"a\\nb\\t\\\\\\\"\\'\\{\"", "a\nb\t\\\"'{"),
+            ("\"smile \\u{1F600}\"", "smile \u{1F600}"),
+            ("r\"C:\\new\\{dir}\"", "C:\\new\\{dir}"),
+            ("r'say \"hi\"'", "say \"hi\""),
+            ("\"two\nlines\"", "two\nlines"),
+        ];
 
         for (input, value) in values {
             let tokens = tokenize(input);
             let token = tokens.first();
 
             assert_eq!(SyntaxKind::StringLiteral, token.kind);
-            assert_eq!(value, *token.downcast_value::<String>().unwrap());
+            assert_eq!(Some(LiteralValue::Str(value.to_string())), token.value);
         }
     }
 
+    #[test]
+    fn lexes_interpolated_strings() -> () {
+        let input = "\"hello {name}, { f({ 1 }) }\\{\" + 1";
+        let tokens = tokenize(input);
+        let token = tokens.first();
+        assert_eq!(SyntaxKind::InterpolatedStringLiteral, token.kind);
+        assert_eq!(3, tokens.len());
+
+        let Some(LiteralValue::Interpolated(parts)) = &token.value else {
+            panic!("expected interpolated parts, got {:?}", token.value);
+        };
+        assert_eq!(5, parts.len());
+        assert_eq!(StringPart::Text("hello ".to_string()), parts[0]);
+        assert_eq!(StringPart::Text(", ".to_string()), parts[2]);
+        let StringPart::Expression { tokens: name, span } = &parts[1] else {
+            panic!("expected an expression, got {:?}", parts[1]);
+        };
+        assert_eq!(vec!["name"], texts(input, name));
+        assert_eq!(7..13, span.range());
+        let StringPart::Expression { tokens: call, .. } = &parts[3] else {
+            panic!("expected an expression, got {:?}", parts[3]);
+        };
+        assert_eq!(vec!["f", "(", "{", "1", "}", ")"], texts(input, call));
+        assert_eq!(StringPart::Text("{".to_string()), parts[4]);
+    }
+
+    #[test]
+    fn lexes_lazily_as_an_iterator() -> () {
+        let file = SourceFile::from_string("test.ion", "a ` b\n// end");
+        let mut lexer = Lexer::new(&file);
+
+        let first = lexer.next().unwrap();
+        assert_eq!("a", file.text(first.span));
+        assert!(lexer.diagnostics().is_empty());
+
+        let texts: Vec<&str> = lexer.by_ref().map(|token| file.text(token.span)).collect();
+        assert_eq!(vec!["`", "b"], texts);
+        assert_eq!(1, lexer.diagnostics().len());
+        assert_eq!(
+            DiagnosticCode::UnexpectedCharacter,
+            lexer.diagnostics()[0].code
+        );
+        assert_eq!(
+            vec![TriviaKind::Whitespace, TriviaKind::LineComment],
+            trivia_kinds(lexer.end_trivia())
+        );
+        assert_eq!(None, lexer.next());
+    }
+
+    #[test]
+    fn spans_tokens_in_bytes() -> () {
+        let file = SourceFile::from_string("test.ion", "let café = \"☕\" + ñ");
+        let tokens = file.tokenize();
+        let spans: Vec<_> = tokens.iter().map(|token| token.span.range()).collect();
+
+        assert_eq!(vec![0..3, 4..9, 10..11, 12..17, 18..19, 20..22], spans);
+        assert_eq!("let café = \"☕\" + ñ", tokens.source_text(&file));
+    }
+
+    #[test]
+    fn compares_tokens_by_value() -> () {
+        let first = tokenize("print(1, 2.5, \"a\", true)");
+        let second = tokenize("print(1, 2.5, \"a\", true)");
+        assert_eq!(first.tokens, second.tokens);
+
+        let other = tokenize("print(1, 2.5, \"b\", true)");
+        assert_ne!(first.tokens, other.tokens);
+        assert_eq!(first.tokens[..6], other.tokens[..6]);
+    }
+
     #[test]
     fn lexes_keywords() -> () {
         assert_kinds(vec![
             ("let", SyntaxKind::LetKeyword),
             ("fn", SyntaxKind::FnKeyword),
+            ("return", SyntaxKind::ReturnKeyword),
+            ("if", SyntaxKind::IfKeyword),
+            ("else", SyntaxKind::ElseKeyword),
+            ("while", SyntaxKind::WhileKeyword),
+            ("for", SyntaxKind::ForKeyword),
+            ("in", SyntaxKind::InKeyword),
+            ("break", SyntaxKind::BreakKeyword),
+            ("continue", SyntaxKind::ContinueKeyword),
+            ("import", SyntaxKind::ImportKeyword),
+            ("pub", SyntaxKind::PubKeyword),
         ]);
     }
 
@@ -131,7 +444,7 @@ mod tests {
             ("-", SyntaxKind::Minus),
             ("*", SyntaxKind::Star),
             ("/", SyntaxKind::Slash),
-            ("//", SyntaxKind::SlashSlash),
+            ("~/", SyntaxKind::TildeSlash),
             ("%", SyntaxKind::Percent),
             ("^", SyntaxKind::Carat),
             ("~", SyntaxKind::Tilde),
@@ -148,9 +461,100 @@ mod tests {
             ("==", SyntaxKind::EqualsEquals),
             ("!=", SyntaxKind::BangEquals),
             (":", SyntaxKind::Colon),
+            ("(", SyntaxKind::LeftParen),
+            (")", SyntaxKind::RightParen),
+            ("{", SyntaxKind::LeftBrace),
+            ("}", SyntaxKind::RightBrace),
+            (",", SyntaxKind::Comma),
+            (".", SyntaxKind::Dot),
         ]);
     }
 
+    #[test]
+    fn lexes_dots_between_names_and_in_numbers() -> () {
+        let tokens = tokenize("a.b .5");
+
+        let kinds: Vec<SyntaxKind> = tokens.iter().map(|token| token.kind).collect();
+        assert_eq!(
+            vec![
+                SyntaxKind::Identifier,
+                SyntaxKind::Dot,
+                SyntaxKind::Identifier,
+                SyntaxKind::FloatLiteral
+            ],
+            kinds
+        );
+    }
+
+    #[test]
+    fn attaches_comments_and_whitespace_as_trivia() -> () {
+        let file = SourceFile::from_string(
+            "test.ion",
+            "// a\n/// b\nlet /* c /* d */ e */ x // f\n\n/* g */",
+        );
+        let tokens = file.tokenize();
+        let keyword = tokens.first();
+        let name = tokens.at(1);
+
+        assert_eq!(
+            vec![
+                TriviaKind::LineComment,
+                TriviaKind::Whitespace,
+                TriviaKind::DocComment,
+                TriviaKind::Whitespace,
+            ],
+            trivia_kinds(&keyword.leading_trivia)
+        );
+        assert_eq!(
+            vec![
+                TriviaKind::Whitespace,
+                TriviaKind::BlockComment,
+                TriviaKind::Whitespace,
+            ],
+            trivia_kinds(&keyword.trailing_trivia)
+        );
+        assert_eq!(
+            "/* c /* d */ e */",
+            file.text(keyword.trailing_trivia[1].span)
+        );
+        assert_eq!(
+            vec![TriviaKind::Whitespace, TriviaKind::LineComment],
+            trivia_kinds(&name.trailing_trivia)
+        );
+        assert_eq!(
+            vec![TriviaKind::Whitespace, TriviaKind::BlockComment],
+            trivia_kinds(&tokens.end_trivia)
+        );
+        assert_eq!(Some("b".to_string()), keyword.doc_comment(&file));
+        assert_eq!(2, tokens.len());
+    }
+
+    #[test]
+    fn reconstructs_the_source_losslessly() -> () {
+        let cases = vec![
+            "",
+            "  \n\t",
+            "// only a comment",
+            "let a = 1 // one\r\n  /// two\n//// three\nprint(a ~/ 2)\n",
+            "fn f() { /* a /* b */ */ }\n\n\n`  1.2.3 'open",
+            "a /* unterminated /* comment */",
+        ];
+
+        for input in cases {
+            let file = SourceFile::from_string("test.ion", input);
+            assert_eq!(input, file.tokenize().source_text(&file));
+        }
+    }
+
+    #[test]
+    fn reports_unterminated_block_comment() -> () {
+        let tokens = tokenize("a /* b /* c */");
+
+        assert_eq!(1, tokens.len());
+        assert_eq!(2, tokens.diagnostics[0].span.len());
+        assert_diagnostics(&tokens, vec![DiagnosticCode::UnterminatedComment]);
+    }
+
     fn assert_kinds(cases: Vec<(&str, SyntaxKind)>) -> () {
         for (input, expected_kind) in cases {
             let tokens = tokenize(input);
@@ -160,24 +564,28 @@ mod tests {
         }
     }
 
-    fn tokenize(input: &str) -> TokenStream {
-        let path = env::current_dir()
-            .expect("failed to find current working directory")
-            .join(
-                "unit-tests.tuna".to_owned() + &COUNTER.fetch_add(1, Ordering::Relaxed).to_string(),
-            );
-
-        match fs::write(&path, input) {
-            Ok(_) => (),
-            Err(e) => panic!("failed to write test file: {}", e),
-        }
+    fn assert_diagnostics(tokens: &TokenStream, expected_codes: Vec<DiagnosticCode>) -> () {
+        let codes: Vec<DiagnosticCode> = tokens
+            .diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.code)
+            .collect();
 
-        let source_file = SourceFile::new(path.to_str().unwrap());
-        match fs::remove_file(path) {
-            Ok(_) => (),
-            Err(e) => panic!("failed to remove test file: {}", e),
-        }
+        assert_eq!(expected_codes, codes);
+    }
 
-        source_file.tokenize().tokens
+    fn texts<'a>(input: &'a str, tokens: &[Token]) -> Vec<&'a str> {
+        tokens
+            .iter()
+            .map(|token| &input[token.span.range()])
+            .collect()
+    }
+
+    fn trivia_kinds(trivia: &[Trivia]) -> Vec<TriviaKind> {
+        trivia.iter().map(|trivia| trivia.kind).collect()
+    }
+
+    fn tokenize(input: &str) -> TokenStream {
+        SourceFile::from_string("test.ion", input).tokenize()
     }
 }
@@ -0,0 +1,292 @@
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, io::Write, rc::Rc};
+
+    use serde_json::{json, Value};
+
+    use crate::{
+        cli::{Cli, EXIT_FAILURE, EXIT_SUCCESS},
+        lsp::transport,
+    };
+
+    const LIB: &str = "file:///project/lib.ion";
+    const MAIN: &str = "file:///project/main.ion";
+    const MAIN_SOURCE: &str = "import \"lib.ion\"
+let total = double(21)
+fn show(value: int) {
+  let sum = value + total
+  print(sum)
+}
+show(total)
+";
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// A scripted client, sending every message up front and reading the replies afterwards
+    #[derive(Default)]
+    struct Client {
+        input: Vec<u8>,
+        next_id: u64,
+    }
+
+    struct Session {
+        code: i32,
+        messages: Vec<Value>,
+    }
+
+    impl Client {
+        fn new() -> Self {
+            let mut client = Self::default();
+            client.request("initialize", json!({ "capabilities": {} }));
+            client.notify("initialized", json!({}));
+            client
+        }
+
+        fn request(&mut self, method: &str, params: Value) -> u64 {
+            self.next_id += 1;
+            let id = self.next_id;
+            self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }));
+            id
+        }
+
+        fn notify(&mut self, method: &str, params: Value) -> () {
+            self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+        }
+
+        fn open(&mut self, uri: &str, text: &str) -> () {
+            let document = json!({ "uri": uri, "languageId": "ion", "version": 1, "text": text });
+            self.notify("textDocument/didOpen", json!({ "textDocument": document }));
+        }
+
+        fn change(&mut self, uri: &str, text: &str) -> () {
+            self.notify(
+                "textDocument/didChange",
+                json!({
+                    "textDocument": { "uri": uri, "version": 2 },
+                    "contentChanges": [{ "text": text }],
+                }),
+            );
+        }
+
+        fn at(&mut self, method: &str, uri: &str, line: u32, character: u32) -> u64 {
+            self.request(
+                method,
+                json!({
+                    "textDocument": { "uri": uri },
+                    "position": { "line": line, "character": character },
+                    "context": { "includeDeclaration": true },
+                }),
+            )
+        }
+
+        fn send(&mut self, message: Value) -> () {
+            transport::write_message(&mut self.input, &message).unwrap();
+        }
+
+        /// Shuts the server down, returning its exit code and everything it sent
+        fn finish(mut self) -> Session {
+            self.request("shutdown", Value::Null);
+            self.notify("exit", Value::Null);
+            self.run()
+        }
+
+        fn run(self) -> Session {
+            let stdout = SharedBuffer::default();
+            let cli = Cli::with_io(
+                Box::new(std::io::Cursor::new(self.input)),
+                Box::new(stdout.clone()),
+                Box::new(std::io::sink()),
+            );
+            let code = cli.run(&["lsp".to_string()]);
+
+            let output = stdout.0.take();
+            let mut reader = std::io::Cursor::new(output);
+            let mut messages = Vec::new();
+            while let Some(message) = transport::read_message(&mut reader).unwrap() {
+                messages.push(message);
+            }
+
+            Session { code, messages }
+        }
+    }
+
+    impl Session {
+        fn response(&self, id: u64) -> &Value {
+            let response = self.messages.iter().find(|message| message["id"] == id);
+            &response.expect("no response")["result"]
+        }
+
+        /// Returns the diagnostics published for `uri`, in the order they were published
+        fn diagnostics(&self, uri: &str) -> Vec<Vec<Value>> {
+            self.messages
+                .iter()
+                .filter(|message| message["method"] == "textDocument/publishDiagnostics")
+                .filter(|message| message["params"]["uri"] == uri)
+                .map(|message| message["params"]["diagnostics"].as_array().unwrap().clone())
+                .collect()
+        }
+    }
+
+    #[test]
+    fn initializes_and_shuts_down() -> () {
+        let session = Client::new().finish();
+
+        assert_eq!(EXIT_SUCCESS, session.code);
+        let capabilities = &session.response(1)["capabilities"];
+        assert_eq!(json!(1), capabilities["textDocumentSync"]);
+        assert_eq!(json!(true), capabilities["hoverProvider"]);
+        assert_eq!(Value::Null, *session.response(2));
+
+        let mut client = Client::new();
+        let id = client.request("textDocument/frobnicate", json!({}));
+        client.notify("exit", Value::Null);
+        let session = client.run();
+        assert_eq!(EXIT_FAILURE, session.code);
+        let error = session.messages.iter().find(|message| message["id"] == id);
+        assert_eq!(json!(-32601), error.unwrap()["error"]["code"]);
+    }
+
+    #[test]
+    fn publishes_diagnostics_as_documents_change() -> () {
+        let mut client = Client::new();
+        client.open(MAIN, "let a = )\nlet b = 1 +");
+        client.change(MAIN, "let a = 1\nprint(c)");
+        client.change(MAIN, "let a = 1\nprint(a)");
+        let session = client.finish();
+
+        let published = session.diagnostics(MAIN);
+        assert_eq!(3, published.len());
+
+        let syntax: Vec<&Value> = published[0].iter().map(|d| &d["code"]).collect();
+        assert_eq!(vec!["E0100", "E0101"], syntax);
+
+        assert_eq!(1, published[1].len());
+        assert_eq!(
+            json!("cannot find 'c' in this scope"),
+            published[1][0]["message"]
+        );
+        assert_eq!(
+            json!({ "start": { "line": 1, "character": 6 }, "end": { "line": 1, "character": 7 } }),
+            published[1][0]["range"]
+        );
+
+        assert!(published[2].is_empty());
+    }
+
+    #[test]
+    fn answers_hover_definition_and_references() -> () {
+        let mut client = Client::new();
+        client.open(LIB, "pub fn double(x: int): int {\n  return x * 2\n}\n");
+        client.open(MAIN, MAIN_SOURCE);
+        let hover = client.at("textDocument/hover", MAIN, 3, 21);
+        let hover_function = client.at("textDocument/hover", MAIN, 1, 13);
+        let definition = client.at("textDocument/definition", MAIN, 1, 13);
+        let references = client.at("textDocument/references", MAIN, 6, 6);
+        let nothing = client.at("textDocument/hover", MAIN, 0, 1);
+        let session = client.finish();
+
+        assert!(session.diagnostics(MAIN).last().unwrap().is_empty());
+        assert_eq!(
+            json!("```ion\nlet total: int\n```"),
+            session.response(hover)["contents"]["value"]
+        );
+        assert_eq!(
+            json!("```ion\nfn double(int): int\n```"),
+            session.response(hover_function)["contents"]["value"]
+        );
+        assert_eq!(
+            json!({
+                "uri": LIB,
+                "range": { "start": { "line": 0, "character": 7 }, "end": { "line": 0, "character": 13 } },
+            }),
+            *session.response(definition)
+        );
+
+        let lines: Vec<(&Value, &Value)> = session
+            .response(references)
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|location| {
+                assert_eq!(json!(MAIN), location["uri"]);
+                let start = &location["range"]["start"];
+                (&start["line"], &start["character"])
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                (&json!(1), &json!(4)),
+                (&json!(3), &json!(20)),
+                (&json!(6), &json!(5))
+            ],
+            lines
+        );
+        assert_eq!(Value::Null, *session.response(nothing));
+    }
+
+    #[test]
+    fn counts_positions_in_utf16_code_units() -> () {
+        let mut client = Client::new();
+        client.open(MAIN, "let s = \"😀\" let n = 1\nprint(s, n)\n");
+        let hover = client.at("textDocument/hover", MAIN, 0, 17);
+        let definition = client.at("textDocument/definition", MAIN, 1, 9);
+        let session = client.finish();
+
+        assert_eq!(
+            json!("```ion\nlet n: int\n```"),
+            session.response(hover)["contents"]["value"]
+        );
+        assert_eq!(
+            json!({
+                "uri": MAIN,
+                "range": { "start": { "line": 0, "character": 17 }, "end": { "line": 0, "character": 18 } },
+            }),
+            *session.response(definition)
+        );
+    }
+
+    #[test]
+    fn lists_symbols_and_completions() -> () {
+        let mut client = Client::new();
+        client.open(LIB, "pub fn double(x: int): int {\n  return x * 2\n}\n");
+        client.open(MAIN, MAIN_SOURCE);
+        let symbols = client.request(
+            "textDocument/documentSymbol",
+            json!({ "textDocument": { "uri": MAIN } }),
+        );
+        let inside = client.at("textDocument/completion", MAIN, 4, 2);
+        let before = client.at("textDocument/completion", MAIN, 3, 2);
+        let session = client.finish();
+
+        let symbols = session.response(symbols).as_array().unwrap();
+        let names: Vec<&Value> = symbols.iter().map(|symbol| &symbol["name"]).collect();
+        assert_eq!(vec!["total", "show"], names);
+        assert_eq!(json!(12), symbols[1]["kind"]);
+        assert_eq!(json!("sum"), symbols[1]["children"][0]["name"]);
+        assert_eq!(json!("int"), symbols[1]["children"][0]["detail"]);
+
+        let labels = |id: u64| -> Vec<String> {
+            let items = session.response(id).as_array().unwrap();
+            items
+                .iter()
+                .map(|item| item["label"].as_str().unwrap().to_string())
+                .collect()
+        };
+        let inside = labels(inside);
+        for label in ["sum", "value", "total", "show", "double", "print", "while"] {
+            assert!(inside.contains(&label.to_string()), "{}", label);
+        }
+        assert!(!labels(before).contains(&"sum".to_string()));
+    }
+}
@@ -1 +1,15 @@
+pub mod cli_spec;
+pub mod cst_spec;
+pub mod diagnostics_spec;
+pub mod formatter_spec;
+pub mod incremental_spec;
+pub mod interpreter_spec;
 pub mod lexer_spec;
+pub mod lsp_spec;
+pub mod modules_spec;
+pub mod parser_spec;
+pub mod repl_spec;
+pub mod resolver_spec;
+pub mod source_spec;
+pub mod typeck_spec;
+pub mod vm_spec;
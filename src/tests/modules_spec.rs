@@ -0,0 +1,224 @@
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, collections::HashMap, io, io::Write, rc::Rc};
+
+    use crate::compiler::Compiler;
+    use crate::diagnostics::{Diagnostic, DiagnosticCode};
+    use crate::interpreter::Interpreter;
+    use crate::modules::{loader::Loader, ModuleGraph};
+    use crate::source::SourceFile;
+    use crate::vm::Vm;
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn runs_imported_modules_first() -> () {
+        let output = run(&[
+            (
+                "main.ion",
+                "import \"lib.ion\"\nimport util.math\nprint(greet(\"ion\"), square(answer))",
+            ),
+            (
+                "lib.ion",
+                "import util.math\nprint(\"loading lib\")\npub let answer = square(3)\n\
+                 pub fn greet(name: string): string { return \"hello \" + name }",
+            ),
+            (
+                "util/math.ion",
+                "print(\"loading math\")\npub fn square(x: int): int { return x * x }",
+            ),
+        ]);
+
+        assert_eq!("loading math\nloading lib\nhello ion 81\n", output);
+    }
+
+    #[test]
+    fn resolves_imports_relative_to_the_importing_file() -> () {
+        let graph = load(&[
+            ("main.ion", "import \"lib/a.ion\""),
+            ("lib/a.ion", "import \"../shared.ion\"\nimport b"),
+            ("lib/b.ion", "import \"../shared.ion\""),
+            ("shared.ion", ""),
+        ])
+        .unwrap();
+
+        let names: Vec<&str> = graph
+            .modules()
+            .iter()
+            .map(|module| module.file.index())
+            .map(|index| ["main.ion", "lib/a.ion", "shared.ion", "lib/b.ion"][index])
+            .collect();
+        assert_eq!(
+            vec!["shared.ion", "lib/b.ion", "lib/a.ion", "main.ion"],
+            names
+        );
+    }
+
+    #[test]
+    fn reports_import_cycles() -> () {
+        let diagnostics = load(&[
+            ("main.ion", "import a"),
+            ("a.ion", "import b"),
+            ("b.ion", "import a"),
+        ])
+        .unwrap_err();
+
+        assert_eq!(codes(&diagnostics), vec![DiagnosticCode::ImportCycle]);
+        assert_eq!(
+            vec!["the cycle is a.ion -> b.ion -> a.ion".to_string()],
+            diagnostics[0].notes
+        );
+    }
+
+    #[test]
+    fn reports_unresolved_imports_and_errors_in_imported_files() -> () {
+        let diagnostics = load(&[
+            ("main.ion", "import missing\nimport broken"),
+            ("broken.ion", "let = 1"),
+        ])
+        .unwrap_err();
+
+        assert_eq!(
+            codes(&diagnostics),
+            vec![
+                DiagnosticCode::UnresolvedImport,
+                DiagnosticCode::UnexpectedToken
+            ]
+        );
+        assert_ne!(diagnostics[0].span.file, diagnostics[1].span.file);
+    }
+
+    #[test]
+    fn only_exposes_public_declarations() -> () {
+        let cases = vec![
+            ("print(hidden)", vec![DiagnosticCode::PrivateName]),
+            ("print(missing)", vec![DiagnosticCode::UndefinedName]),
+            ("print(indirect)", vec![DiagnosticCode::UndefinedName]),
+            ("let shown = 1", vec![DiagnosticCode::DuplicateDeclaration]),
+            ("fn hidden() {}", vec![]),
+            ("let a: string = shown", vec![DiagnosticCode::TypeMismatch]),
+        ];
+
+        for (main, expected) in cases {
+            let graph = load(&[
+                ("main.ion", &format!("import lib\n{}", main)),
+                (
+                    "lib.ion",
+                    "import other\npub let shown = 1\nlet hidden = indirect",
+                ),
+                ("other.ion", "pub let indirect = 2"),
+            ])
+            .unwrap();
+
+            let diagnostics = graph.check().err().unwrap_or_default();
+            assert_eq!(expected, codes(&diagnostics), "{}", main);
+        }
+    }
+
+    #[test]
+    fn keeps_private_names_apart() -> () {
+        let output = run(&[
+            (
+                "main.ion",
+                "import a\nimport b\nlet helper = \"main\"\nprint(a(), b(), helper)",
+            ),
+            (
+                "a.ion",
+                "fn helper(): string { return \"a\" }\n\
+                 pub fn a(): string { let helper = \"shadowed\" return outer() + helper }\n\
+                 fn outer(): string { return helper() }",
+            ),
+            (
+                "b.ion",
+                "let count = 0\nfn helper(): int { count = count + 1 return count }\n\
+                 pub fn b(): int { helper() return helper() }",
+            ),
+        ]);
+
+        assert_eq!("ashadowed 2 main\n", output);
+    }
+
+    #[test]
+    fn shows_private_names_as_written() -> () {
+        let files = [
+            ("main.ion", "import q\nprint(get())"),
+            (
+                "q.ion",
+                "fn helper(): int { return 1 }\npub fn get(): fn(): int { return helper }",
+            ),
+        ];
+        assert_eq!("<fn helper>\n", run(&files));
+
+        let graph = load(&files).expect("failed to load");
+        let function = Compiler::new()
+            .compile(&graph.link())
+            .expect("failed to compile");
+        let buffer = SharedBuffer::default();
+        Vm::with_output(Box::new(buffer.clone()))
+            .run(function)
+            .expect("failed to run");
+        assert_eq!("<fn helper>\n", String::from_utf8(buffer.0.take()).unwrap());
+    }
+
+    #[test]
+    fn reports_exported_names_declared_by_several_modules() -> () {
+        let graph = load(&[
+            ("main.ion", "import a\nimport b"),
+            ("a.ion", "pub fn helper() {}"),
+            ("b.ion", "pub let helper = 1"),
+        ])
+        .unwrap();
+
+        let diagnostics = graph.check().unwrap_err();
+        assert_eq!(
+            codes(&diagnostics),
+            vec![DiagnosticCode::DuplicateDeclaration]
+        );
+    }
+
+    fn load(files: &[(&str, &str)]) -> Result<ModuleGraph, Vec<Diagnostic>> {
+        let files: HashMap<String, String> = files
+            .iter()
+            .map(|(name, source)| (name.to_string(), source.to_string()))
+            .collect();
+        let entry = SourceFile::from_string("main.ion", files["main.ion"].clone());
+
+        let mut loader = Loader::with_reader(Box::new(move |path| {
+            let name = path.to_string_lossy().into_owned();
+            match files.get(&name) {
+                Some(source) => Ok(SourceFile::from_string(name, source.clone())),
+                None => Err(io::Error::from(io::ErrorKind::NotFound)),
+            }
+        }));
+        loader.load(entry)
+    }
+
+    fn run(files: &[(&str, &str)]) -> String {
+        let graph = load(files).expect("failed to load");
+        graph.check().expect("failed to check");
+
+        let buffer = SharedBuffer::default();
+        Interpreter::with_output(Box::new(buffer.clone()))
+            .interpret(&graph.link())
+            .expect("failed to run");
+        String::from_utf8(buffer.0.take()).unwrap()
+    }
+
+    fn codes(diagnostics: &[Diagnostic]) -> Vec<DiagnosticCode> {
+        diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.code)
+            .collect()
+    }
+}
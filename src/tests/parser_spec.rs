@@ -0,0 +1,359 @@
+#[cfg(test)]
+mod tests {
+    use crate::diagnostics::DiagnosticCode;
+    use crate::parser::ast::printer::Printer;
+    use crate::source::SourceFile;
+
+    #[test]
+    fn parses_binary_operators_by_precedence() -> () {
+        assert_parses(vec![
+            ("1 + 2 * 3", "(+ 1 (* 2 3))"),
+            ("1 * 2 + 3", "(+ (* 1 2) 3)"),
+            ("1 - 2 - 3", "(- (- 1 2) 3)"),
+            ("1 ~/ 2 % 3 / 4", "(/ (% (~/ 1 2) 3) 4)"),
+            ("1 < 2 == 3 >= 4", "(== (< 1 2) (>= 3 4))"),
+            ("1 != 2 && 3 <= 4", "(&& (!= 1 2) (<= 3 4))"),
+            ("true || false && true", "(|| true (&& false true))"),
+            ("1 | 2 ~ 3 & 4", "(| 1 (~ 2 (& 3 4)))"),
+            ("1 & 2 + 3 > 4", "(> (& 1 (+ 2 3)) 4)"),
+        ]);
+    }
+
+    #[test]
+    fn parses_carat_as_right_associative() -> () {
+        assert_parses(vec![
+            ("2 ^ 3 ^ 4", "(^ 2 (^ 3 4))"),
+            ("2 * 3 ^ 4", "(* 2 (^ 3 4))"),
+        ]);
+    }
+
+    #[test]
+    fn parses_unary_operators() -> () {
+        assert_parses(vec![
+            ("-1", "(- 1)"),
+            ("!true", "(! true)"),
+            ("~1", "(~ 1)"),
+            ("--1", "(- (- 1))"),
+            ("-1 * 2", "(* (- 1) 2)"),
+            ("-2 ^ 2", "(- (^ 2 2))"),
+            ("2 ^ -2", "(^ 2 (- 2))"),
+            ("!true == false", "(== (! true) false)"),
+        ]);
+    }
+
+    #[test]
+    fn folds_minus_into_the_literal_of_a_minimum() -> () {
+        assert_parses(vec![
+            ("-9223372036854775808", "-9223372036854775808"),
+            ("-128i8", "-128i8"),
+            ("-128i8 + 1", "(+ -128i8 1)"),
+            ("--128i8", "(- -128i8)"),
+            ("1 - -0x8000_0000_0000_0000", "(- 1 -0x8000_0000_0000_0000)"),
+            ("-127i8", "(- 127i8)"),
+        ]);
+
+        let cases = vec![
+            ("128i8", "'i8'"),
+            ("9223372036854775808", "'i64'"),
+            ("1 -9223372036854775808", "'i64'"),
+            ("-32768i16 ^ 2", "'i16'"),
+        ];
+        for (input, ty) in cases {
+            let diagnostics = source_file(input).parse().unwrap_err();
+
+            assert_eq!(1, diagnostics.len(), "{}", input);
+            assert_eq!(DiagnosticCode::IntegerOutOfRange, diagnostics[0].code);
+            let message = format!("integer literal is too large for {}", ty);
+            assert_eq!(message, diagnostics[0].message, "{}", input);
+        }
+    }
+
+    #[test]
+    fn parses_groupings() -> () {
+        assert_parses(vec![
+            ("(1 + 2) * 3", "(* (group (+ 1 2)) 3)"),
+            ("-(1)", "(- (group 1))"),
+            ("((null))", "(group (group null))"),
+        ]);
+    }
+
+    #[test]
+    fn parses_variables_and_assignments() -> () {
+        assert_parses(vec![
+            ("a + b", "(+ a b)"),
+            ("a = 1 + 2", "(= a (+ 1 2))"),
+            ("a = b = c", "(= a (= b c))"),
+        ]);
+    }
+
+    #[test]
+    fn parses_let_statements() -> () {
+        assert_parses(vec![
+            ("let a = 1", "(let a 1)"),
+            ("let a: int = 1 + 2", "(let a: int (+ 1 2))"),
+            ("let a: null = null", "(let a: null null)"),
+        ]);
+    }
+
+    #[test]
+    fn parses_programs_with_multiple_statements() -> () {
+        assert_parses(vec![
+            ("let a = 1 a = a + 1", "(let a 1)\n(= a (+ a 1))"),
+            ("1\n2", "1\n2"),
+            ("", ""),
+        ]);
+    }
+
+    #[test]
+    fn parses_nested_blocks() -> () {
+        assert_parses(vec![
+            ("{}", "{}"),
+            ("{ let a = 1 { a } }", "{(let a 1) {a}}"),
+            ("let a = 1 { a = 2 } a", "(let a 1)\n{(= a 2)}\na"),
+        ]);
+    }
+
+    #[test]
+    fn parses_function_declarations() -> () {
+        assert_parses(vec![
+            ("fn f() {}", "(fn f() {})"),
+            (
+                "fn add(a: int, b: float): float { return a + b }",
+                "(fn add(a: int, b: float): float {(return (+ a b))})",
+            ),
+            ("fn f(): null { return }", "(fn f(): null {(return)})"),
+            (
+                "fn f() { return } let a = 1",
+                "(fn f() {(return)})\n(let a 1)",
+            ),
+            (
+                "fn outer() { fn inner() { return 1 } return inner }",
+                "(fn outer() {(fn inner() {(return 1)}) (return inner)})",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn parses_function_type_annotations() -> () {
+        assert_parses(vec![
+            ("let f: fn() = g", "(let f: fn() g)"),
+            (
+                "fn apply(f: fn(int, fn(): null): bool, x: int): fn(): int {}",
+                "(fn apply(f: fn(int, fn(): null): bool, x: int): fn(): int {})",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn parses_call_expressions() -> () {
+        assert_parses(vec![
+            ("f()", "(call f)"),
+            ("f(1, a + b)", "(call f 1 (+ a b))"),
+            ("f(1)(2)", "(call (call f 1) 2)"),
+            ("-f(x) ^ 2", "(- (^ (call f x) 2))"),
+            ("(f)(g(1))", "(call (group f) (call g 1))"),
+        ]);
+    }
+
+    #[test]
+    fn parses_interpolated_strings() -> () {
+        assert_parses(vec![
+            ("\"a {b} c\"", "(interpolate \"a \" b \" c\")"),
+            (
+                "\"{f(1) + 2}{\"x\"}\"",
+                "(interpolate (+ (call f 1) 2) \"x\")",
+            ),
+            ("\"{\"{a}\"}\"", "(interpolate (interpolate a))"),
+        ]);
+    }
+
+    #[test]
+    fn parses_if_statements() -> () {
+        assert_parses(vec![
+            ("if a { b }", "(if a {b})"),
+            ("if a == 1 { b } else { c }", "(if (== a 1) {b} {c})"),
+            (
+                "if a { b } else if c { d } else { e }",
+                "(if a {b} (if c {d} {e}))",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn parses_loops() -> () {
+        assert_parses(vec![
+            (
+                "while a < 10 { a = a + 1 }",
+                "(while (< a 10) {(= a (+ a 1))})",
+            ),
+            (
+                "for c in \"abc\" { print(c) }",
+                "(for c \"abc\" {(call print c)})",
+            ),
+            (
+                "while true { if a { break } else { continue } }",
+                "(while true {(if a {(break)} {(continue)})})",
+            ),
+            (
+                "for a in b { while c { break } continue }",
+                "(for a b {(while c {(break)}) (continue)})",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn parses_imports_and_public_declarations() -> () {
+        assert_parses(vec![
+            ("import \"lib/math.ion\"", "(import \"lib/math.ion\")"),
+            ("import lib.math", "(import lib.math)"),
+            ("pub let a = 1", "(pub let a 1)"),
+            (
+                "import a pub fn f(): int { return 1 }",
+                "(import a)\n(pub fn f(): int {(return 1)})",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn reports_syntax_errors() -> () {
+        let cases = vec![
+            ("1 +", DiagnosticCode::UnexpectedEndOfFile),
+            ("(1 + 2", DiagnosticCode::UnexpectedEndOfFile),
+            ("1 + )", DiagnosticCode::UnexpectedToken),
+            ("1 )", DiagnosticCode::UnexpectedToken),
+            ("let = 1", DiagnosticCode::UnexpectedToken),
+            ("let a: = 1", DiagnosticCode::UnexpectedToken),
+            ("let a: int", DiagnosticCode::UnexpectedEndOfFile),
+            ("{ let a = 1", DiagnosticCode::UnexpectedEndOfFile),
+            ("1 = 2", DiagnosticCode::InvalidAssignmentTarget),
+            ("fn f(a) {}", DiagnosticCode::UnexpectedToken),
+            ("fn f(a: int,) {}", DiagnosticCode::UnexpectedToken),
+            ("fn f()", DiagnosticCode::UnexpectedEndOfFile),
+            ("f(1, 2", DiagnosticCode::UnexpectedEndOfFile),
+            ("return 1", DiagnosticCode::ReturnOutsideFunction),
+            ("{ return }", DiagnosticCode::ReturnOutsideFunction),
+            ("if a b", DiagnosticCode::UnexpectedToken),
+            ("if a {} else b", DiagnosticCode::UnexpectedToken),
+            ("for a b {}", DiagnosticCode::UnexpectedToken),
+            ("break", DiagnosticCode::BreakOutsideLoop),
+            ("continue", DiagnosticCode::ContinueOutsideLoop),
+            ("if a { break }", DiagnosticCode::BreakOutsideLoop),
+            (
+                "while a { fn f() { continue } }",
+                DiagnosticCode::ContinueOutsideLoop,
+            ),
+            ("import", DiagnosticCode::UnexpectedEndOfFile),
+            ("import a.", DiagnosticCode::UnexpectedEndOfFile),
+            ("import 1", DiagnosticCode::UnexpectedToken),
+            ("{ import a }", DiagnosticCode::MisplacedImport),
+            ("pub 1", DiagnosticCode::UnexpectedToken),
+            (
+                "fn f() { pub let a = 1 }",
+                DiagnosticCode::InvalidVisibility,
+            ),
+            ("\"{}\"", DiagnosticCode::UnexpectedToken),
+            ("\"{1 2}\"", DiagnosticCode::UnexpectedToken),
+            ("\"{(1}\"", DiagnosticCode::UnexpectedToken),
+        ];
+
+        for (input, code) in cases {
+            let diagnostics = source_file(input).parse().unwrap_err();
+
+            assert_eq!(1, diagnostics.len(), "{}", input);
+            assert_eq!(code, diagnostics[0].code, "{}", input);
+        }
+    }
+
+    #[test]
+    fn hints_at_floor_division_written_as_a_comment() -> () {
+        let diagnostics = source_file("print(7 // 2)\nprint(1)").parse().unwrap_err();
+
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(DiagnosticCode::UnexpectedToken, diagnostics[0].code);
+        assert_eq!(
+            Some("floor division is written '~/', since '//' starts a comment".to_string()),
+            diagnostics[0].help
+        );
+
+        let diagnostics = source_file("print(a // the divisor\n)\nprint(")
+            .parse()
+            .unwrap_err();
+        assert_eq!(None, diagnostics[0].help);
+        assert!(source_file("let a = 7 // 2").parse().is_ok());
+    }
+
+    #[test]
+    fn recovers_at_statement_boundaries() -> () {
+        let source = "let a = \nlet b = 1 +* 2\nfn f( { return 1 }\nprint(b)\nlet c = )";
+        let file = source_file(source);
+        let (program, diagnostics) = file.parse_partial();
+
+        let lines: Vec<usize> = diagnostics
+            .iter()
+            .map(|diagnostic| file.location(diagnostic.span.start as usize).line)
+            .collect();
+        assert_eq!(vec![2, 2, 3, 5], lines);
+        assert_eq!(
+            "(let a (error))\n(let b (+ 1 (* (error) 2)))\n(error)\n(call print b)\n(let c (error))\n(error)",
+            Printer::new(&file).print_program(&program)
+        );
+    }
+
+    #[test]
+    fn recovers_inside_blocks() -> () {
+        let file = source_file("fn f() {\n  let = 1\n  return 2\n}\nwhile a { break ) }");
+        let (program, diagnostics) = file.parse_partial();
+
+        assert_eq!(2, diagnostics.len());
+        assert_eq!(
+            "(fn f() {(error) (return 2)})\n(while a {(break) (error)})",
+            Printer::new(&file).print_program(&program)
+        );
+
+        let file = source_file("if a { print(1)");
+        let (program, diagnostics) = file.parse_partial();
+        assert_eq!(DiagnosticCode::UnexpectedEndOfFile, diagnostics[0].code);
+        assert_eq!(
+            "(if a {(call print 1)})",
+            Printer::new(&file).print_program(&program)
+        );
+    }
+
+    #[test]
+    fn keeps_parsing_after_misplaced_statements() -> () {
+        let file = source_file("break\n{ import a }\n1 = 2\nreturn 3");
+        let (program, diagnostics) = file.parse_partial();
+
+        let codes: Vec<DiagnosticCode> = diagnostics.iter().map(|d| d.code).collect();
+        assert_eq!(
+            vec![
+                DiagnosticCode::BreakOutsideLoop,
+                DiagnosticCode::MisplacedImport,
+                DiagnosticCode::InvalidAssignmentTarget,
+                DiagnosticCode::ReturnOutsideFunction,
+            ],
+            codes
+        );
+        assert_eq!(
+            "(break)\n{(import a)}\n(error)\n(return 3)",
+            Printer::new(&file).print_program(&program)
+        );
+    }
+
+    fn assert_parses(cases: Vec<(&str, &str)>) -> () {
+        for (input, expected) in cases {
+            let file = source_file(input);
+            let program = file.parse().expect("failed to parse");
+            assert_eq!(
+                expected,
+                Printer::new(&file).print_program(&program),
+                "{}",
+                input
+            );
+        }
+    }
+
+    fn source_file(source: &str) -> SourceFile {
+        SourceFile::from_string("test.ion", source)
+    }
+}
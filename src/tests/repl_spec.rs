@@ -0,0 +1,173 @@
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, io::Write, rc::Rc};
+
+    use crate::diagnostics::renderer::Renderer;
+    use crate::repl::{Repl, Status};
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl SharedBuffer {
+        fn take(&self) -> String {
+            String::from_utf8(self.0.take()).unwrap()
+        }
+    }
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    struct Session {
+        repl: Repl,
+        output: SharedBuffer,
+        errors: SharedBuffer,
+    }
+
+    impl Session {
+        fn new() -> Self {
+            let output = SharedBuffer::default();
+            let errors = SharedBuffer::default();
+            let repl = Repl::new(
+                Box::new(output.clone()),
+                Box::new(errors.clone()),
+                Renderer::plain(),
+            );
+
+            Self {
+                repl,
+                output,
+                errors,
+            }
+        }
+
+        /// Feeds every line, expecting the repl to be ready only after the last one
+        fn enter(&mut self, lines: &[&str]) -> String {
+            for (index, line) in lines.iter().enumerate() {
+                let expected = if index == lines.len() - 1 {
+                    Status::Ready
+                } else {
+                    Status::Continue
+                };
+                assert_eq!(expected, self.repl.feed_line(line), "{}", line);
+            }
+
+            self.output.take()
+        }
+    }
+
+    #[test]
+    fn keeps_bindings_between_entries() -> () {
+        let mut session = Session::new();
+
+        assert_eq!("", session.enter(&["let a = 1"]));
+        assert_eq!("", session.enter(&["fn f(x: int): int { return x + a }"]));
+        assert_eq!("3\n", session.enter(&["f(2)"]));
+        assert_eq!("", session.enter(&["let a = \"redefined\""]));
+        assert_eq!("\"redefined\"\n", session.enter(&["a"]));
+        assert_eq!("", session.errors.take());
+    }
+
+    #[test]
+    fn prints_values_of_expression_statements() -> () {
+        let mut session = Session::new();
+
+        assert_eq!("3\n1.5\ntrue\n", session.enter(&["1 + 2 3 / 2.0 !false"]));
+        assert_eq!("hi\n", session.enter(&["print(\"hi\")"]));
+        assert_eq!("<native fn len>\n", session.enter(&["len"]));
+    }
+
+    #[test]
+    fn asks_for_continuation_lines() -> () {
+        let mut session = Session::new();
+
+        let output = session.enter(&["fn f() {", "  return 1", "}"])
+            + &session.enter(&["f() +", "1"])
+            + &session.enter(&["\"two", "lines\""]);
+        assert_eq!("2\n\"two\\nlines\"\n", output);
+    }
+
+    #[test]
+    fn reports_errors_without_losing_state() -> () {
+        let mut session = Session::new();
+        session.enter(&["let a = 1"]);
+
+        session.enter(&["a +", ""]);
+        assert!(session.errors.take().starts_with("error[E0101]"));
+
+        session.enter(&["let b: string = a"]);
+        assert!(session.errors.take().starts_with("error[E0501]"));
+
+        session.enter(&["a ~/ 0"]);
+        let errors = session.errors.take();
+        assert!(errors.starts_with("error[E0302]"), "{}", errors);
+        assert!(errors.contains("--> <repl>:1:1"), "{}", errors);
+
+        assert_eq!("1\n", session.enter(&["a"]));
+    }
+
+    #[test]
+    fn forgets_declarations_of_rejected_entries() -> () {
+        let mut session = Session::new();
+        session.enter(&["let y = \"kept\""]);
+
+        session.enter(&["let x: int = \"a\""]);
+        assert!(session.errors.take().starts_with("error[E0501]"));
+        assert_eq!("unknown\n", session.enter(&[":type x"]));
+
+        session.enter(&["fn g(): int { return \"s\" }"]);
+        assert!(session.errors.take().starts_with("error[E0501]"));
+        assert_eq!("unknown\n", session.enter(&[":type g"]));
+
+        session.enter(&["let y: int = \"a\""]);
+        session.errors.take();
+        assert_eq!("string\n", session.enter(&[":type y"]));
+    }
+
+    #[test]
+    fn reports_runtime_errors_in_earlier_entries() -> () {
+        let mut session = Session::new();
+        session.enter(&["let a = 1"]);
+        session.enter(&["fn f(a: int): int {", "  return 100 ~/ a", "}"]);
+
+        session.enter(&["f(0)"]);
+        let errors = session.errors.take();
+        assert!(errors.starts_with("error[E0302]"), "{}", errors);
+        assert!(errors.contains("--> <repl>:2:10"), "{}", errors);
+        assert!(errors.contains("2 |   return 100 ~/ a\n"), "{}", errors);
+        assert!(errors.contains("  |          ^^^^^^^^\n"), "{}", errors);
+    }
+
+    #[test]
+    fn runs_meta_commands() -> () {
+        let mut session = Session::new();
+        session.enter(&["fn f(x: int) { return x > 1 }"]);
+
+        assert_eq!("fn(int): bool\n", session.enter(&[":type f"]));
+        assert_eq!("float\n", session.enter(&[":type 1 / 2"]));
+        assert_eq!("(+ 1 (* 2 3))\n", session.enter(&[":ast 1 + 2 * 3"]));
+        assert_eq!(
+            "LetKeyword \"let\"\nIdentifier \"x\"\n",
+            session.enter(&[":tokens let x"])
+        );
+
+        session.enter(&[":frob"]);
+        assert!(session.errors.take().starts_with("unknown command ':frob'"));
+        assert_eq!(Status::Exit, session.repl.feed_line(":quit"));
+    }
+
+    #[test]
+    fn reads_lines_until_end_of_input() -> () {
+        let mut session = Session::new();
+        let mut input = std::io::Cursor::new("let a = 2\n{\nprint(a)\n}\n:quit\nprint(3)\n");
+        session.repl.run(&mut input);
+
+        assert_eq!(">> >> .. .. 2\n>> \n", session.output.take());
+    }
+}
@@ -0,0 +1,186 @@
+#[cfg(test)]
+mod tests {
+    use crate::diagnostics::DiagnosticCode;
+    use crate::resolver::{
+        symbols::{ScopeKind, SymbolKind},
+        Resolver,
+    };
+    use crate::source::SourceFile;
+
+    #[test]
+    fn accepts_programs_using_declared_names() -> () {
+        let programs = [
+            "let a = 1 print(a) a = 2",
+            "let a = 1 let b = a + 1 { let a = b print(a) }",
+            "fn fib(n: int): int { if n < 2 { return n } return fib(n - 1) + fib(n - 2) }",
+            "fn counter() { let count = 0 fn next(): int { count = count + 1 return count } \
+             return next }",
+            "fn f() { return g() } fn g() { return 1 } f()",
+            "for i in range(0, 3) { let i = i * 2 print(i) } for i in \"ab\" {}",
+            "let print = 1 let a = 1 { let a = a }",
+            "let i = 0 while i < 3 { i = i + 1 }",
+        ];
+
+        for input in programs {
+            let program = source_file(input).parse().expect("failed to parse");
+            let result = Resolver::new().resolve(&program);
+            assert!(result.is_ok(), "{}: {:?}", input, result.unwrap_err());
+        }
+    }
+
+    #[test]
+    fn reports_scope_errors_at_offending_name() -> () {
+        let cases = [
+            ("print(a)", DiagnosticCode::UndefinedName, "a"),
+            ("a = 1", DiagnosticCode::UndefinedName, "a"),
+            ("{ let a = 1 } print(a)", DiagnosticCode::UndefinedName, "a"),
+            (
+                "fn f(x: int) {} print(x)",
+                DiagnosticCode::UndefinedName,
+                "x",
+            ),
+            ("fn f() { return y }", DiagnosticCode::UndefinedName, "y"),
+            (
+                "for i in range(0, 1) {} i",
+                DiagnosticCode::UndefinedName,
+                "i",
+            ),
+            (
+                "let a = 1 let a = 2",
+                DiagnosticCode::DuplicateDeclaration,
+                "a",
+            ),
+            (
+                "fn f() {} let f = 1",
+                DiagnosticCode::DuplicateDeclaration,
+                "f",
+            ),
+            (
+                "fn f(a: int, a: int) {}",
+                DiagnosticCode::DuplicateDeclaration,
+                "a",
+            ),
+            (
+                "fn f(a: int) { let a = 1 }",
+                DiagnosticCode::DuplicateDeclaration,
+                "a",
+            ),
+            (
+                "print(a) let a = 1",
+                DiagnosticCode::UseBeforeDefinition,
+                "a",
+            ),
+            ("let a = a", DiagnosticCode::UseBeforeDefinition, "a"),
+            (
+                "{ a = 2 let a = 1 }",
+                DiagnosticCode::UseBeforeDefinition,
+                "a",
+            ),
+            (
+                "fn f() { g() fn g() {} }",
+                DiagnosticCode::UseBeforeDefinition,
+                "g",
+            ),
+            (
+                "fn f() { fn g() { return x } let x = 1 }",
+                DiagnosticCode::UseBeforeDefinition,
+                "x",
+            ),
+        ];
+
+        for (input, code, failing_source) in cases {
+            let program = source_file(input).parse().expect("failed to parse");
+            let diagnostics = Resolver::new().resolve(&program).unwrap_err();
+
+            assert_eq!(1, diagnostics.len(), "{}: {:?}", input, diagnostics);
+            let span = &diagnostics[0].span;
+            let text: String = input
+                .chars()
+                .skip(span.start as usize)
+                .take(span.len())
+                .collect();
+
+            assert_eq!(code, diagnostics[0].code, "{}", input);
+            assert_eq!(failing_source, text, "{}", input);
+        }
+    }
+
+    #[test]
+    fn points_at_the_conflicting_declaration() -> () {
+        let file = source_file("let a = 1\nprint(b)\nlet a = 2\nlet b = 3");
+        let program = file.parse().unwrap();
+        let diagnostics = Resolver::new().resolve(&program).unwrap_err();
+
+        let labels: Vec<(usize, &str)> = diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let label = &diagnostic.labels[0];
+                (
+                    file.location(label.span.start as usize).line,
+                    label.message.as_str(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            vec![(1, "previously declared here"), (4, "'b' is defined here")],
+            labels
+        );
+    }
+
+    #[test]
+    fn binds_references_to_declarations() -> () {
+        let input = "let a = 1 fn f(a: int) { return a } { let b = a } f(a) print(f)";
+        let program = source_file(input).parse().unwrap();
+        let resolver = Resolver::new();
+        resolver.resolve(&program).unwrap();
+        let table = resolver.into_symbol_table();
+
+        let global_a = table.symbol_at(4).unwrap();
+        let parameter_a = table.symbol_at(15).unwrap();
+        assert_eq!(SymbolKind::Variable, table.symbol(global_a).kind);
+        assert_eq!(SymbolKind::Parameter, table.symbol(parameter_a).kind);
+        assert_eq!(
+            ScopeKind::Function,
+            table.scope(table.symbol(parameter_a).scope).kind
+        );
+
+        let positions = |symbol| {
+            table
+                .references_to(symbol)
+                .map(|reference| reference.span.start as usize)
+                .collect::<Vec<usize>>()
+        };
+        assert_eq!(vec![46, 52], positions(global_a));
+        assert_eq!(vec![32], positions(parameter_a));
+
+        let print = table.symbol_at(55).unwrap();
+        assert_eq!(SymbolKind::Native, table.symbol(print).kind);
+        assert_eq!(table.symbol_at(61), table.symbol_at(13));
+    }
+
+    #[test]
+    fn keeps_global_scope_between_programs() -> () {
+        let resolver = Resolver::new();
+        for input in ["let a = 1", "fn f() { return a + b }", "let b = 2"] {
+            let program = source_file(input).parse().unwrap();
+            let _ = resolver.resolve(&program);
+        }
+
+        let program = source_file("f() a").parse().unwrap();
+        assert!(resolver.resolve(&program).is_ok());
+
+        // `b` was not declared yet when `f` was resolved
+        let table = resolver.symbol_table();
+        let b = table
+            .symbols()
+            .find(|(_, symbol)| symbol.name == "b")
+            .unwrap()
+            .0;
+        assert_eq!(0, table.references_to(b).count());
+    }
+
+    fn source_file(source: &str) -> SourceFile {
+        SourceFile::from_string("test.ion", source)
+    }
+}
@@ -0,0 +1,108 @@
+#[cfg(test)]
+mod tests {
+    use std::{env, io, path::Path};
+
+    use crate::{
+        lexer::syntax::{Location, Span, SyntaxKind},
+        source::{SourceFile, SourceMap},
+    };
+
+    #[test]
+    fn creates_files_from_strings() -> () {
+        let file = SourceFile::from_string("<buffer>", "let a = 1");
+
+        assert_eq!("<buffer>", file.absolute_path);
+        assert_eq!("<buffer>", file.relative_path);
+        assert_eq!(SyntaxKind::LetKeyword, file.tokenize().first().kind);
+    }
+
+    #[test]
+    fn opens_files_relative_to_the_current_directory() -> () {
+        let file = SourceFile::open("Cargo.toml").expect("the manifest is readable");
+
+        assert_eq!("Cargo.toml", file.relative_path);
+        assert!(Path::new(&file.absolute_path).is_absolute());
+        assert!(file.source().contains("[package]"));
+    }
+
+    #[test]
+    fn opens_files_given_absolute_paths() -> () {
+        let path = env::current_dir().unwrap().join("src").join("lib.rs");
+        let file = SourceFile::open(&path).expect("the crate root is readable");
+
+        assert_eq!(
+            Path::new("src").join("lib.rs"),
+            Path::new(&file.relative_path)
+        );
+    }
+
+    #[test]
+    fn reports_unreadable_files() -> () {
+        let error = SourceFile::open("does/not/exist.ion").err().unwrap();
+
+        assert_eq!(io::ErrorKind::NotFound, error.kind());
+    }
+
+    #[test]
+    fn looks_up_lines_and_columns() -> () {
+        let file = SourceFile::from_string("test.ion", "let a = \"x\ny\"\n\nprint(a)");
+
+        assert_eq!(4, file.line_count());
+        assert_eq!(Location { line: 1, column: 0 }, file.location(0));
+        assert_eq!(
+            Location {
+                line: 1,
+                column: 10
+            },
+            file.location(10)
+        );
+        assert_eq!(Location { line: 2, column: 0 }, file.location(11));
+        assert_eq!(Location { line: 3, column: 0 }, file.location(14));
+        assert_eq!(Location { line: 4, column: 5 }, file.location(20));
+    }
+
+    #[test]
+    fn counts_columns_in_characters_from_byte_offsets() -> () {
+        let file = SourceFile::from_string("test.ion", "let é = \"😀\"\nlet ü = 1");
+
+        assert_eq!(Location { line: 1, column: 5 }, file.location(6));
+        assert_eq!(
+            Location {
+                line: 1,
+                column: 10
+            },
+            file.location(14)
+        );
+        assert_eq!(Location { line: 2, column: 0 }, file.location(16));
+        assert_eq!(Location { line: 2, column: 6 }, file.location(23));
+        assert_eq!("😀", file.text(Span::new(file.id(), 10, 14)));
+    }
+
+    #[test]
+    fn hands_out_file_ids() -> () {
+        let mut map = SourceMap::new();
+        let first = map.add(SourceFile::from_string("first.ion", "let a = 1"));
+        let second = map.add(SourceFile::from_string("second.ion", "\nlet b = 2"));
+
+        assert_ne!(first, second);
+        assert_eq!("second.ion", map.get(second).relative_path);
+        assert_eq!(Some(first), map.find("first.ion"));
+        assert_eq!(None, map.find("third.ion"));
+
+        let tokens = map.get(second).tokenize();
+        let span = tokens.first().span;
+        assert_eq!(second, span.file);
+        assert_eq!(Location { line: 2, column: 0 }, map.location(&span));
+    }
+
+    #[test]
+    fn merges_spans() -> () {
+        let tokens = SourceFile::from_string("test.ion", "a + bc").tokenize();
+        let span = tokens.first().span.to(&tokens.at(2).span);
+
+        assert_eq!(0..6, span.range());
+        assert_eq!(6, span.len());
+        assert!(span.contains(6));
+        assert!(!span.contains(7));
+    }
+}
@@ -0,0 +1,216 @@
+#[cfg(test)]
+mod tests {
+    use crate::diagnostics::DiagnosticCode;
+    use crate::parser::ast::statements::StatementNode;
+    use crate::source::SourceFile;
+    use crate::typeck::{types::Type, TypeChecker};
+
+    #[test]
+    fn accepts_well_typed_programs() -> () {
+        let programs = [
+            "let a: int = 1 + 2 * 3 let b: float = a / 2 let c: bool = a < 2 && !(b >= 1.5)",
+            "let s: string = \"a\" + \"b\" let n: int = len(s) + len(range(0, 2)) print(s, n)",
+            "let a = 6 & 3 | 1 ~ 2 let b = true ~ false let c: int = -a ~/ 2 % 3 ^ 2",
+            "let x: null = null let y = 1 y = 2 let f: fn(int): bool = even \
+             fn even(n: int): bool { return n % 2 == 0 }",
+            "fn fib(n: int): int { if n < 2 { return n } return fib(n - 1) + fib(n - 2) } \
+             let result: int = fib(10)",
+            "fn counter() { let count = 0 fn next(): int { count = count + 1 return count } \
+             return next } let next: fn(): int = counter() let n: int = next()",
+            "fn apply(f: fn(int): int, x: int): int { return f(x) } \
+             fn double(x: int): int { return x * 2 } let y: int = apply(double, 2)",
+            "for i in range(0, 3) { let j: int = i + 1 } for c in \"ab\" { let d: string = c }",
+            "let i = 0 while i < 3 { i = i + 1 if i == 2 { break } else { continue } }",
+            "fn f() {} let x: null = f() fn g() { return } let y: null = g()",
+            "let u = undefined + 1 let v: int = undefined",
+            "fn sign(x: int): int { if x < 0 { return -1 } else if x > 0 { return 1 } else { return 0 } }",
+            "fn forever(): int { while true { if false { fn g() { while true { break } } } } }",
+            "fn f(): null {} fn g(c: bool) { if c { return 1 } }",
+        ];
+
+        for input in programs {
+            let program = source_file(input).parse().expect("failed to parse");
+            let result = TypeChecker::new().check(&program);
+            assert!(result.is_ok(), "{}: {:?}", input, result.unwrap_err());
+        }
+    }
+
+    #[test]
+    fn infers_types_of_unannotated_bindings() -> () {
+        let cases = [
+            ("let a = 1", "a", "int"),
+            ("let a = 1 / 2", "a", "float"),
+            ("let a = 1 + 2.0", "a", "float"),
+            ("let a = \"a\" < \"b\"", "a", "bool"),
+            ("let a = range(0, 1)", "a", "range"),
+            (
+                "fn f(a: int, b: string): bool { return true }",
+                "f",
+                "fn(int, string): bool",
+            ),
+            ("fn f() { return 1 }", "f", "fn(): int"),
+            ("fn f() {}", "f", "fn(): null"),
+            (
+                "fn f(x: int) { if x > 0 { return 1 } return \"a\" }",
+                "f",
+                "fn(int): unknown",
+            ),
+            ("let a = print", "a", "fn(...): null"),
+            (
+                "fn f() { fn g() { return 1.5 } return g } let a = f()",
+                "a",
+                "fn(): float",
+            ),
+        ];
+
+        for (input, expression, expected) in cases {
+            let checker = TypeChecker::new();
+            checker.check(&source_file(input).parse().unwrap()).unwrap();
+
+            let program = source_file(expression).parse().unwrap();
+            let StatementNode::Expression(statement) = &program.statements[0] else {
+                panic!("expected an expression statement");
+            };
+            let ty = checker.check_expression(&statement.expression).unwrap();
+
+            assert_eq!(expected, ty.to_string(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn reports_type_errors_at_offending_node() -> () {
+        let cases = [
+            ("let a: integer = 1", DiagnosticCode::UnknownType, "integer"),
+            ("let a: int = 1.5", DiagnosticCode::TypeMismatch, "1.5"),
+            ("let a: float = 1", DiagnosticCode::TypeMismatch, "1"),
+            ("let a = 1 a = \"a\"", DiagnosticCode::TypeMismatch, "\"a\""),
+            (
+                "let a = \"a\" ~/ 2",
+                DiagnosticCode::InvalidOperandTypes,
+                "\"a\" ~/ 2",
+            ),
+            (
+                "let a = 1 && true",
+                DiagnosticCode::InvalidOperandTypes,
+                "1",
+            ),
+            (
+                "let a = true || \"b\"",
+                DiagnosticCode::InvalidOperandTypes,
+                "\"b\"",
+            ),
+            (
+                "let a = true + 1",
+                DiagnosticCode::InvalidOperandTypes,
+                "true + 1",
+            ),
+            (
+                "let a = 1.5 & 1",
+                DiagnosticCode::InvalidOperandTypes,
+                "1.5 & 1",
+            ),
+            (
+                "let a = 1 < \"b\"",
+                DiagnosticCode::InvalidOperandTypes,
+                "1 < \"b\"",
+            ),
+            (
+                "let a = -true",
+                DiagnosticCode::InvalidOperandTypes,
+                "-true",
+            ),
+            ("let a = !1", DiagnosticCode::InvalidOperandTypes, "!1"),
+            ("let a = ~1.0", DiagnosticCode::InvalidOperandTypes, "~1.0"),
+            ("let a = 1 a(2)", DiagnosticCode::NotAFunction, "a"),
+            ("range(1)", DiagnosticCode::WrongArgumentCount, "range(1)"),
+            ("range(1, \"2\")", DiagnosticCode::TypeMismatch, "\"2\""),
+            (
+                "fn f(): int { return \"a\" }",
+                DiagnosticCode::TypeMismatch,
+                "\"a\"",
+            ),
+            (
+                "fn f(): int { return }",
+                DiagnosticCode::TypeMismatch,
+                "return",
+            ),
+            (
+                "fn f(g: fn(int)) {} f(print)",
+                DiagnosticCode::TypeMismatch,
+                "print",
+            ),
+            ("if 1 {}", DiagnosticCode::TypeMismatch, "1"),
+            ("while \"a\" {}", DiagnosticCode::TypeMismatch, "\"a\""),
+            ("for i in 1 {}", DiagnosticCode::NotIterableType, "1"),
+            (
+                "for i in \"ab\" { let x: int = i }",
+                DiagnosticCode::TypeMismatch,
+                "i",
+            ),
+            (
+                "fn f(x: fn(): strin) {}",
+                DiagnosticCode::UnknownType,
+                "strin",
+            ),
+            ("fn f(): int { }", DiagnosticCode::MissingReturn, "f"),
+            (
+                "fn f(c: bool): int { if c { return 1 } } print(f(false))",
+                DiagnosticCode::MissingReturn,
+                "f",
+            ),
+            (
+                "fn f(): int { while true { break } }",
+                DiagnosticCode::MissingReturn,
+                "f",
+            ),
+        ];
+
+        for (input, code, failing_source) in cases {
+            let program = source_file(input).parse().expect("failed to parse");
+            let diagnostics = TypeChecker::new().check(&program).unwrap_err();
+
+            assert_eq!(1, diagnostics.len(), "{}: {:?}", input, diagnostics);
+            let span = &diagnostics[0].span;
+            let text: String = input
+                .chars()
+                .skip(span.start as usize)
+                .take(span.len())
+                .collect();
+
+            assert_eq!(code, diagnostics[0].code, "{}", input);
+            assert_eq!(failing_source, text, "{}", input);
+        }
+    }
+
+    #[test]
+    fn does_not_cascade_errors() -> () {
+        let program = source_file("let a: nope = 1 let b = a + 1 let c = b * 2 c = \"x\" if 1 {}")
+            .parse()
+            .unwrap();
+        let diagnostics = TypeChecker::new().check(&program).unwrap_err();
+        let codes: Vec<DiagnosticCode> = diagnostics.iter().map(|d| d.code).collect();
+
+        assert_eq!(
+            vec![DiagnosticCode::UnknownType, DiagnosticCode::TypeMismatch],
+            codes
+        );
+    }
+
+    #[test]
+    fn keeps_global_scope_between_programs() -> () {
+        let checker = TypeChecker::new();
+        checker
+            .check(&source_file("let a = 1").parse().unwrap())
+            .unwrap();
+
+        let diagnostics = checker
+            .check(&source_file("let b: string = a").parse().unwrap())
+            .unwrap_err();
+        assert_eq!("expected string, found int", diagnostics[0].message);
+        assert!(Type::Int.is_assignable_to(&Type::Unknown));
+    }
+
+    fn source_file(source: &str) -> SourceFile {
+        SourceFile::from_string("test.ion", source)
+    }
+}
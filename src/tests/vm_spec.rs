@@ -0,0 +1,180 @@
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, io::Write, rc::Rc};
+
+    use crate::compiler::{disassembler, Compiler};
+    use crate::interpreter::{error::RuntimeError, Interpreter};
+    use crate::source::SourceFile;
+    use crate::vm::Vm;
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn prints_the_same_as_the_interpreter() -> () {
+        let programs = [
+            "print(1 + 2 * 3, 1 / 2, -7 ~/ 2, -7 % 3, 2 ^ 3 ^ 2, 2 ^ -1.0)",
+            "print(6 & 3, 6 | 3, 6 ~ 3, ~0, !true, \"ab\" + \"cd\")",
+            "print(true && false, false || true, false && 1 / 0 == 0, true || x)",
+            "print(1 < 2, 1 == 1.0, \"a\" < \"b\", null == null, 1 == \"1\")",
+            "let a = 1 { let a = 2 print(a) } print(a)",
+            "let a = 1 { a = 2 } print(a)",
+            "let a = 1 print(a = 5, a)",
+            "fn f() {} print(f(), f, print)",
+            "fn fib(n: int): int { if n < 2 { return n } return fib(n - 1) + fib(n - 2) } \
+             print(fib(15))",
+            "fn counter() { let count = 0 \
+             fn next(): int { count = count + 1 return count } return next } \
+             let next = counter() next() print(next(), counter()())",
+            "fn outer() { let x = 1 fn middle() { fn inner() { x = x + 1 return x } return inner } \
+             let f = middle() f() print(f(), x) } outer()",
+            "{ let fs = 0 for i in range(0, 3) { fn f() { return i } if i == 1 { fs = f } } \
+             print(fs()) }",
+            "{ fn even(n: int): bool { if n == 0 { return true } return !even(n - 1) } \
+             print(even(10)) }",
+            "if 1 > 2 { print(1) } else if 2 > 1 { print(2) } else { print(3) }",
+            "let i = 0 while i < 3 { i = i + 1 } print(i)",
+            "for i in range(0, 3) { print(i) } for c in \"ab\" { print(c) }",
+            "for i in range(0, 10) { let x = i * 2 if i == 1 { continue } \
+             if i == 3 { break } print(x) }",
+            "for i in range(0, 3) { for j in range(0, 3) { if j > i { break } print(i, j) } }",
+            "let i = 0 while true { i = i + 1 if i == 5 { break } } print(i)",
+            "fn first(): int { for i in range(5, 10) { return i } return 0 } print(first())",
+            "print(len(\"héllo\"), len(range(3, 1)), str(1.0) + str(null))",
+            "let name = \"ion\" print(\"hello {name}, {1 + 1} {\"{null}\"}\\n\")",
+        ];
+
+        for input in programs {
+            let program = source_file(input).parse().expect("failed to parse");
+
+            let expected = SharedBuffer::default();
+            Interpreter::with_output(Box::new(expected.clone()))
+                .interpret(&program)
+                .expect("failed to interpret");
+
+            assert_eq!(
+                String::from_utf8(expected.0.take()).unwrap(),
+                run(input).expect("failed to run"),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn reports_the_same_runtime_errors_as_the_interpreter() -> () {
+        let programs = [
+            "x",
+            "x = 1",
+            "1 + 1 / 0",
+            "1 % 0.0",
+            "2 ^ 63",
+            "1 + 2 ^ -1",
+            "-(-9223372036854775807 - 1)",
+            "1(2)",
+            "fn f(a: int) {} f()",
+            "range(1)",
+            "range(1, \"2\")",
+            "for i in 1 {}",
+            "if 1 {}",
+            "while null {}",
+            "fn f() { f() } f()",
+            "1 && true",
+            "true && 1",
+            "true - 1",
+            "!1",
+            "fn f() { let a = 1 return a + g() } f()",
+        ];
+
+        for input in programs {
+            let program = source_file(input).parse().expect("failed to parse");
+            let expected = Interpreter::with_output(Box::new(SharedBuffer::default()))
+                .interpret(&program)
+                .unwrap_err();
+            let error = run(input).unwrap_err();
+
+            assert_eq!(expected.kind, error.kind, "{}", input);
+            assert_eq!(expected.span, error.span, "{}", input);
+        }
+    }
+
+    #[test]
+    fn keeps_global_state_between_programs() -> () {
+        let buffer = SharedBuffer::default();
+        let mut vm = Vm::with_output(Box::new(buffer.clone()));
+
+        for input in [
+            "let a = 1",
+            "fn f(): int { return a + 1 }",
+            "f(1)",
+            "print(f())",
+        ] {
+            let program = source_file(input).parse().unwrap();
+            let function = Compiler::new().compile(&program).unwrap();
+            let _ = vm.run(function);
+        }
+
+        assert_eq!("2\n", String::from_utf8(buffer.0.take()).unwrap());
+    }
+
+    #[test]
+    fn disassembles_chunks() -> () {
+        let file = source_file("let a = 1\nfn f(b: int) { return a + b }\nprint(f(2))");
+        let program = file.parse().unwrap();
+        let function = Compiler::new().compile(&program).unwrap();
+
+        let expected = "\
+== <script> ==
+0000    1 Constant            0 '1'
+0003    | DefineGlobal        1 '\"a\"'
+0006    2 Closure             2 '<fn f>'
+0009    | DefineGlobal        3 '\"f\"'
+0012    3 GetGlobal           4 '\"print\"'
+0015    | GetGlobal           5 '\"f\"'
+0018    | Constant            6 '2'
+0021    | Call                1
+0023    | Call                1
+0025    | Pop
+0026    | Null
+0027    | Return
+
+== f ==
+0000    2 GetGlobal           0 '\"a\"'
+0003    | GetLocal            1
+0005    | Add
+0006    | Return
+0007    | Null
+0008    | Return
+";
+
+        assert_eq!(
+            expected,
+            disassembler::disassemble(&function.chunk, "<script>", &file)
+        );
+    }
+
+    fn run(input: &str) -> Result<String, RuntimeError> {
+        let program = source_file(input).parse().expect("failed to parse");
+        let function = Compiler::new()
+            .compile(&program)
+            .expect("failed to compile");
+        let buffer = SharedBuffer::default();
+
+        Vm::with_output(Box::new(buffer.clone())).run(function)?;
+        Ok(String::from_utf8(buffer.0.take()).unwrap())
+    }
+
+    fn source_file(source: &str) -> SourceFile {
+        SourceFile::from_string("test.ion", source)
+    }
+}
@@ -0,0 +1,663 @@
+pub mod types;
+
+use std::{cell::RefCell, collections::HashMap};
+
+use crate::{
+    diagnostics::{Diagnostic, DiagnosticCode},
+    lexer::{
+        syntax::{LiteralValue, Span, SyntaxKind},
+        syntax_facts,
+    },
+    parser::ast::{
+        base::{Expression, Identifier, Statement},
+        expressions::{
+            Assignment, Binary, Call, ErrorExpression, ExpressionNode, Grouping, Interpolation,
+            InterpolationPart, Literal, Unary, Variable,
+        },
+        statements::{
+            BlockStatement, BreakStatement, ContinueStatement, ErrorStatement, ExpressionStatement,
+            ForStatement, FunctionDeclaration, IfStatement, ImportStatement, LetStatement, Program,
+            ReturnStatement, StatementNode, WhileStatement,
+        },
+        types::TypeAnnotation,
+        visitor::{ExpressionVisitor, StatementVisitor, SyntaxNodeVisitor},
+    },
+};
+use types::{FunctionType, Type};
+
+/// The function whose body is currently being checked
+struct FunctionContext {
+    /// The annotated return type, or None if it is inferred from the return statements
+    return_type: Option<Type>,
+    returned: Vec<Type>,
+}
+
+/// Checks that every operation in a program is applied to values of the right types
+///
+/// Bindings without an annotation take the type of their initializer, and functions without
+/// a return type take the type of the values they return. Like the `Interpreter`, the checker
+/// keeps its global scope between calls to `check`, though only for programs without errors
+pub struct TypeChecker {
+    scopes: RefCell<Vec<HashMap<String, Type>>>,
+    functions: RefCell<Vec<FunctionContext>>,
+    /// The type of every declared name and every use of one, by the span of the name
+    names: RefCell<HashMap<Span, Type>>,
+    diagnostics: RefCell<Vec<Diagnostic>>,
+}
+
+impl Default for TypeChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TypeChecker {
+    pub fn new() -> Self {
+        Self {
+            scopes: RefCell::new(vec![get_native_types()]),
+            functions: RefCell::new(Vec::new()),
+            names: RefCell::new(HashMap::new()),
+            diagnostics: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn check(&self, program: &Program) -> Result<(), Vec<Diagnostic>> {
+        // a program with errors is never run, so its declarations must not outlive the check
+        let globals = self.scopes.borrow().last().unwrap().clone();
+        for statement in program.statements.iter() {
+            self.check_statement(statement);
+        }
+
+        let result = self.finish(());
+        if result.is_err() {
+            *self.scopes.borrow_mut().last_mut().unwrap() = globals;
+        }
+        result
+    }
+
+    /// Returns the type of a single expression, checked against the global scope
+    pub fn check_expression(&self, expression: &ExpressionNode) -> Result<Type, Vec<Diagnostic>> {
+        let ty = self.type_of(expression);
+        self.finish(ty)
+    }
+
+    /// Returns the type of the name declared or used at `span`, e.g. for showing it in an editor
+    pub fn type_of_name(&self, span: &Span) -> Option<Type> {
+        self.names.borrow().get(span).cloned()
+    }
+
+    fn finish<T>(&self, value: T) -> Result<T, Vec<Diagnostic>> {
+        let diagnostics = self.diagnostics.take();
+        if diagnostics.is_empty() {
+            Ok(value)
+        } else {
+            Err(diagnostics)
+        }
+    }
+
+    fn check_statement(&self, statement: &StatementNode) -> () {
+        Statement::accept(statement, self)
+    }
+
+    fn type_of(&self, expression: &impl Expression) -> Type {
+        Expression::accept(expression, self)
+    }
+
+    fn report(&self, diagnostic: Diagnostic) -> () {
+        self.diagnostics.borrow_mut().push(diagnostic);
+    }
+
+    /// Reports a mismatch unless `found` can be used where `expected` is required
+    fn expect_type(&self, expected: &Type, found: &Type, span: Span) -> bool {
+        if found.is_assignable_to(expected) {
+            return true;
+        }
+
+        self.report(Diagnostic::error(
+            DiagnosticCode::TypeMismatch,
+            format!("expected {}, found {}", expected, found),
+            span,
+        ));
+        false
+    }
+
+    fn declare(&self, name: &Identifier, ty: Type) -> () {
+        self.names.borrow_mut().insert(name.span, ty.clone());
+        let mut scopes = self.scopes.borrow_mut();
+        scopes.last_mut().unwrap().insert(name.text.clone(), ty);
+    }
+
+    fn lookup(&self, name: &str) -> Option<Type> {
+        let scopes = self.scopes.borrow();
+        scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name).cloned())
+    }
+
+    fn begin_scope(&self) -> () {
+        self.scopes.borrow_mut().push(HashMap::new());
+    }
+
+    fn end_scope(&self) -> () {
+        self.scopes.borrow_mut().pop();
+    }
+
+    fn check_block(&self, statements: &[StatementNode]) -> () {
+        self.begin_scope();
+        for statement in statements.iter() {
+            self.check_statement(statement);
+        }
+        self.end_scope();
+    }
+
+    fn resolve_annotation(&self, annotation: &TypeAnnotation) -> Type {
+        match annotation {
+            TypeAnnotation::Named(name) => Type::from_name(&name.text).unwrap_or_else(|| {
+                self.report(Diagnostic::error(
+                    DiagnosticCode::UnknownType,
+                    format!("unknown type '{}'", name.text),
+                    name.span,
+                ));
+                Type::Unknown
+            }),
+            TypeAnnotation::Function(function) => {
+                let parameters = function
+                    .parameters
+                    .iter()
+                    .map(|parameter| self.resolve_annotation(parameter))
+                    .collect();
+                let return_type = match &function.return_type {
+                    Some(return_type) => self.resolve_annotation(return_type),
+                    None => Type::Null,
+                };
+
+                Type::function(parameters, return_type)
+            }
+        }
+    }
+
+    /// Checks that an operand of `&&` or `||` is a boolean
+    fn check_logical_operand(&self, operator: &str, operand: &Type, span: Span) -> () {
+        if !Type::Bool.is_assignable_to(operand) {
+            self.report(Diagnostic::error(
+                DiagnosticCode::InvalidOperandTypes,
+                format!(
+                    "cannot apply '{}' to an operand of type {}",
+                    operator, operand
+                ),
+                span,
+            ));
+        }
+    }
+}
+
+/// Returns the types of the natives, see `interpreter::natives`
+fn get_native_types() -> HashMap<String, Type> {
+    let print = Type::Function(FunctionType {
+        parameters: Vec::new(),
+        variadic: true,
+        return_type: Box::new(Type::Null),
+    });
+
+    HashMap::from([
+        ("print".to_string(), print),
+        (
+            "range".to_string(),
+            Type::function(vec![Type::Int, Type::Int], Type::Range),
+        ),
+        (
+            "len".to_string(),
+            Type::function(vec![Type::Unknown], Type::Int),
+        ),
+        (
+            "str".to_string(),
+            Type::function(vec![Type::Unknown], Type::String),
+        ),
+    ])
+}
+
+/// Returns the type of applying a (non short-circuiting) infix operator, or None if the operator
+/// does not apply to the operands
+///
+/// `^` on two ints is an int, raising an int to a negative power is a runtime error
+fn get_binary_type(kind: SyntaxKind, left: &Type, right: &Type) -> Option<Type> {
+    use Type::{Bool, Float, Int, Unknown};
+
+    match kind {
+        SyntaxKind::EqualsEquals | SyntaxKind::BangEquals => return Some(Bool),
+        _ if *left == Unknown || *right == Unknown => {
+            let ty = match kind {
+                SyntaxKind::LT | SyntaxKind::LTE | SyntaxKind::GT | SyntaxKind::GTE => Bool,
+                SyntaxKind::Slash => Float,
+                _ => Unknown,
+            };
+            return Some(ty);
+        }
+        _ => (),
+    }
+
+    let numeric = left.is_numeric() && right.is_numeric();
+    let ty = match (kind, left, right) {
+        (SyntaxKind::Plus, Type::String, Type::String) => Type::String,
+        (
+            SyntaxKind::Plus
+            | SyntaxKind::Minus
+            | SyntaxKind::Star
+            | SyntaxKind::TildeSlash
+            | SyntaxKind::Percent
+            | SyntaxKind::Carat,
+            Int,
+            Int,
+        ) => Int,
+        (
+            SyntaxKind::Plus
+            | SyntaxKind::Minus
+            | SyntaxKind::Star
+            | SyntaxKind::Slash
+            | SyntaxKind::TildeSlash
+            | SyntaxKind::Percent
+            | SyntaxKind::Carat,
+            _,
+            _,
+        ) if numeric => Float,
+        (SyntaxKind::Ampersand | SyntaxKind::Pipe | SyntaxKind::Tilde, Int, Int) => Int,
+        (SyntaxKind::Ampersand | SyntaxKind::Pipe | SyntaxKind::Tilde, Bool, Bool) => Bool,
+        (SyntaxKind::LT | SyntaxKind::LTE | SyntaxKind::GT | SyntaxKind::GTE, _, _)
+            if numeric || (*left == Type::String && *right == Type::String) =>
+        {
+            Bool
+        }
+        _ => return None,
+    };
+
+    Some(ty)
+}
+
+fn get_unary_type(kind: SyntaxKind, operand: &Type) -> Option<Type> {
+    match (kind, operand) {
+        (_, Type::Unknown) => Some(Type::Unknown),
+        (SyntaxKind::Minus, Type::Int | Type::Float) => Some(operand.clone()),
+        (SyntaxKind::Bang, Type::Bool) => Some(Type::Bool),
+        (SyntaxKind::Tilde, Type::Int) => Some(Type::Int),
+        _ => None,
+    }
+}
+
+/// Returns whether running the statements always ends in a return statement
+///
+/// Only an `if` with an `else` and a `while true` without a `break` are followed, the value of any
+/// other condition is not considered
+fn always_returns(statements: &[StatementNode]) -> bool {
+    statements.iter().any(|statement| match statement {
+        StatementNode::Return(_) => true,
+        StatementNode::Block(block) => always_returns(&block.statements),
+        StatementNode::If(if_statement) => match &if_statement.else_branch {
+            Some(else_branch) => {
+                always_returns(&if_statement.then_branch.statements)
+                    && always_returns(std::slice::from_ref(else_branch.as_ref()))
+            }
+            None => false,
+        },
+        StatementNode::While(while_statement) => {
+            is_true(&while_statement.condition) && !breaks(&while_statement.body.statements)
+        }
+        _ => false,
+    })
+}
+
+/// Returns whether the statements contain a `break` out of the enclosing loop
+fn breaks(statements: &[StatementNode]) -> bool {
+    statements.iter().any(|statement| match statement {
+        StatementNode::Break(_) => true,
+        StatementNode::Block(block) => breaks(&block.statements),
+        StatementNode::If(if_statement) => {
+            breaks(&if_statement.then_branch.statements)
+                || if_statement
+                    .else_branch
+                    .as_ref()
+                    .is_some_and(|else_branch| breaks(std::slice::from_ref(else_branch.as_ref())))
+        }
+        _ => false,
+    })
+}
+
+fn is_true(expression: &ExpressionNode) -> bool {
+    match expression {
+        ExpressionNode::Literal(literal) => literal.token.value == Some(LiteralValue::Bool(true)),
+        ExpressionNode::Grouping(grouping) => is_true(&grouping.expression),
+        _ => false,
+    }
+}
+
+impl SyntaxNodeVisitor<Type> for TypeChecker {}
+
+impl ExpressionVisitor<Type> for TypeChecker {
+    fn visit_literal(&self, literal: &Literal) -> Type {
+        match literal.token.kind {
+            SyntaxKind::IntLiteral => Type::Int,
+            SyntaxKind::FloatLiteral => Type::Float,
+            SyntaxKind::StringLiteral => Type::String,
+            SyntaxKind::BoolLiteral => Type::Bool,
+            _ => Type::Null,
+        }
+    }
+
+    fn visit_interpolation(&self, interpolation: &Interpolation) -> Type {
+        // any value can be converted to text, so the parts only need checking on their own
+        for part in interpolation.parts.iter() {
+            if let InterpolationPart::Expression(expression) = part {
+                self.type_of(expression);
+            }
+        }
+
+        Type::String
+    }
+
+    fn visit_binary(&self, binary: &Binary) -> Type {
+        let left = self.type_of(binary.left.as_ref());
+        let right = self.type_of(binary.right.as_ref());
+        let operator = &binary.operator;
+        let lexeme = syntax_facts::get_operator_lexeme(&operator.kind).unwrap_or_default();
+
+        if matches!(
+            operator.kind,
+            SyntaxKind::AmpersandAmpersand | SyntaxKind::PipePipe
+        ) {
+            self.check_logical_operand(lexeme, &left, binary.left.span());
+            self.check_logical_operand(lexeme, &right, binary.right.span());
+            return Type::Bool;
+        }
+
+        get_binary_type(operator.kind, &left, &right).unwrap_or_else(|| {
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidOperandTypes,
+                    format!(
+                        "cannot apply '{}' to operands of type {} and {}",
+                        lexeme, left, right
+                    ),
+                    binary.left.span().to(&binary.right.span()),
+                )
+                .with_label(binary.left.span(), left.to_string())
+                .with_label(binary.right.span(), right.to_string()),
+            );
+            Type::Unknown
+        })
+    }
+
+    fn visit_unary(&self, unary: &Unary) -> Type {
+        let operand = self.type_of(unary.operand.as_ref());
+        let operator = &unary.operator;
+
+        get_unary_type(operator.kind, &operand).unwrap_or_else(|| {
+            let lexeme = syntax_facts::get_operator_lexeme(&operator.kind).unwrap_or_default();
+            self.report(Diagnostic::error(
+                DiagnosticCode::InvalidOperandTypes,
+                format!(
+                    "cannot apply '{}' to an operand of type {}",
+                    lexeme, operand
+                ),
+                operator.span.to(&unary.operand.span()),
+            ));
+            Type::Unknown
+        })
+    }
+
+    fn visit_grouping(&self, grouping: &Grouping) -> Type {
+        self.type_of(grouping.expression.as_ref())
+    }
+
+    /// Names that cannot be found are reported by the `Resolver`
+    fn visit_variable(&self, variable: &Variable) -> Type {
+        let ty = self.lookup(&variable.name.text).unwrap_or(Type::Unknown);
+        self.names
+            .borrow_mut()
+            .insert(variable.name.span, ty.clone());
+
+        ty
+    }
+
+    fn visit_assignment(&self, assignment: &Assignment) -> Type {
+        let value = self.type_of(assignment.value.as_ref());
+        let name = &assignment.name;
+
+        if let Some(ty) = self.lookup(&name.text) {
+            self.names.borrow_mut().insert(name.span, ty.clone());
+            if !value.is_assignable_to(&ty) {
+                self.report(
+                    Diagnostic::error(
+                        DiagnosticCode::TypeMismatch,
+                        format!("expected {}, found {}", ty, value),
+                        assignment.value.span(),
+                    )
+                    .with_label(name.span, format!("'{}' has type {}", name.text, ty)),
+                );
+            }
+        }
+
+        value
+    }
+
+    fn visit_call(&self, call: &Call) -> Type {
+        let callee = self.type_of(call.callee.as_ref());
+        let arguments: Vec<Type> = call
+            .arguments
+            .iter()
+            .map(|argument| self.type_of(argument))
+            .collect();
+
+        let function = match callee {
+            Type::Function(function) => function,
+            Type::Unknown => return Type::Unknown,
+            ty => {
+                self.report(Diagnostic::error(
+                    DiagnosticCode::NotAFunction,
+                    format!("a value of type {} is not callable", ty),
+                    call.callee.span(),
+                ));
+                return Type::Unknown;
+            }
+        };
+
+        let expected = function.parameters.len();
+        let count_matches = if function.variadic {
+            arguments.len() >= expected
+        } else {
+            arguments.len() == expected
+        };
+        if !count_matches {
+            self.report(Diagnostic::error(
+                DiagnosticCode::WrongArgumentCount,
+                format!(
+                    "expected {} argument(s), but {} were given",
+                    expected,
+                    arguments.len()
+                ),
+                call.span,
+            ));
+        }
+
+        for ((parameter, argument), expression) in function
+            .parameters
+            .iter()
+            .zip(arguments.iter())
+            .zip(call.arguments.iter())
+        {
+            self.expect_type(parameter, argument, expression.span());
+        }
+
+        *function.return_type
+    }
+
+    fn visit_error_expression(&self, _error: &ErrorExpression) -> Type {
+        Type::Unknown
+    }
+}
+
+impl SyntaxNodeVisitor<()> for TypeChecker {}
+
+impl StatementVisitor<()> for TypeChecker {
+    fn visit_let_statement(&self, let_statement: &LetStatement) -> () {
+        let initializer = self.type_of(&let_statement.initializer);
+
+        let ty = match &let_statement.type_annotation {
+            Some(annotation) => {
+                let annotated = self.resolve_annotation(annotation);
+                if !initializer.is_assignable_to(&annotated) {
+                    self.report(
+                        Diagnostic::error(
+                            DiagnosticCode::TypeMismatch,
+                            format!("expected {}, found {}", annotated, initializer),
+                            let_statement.initializer.span(),
+                        )
+                        .with_label(annotation.span(), "expected due to this"),
+                    );
+                }
+
+                annotated
+            }
+            None => initializer,
+        };
+
+        self.declare(&let_statement.name, ty);
+    }
+
+    fn visit_expression_statement(&self, expression_statement: &ExpressionStatement) -> () {
+        self.type_of(&expression_statement.expression);
+    }
+
+    fn visit_block_statement(&self, block: &BlockStatement) -> () {
+        self.check_block(&block.statements);
+    }
+
+    fn visit_function_declaration(&self, function: &FunctionDeclaration) -> () {
+        let parameters: Vec<Type> = function
+            .parameters
+            .iter()
+            .map(|parameter| self.resolve_annotation(&parameter.type_annotation))
+            .collect();
+        let return_type = function
+            .return_type
+            .as_ref()
+            .map(|annotation| self.resolve_annotation(annotation));
+
+        // declared before checking the body so the function can call itself, recursive calls
+        // to a function without a return type have an unknown result
+        let name = &function.name;
+        let preliminary = return_type.clone().unwrap_or(Type::Unknown);
+        self.declare(name, Type::function(parameters.clone(), preliminary));
+
+        self.functions.borrow_mut().push(FunctionContext {
+            return_type: return_type.clone(),
+            returned: Vec::new(),
+        });
+        self.begin_scope();
+        for (parameter, ty) in function.parameters.iter().zip(parameters.iter()) {
+            self.declare(&parameter.name, ty.clone());
+        }
+        for statement in function.body.statements.iter() {
+            self.check_statement(statement);
+        }
+        self.end_scope();
+        let context = self.functions.borrow_mut().pop().unwrap();
+
+        let returns_value =
+            matches!(&return_type, Some(ty) if *ty != Type::Null && *ty != Type::Unknown);
+        if returns_value && !always_returns(&function.body.statements) {
+            let body = function.body.span;
+            let closing_brace = Span {
+                start: body.end - 1,
+                ..body
+            };
+            self.report(
+                Diagnostic::error(
+                    DiagnosticCode::MissingReturn,
+                    format!(
+                        "function '{}' does not return a value on every path",
+                        name.text
+                    ),
+                    name.span,
+                )
+                .with_label(closing_brace, "the function can reach its end here")
+                .with_help("add a return statement at the end of the function"),
+            );
+        }
+
+        let return_type = return_type.unwrap_or_else(|| {
+            let mut returned = context.returned.into_iter();
+            match returned.next() {
+                Some(first) if returned.all(|ty| ty == first) => first,
+                Some(_) => Type::Unknown,
+                None => Type::Null,
+            }
+        });
+        self.declare(name, Type::function(parameters, return_type));
+    }
+
+    fn visit_return_statement(&self, return_statement: &ReturnStatement) -> () {
+        let (ty, span) = match &return_statement.value {
+            Some(value) => (self.type_of(value), value.span()),
+            None => (Type::Null, return_statement.keyword.span),
+        };
+
+        let expected = match self.functions.borrow().last() {
+            Some(context) => context.return_type.clone(),
+            None => return,
+        };
+        if let Some(expected) = expected {
+            self.expect_type(&expected, &ty, span);
+        }
+
+        if let Some(context) = self.functions.borrow_mut().last_mut() {
+            context.returned.push(ty);
+        }
+    }
+
+    fn visit_if_statement(&self, if_statement: &IfStatement) -> () {
+        let condition = self.type_of(&if_statement.condition);
+        self.expect_type(&Type::Bool, &condition, if_statement.condition.span());
+
+        self.check_block(&if_statement.then_branch.statements);
+        if let Some(else_branch) = &if_statement.else_branch {
+            self.check_statement(else_branch);
+        }
+    }
+
+    fn visit_while_statement(&self, while_statement: &WhileStatement) -> () {
+        let condition = self.type_of(&while_statement.condition);
+        self.expect_type(&Type::Bool, &condition, while_statement.condition.span());
+
+        self.check_block(&while_statement.body.statements);
+    }
+
+    fn visit_for_statement(&self, for_statement: &ForStatement) -> () {
+        let iterable = self.type_of(&for_statement.iterable);
+        let item = match iterable {
+            Type::Range => Type::Int,
+            Type::String => Type::String,
+            Type::Unknown => Type::Unknown,
+            ty => {
+                self.report(Diagnostic::error(
+                    DiagnosticCode::NotIterableType,
+                    format!("a value of type {} is not iterable", ty),
+                    for_statement.iterable.span(),
+                ));
+                Type::Unknown
+            }
+        };
+
+        self.begin_scope();
+        self.declare(&for_statement.variable, item);
+        self.check_block(&for_statement.body.statements);
+        self.end_scope();
+    }
+
+    fn visit_break_statement(&self, _break_statement: &BreakStatement) -> () {}
+
+    fn visit_continue_statement(&self, _continue_statement: &ContinueStatement) -> () {}
+
+    fn visit_import_statement(&self, _import: &ImportStatement) -> () {}
+
+    fn visit_error_statement(&self, _error: &ErrorStatement) -> () {}
+}
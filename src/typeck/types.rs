@@ -0,0 +1,100 @@
+use std::fmt;
+
+/// The static type of an expression or binding
+#[derive(Debug, Clone, PartialEq)]
+pub enum Type {
+    Int,
+    Float,
+    String,
+    Bool,
+    Null,
+    Range,
+    Function(FunctionType),
+    /// The type of anything that could not be typed, e.g. because of an earlier error
+    ///
+    /// It is compatible with every type, so a single mistake does not cascade into many
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionType {
+    pub parameters: Vec<Type>,
+    /// Whether any number of further arguments of any type are accepted, as by `print`
+    pub variadic: bool,
+    pub return_type: Box<Type>,
+}
+
+impl Type {
+    /// Returns the type with the given name, or None if there is no such type
+    pub fn from_name(name: &str) -> Option<Type> {
+        let ty = match name {
+            "int" => Type::Int,
+            "float" => Type::Float,
+            "string" => Type::String,
+            "bool" => Type::Bool,
+            "null" => Type::Null,
+            "range" => Type::Range,
+            _ => return None,
+        };
+
+        Some(ty)
+    }
+
+    pub fn function(parameters: Vec<Type>, return_type: Type) -> Type {
+        Type::Function(FunctionType {
+            parameters,
+            variadic: false,
+            return_type: Box::new(return_type),
+        })
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, Type::Int | Type::Float)
+    }
+
+    /// Returns whether a value of this type can be stored where `target` is expected
+    ///
+    /// There are no implicit conversions, so an int is not assignable to a float
+    pub fn is_assignable_to(&self, target: &Type) -> bool {
+        match (self, target) {
+            (Type::Unknown, _) | (_, Type::Unknown) => true,
+            (Type::Function(source), Type::Function(target)) => {
+                source.variadic == target.variadic
+                    && source.parameters.len() == target.parameters.len()
+                    && source
+                        .parameters
+                        .iter()
+                        .zip(target.parameters.iter())
+                        .all(|(source, target)| target.is_assignable_to(source))
+                    && source.return_type.is_assignable_to(&target.return_type)
+            }
+            _ => self == target,
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Type::Int => write!(f, "int"),
+            Type::Float => write!(f, "float"),
+            Type::String => write!(f, "string"),
+            Type::Bool => write!(f, "bool"),
+            Type::Null => write!(f, "null"),
+            Type::Range => write!(f, "range"),
+            Type::Function(function) => {
+                let mut parameters: Vec<String> = function
+                    .parameters
+                    .iter()
+                    .map(|parameter| parameter.to_string())
+                    .collect();
+                if function.variadic {
+                    parameters.push("...".to_string());
+                }
+
+                write!(f, "fn({}): {}", parameters.join(", "), function.return_type)
+            }
+            Type::Unknown => write!(f, "unknown"),
+        }
+    }
+}
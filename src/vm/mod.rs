@@ -0,0 +1,460 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    io::{self, Write},
+    rc::Rc,
+};
+
+use crate::{
+    compiler::chunk::{CompiledFunction, Constant, OpCode},
+    interpreter::{
+        error::{RuntimeError, RuntimeErrorKind},
+        natives, operators,
+        value::Value,
+        MAX_CALL_DEPTH,
+    },
+    lexer::syntax::SyntaxKind,
+};
+
+/// A variable captured by a closure
+///
+/// While the variable is still on the stack the upvalue points at its slot, once the variable
+/// goes out of scope its value is moved into the upvalue itself
+pub enum Upvalue {
+    Open(usize),
+    Closed(Value),
+}
+
+/// A compiled function along with the variables it captured when it was declared
+pub struct Closure {
+    pub function: Rc<CompiledFunction>,
+    pub upvalues: Vec<Rc<RefCell<Upvalue>>>,
+}
+
+struct CallFrame {
+    closure: Rc<Closure>,
+    ip: usize,
+    /// Stack index of slot zero, which holds the called closure
+    base: usize,
+}
+
+/// Runs functions produced by the `compiler` on a value stack
+///
+/// Like the `Interpreter`, the vm keeps its globals between calls to `run`
+pub struct Vm {
+    stack: Vec<Value>,
+    frames: Vec<CallFrame>,
+    globals: HashMap<String, Value>,
+    /// Upvalues still pointing into the stack, so closures capturing the same variable share it
+    open_upvalues: Vec<Rc<RefCell<Upvalue>>>,
+    output: Box<dyn Write>,
+}
+
+impl Default for Vm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Vm {
+    pub fn new() -> Self {
+        Self::with_output(Box::new(io::stdout()))
+    }
+
+    /// Creates a vm that writes everything printed by the program into `output`
+    pub fn with_output(output: Box<dyn Write>) -> Self {
+        let globals = natives::get_natives()
+            .into_iter()
+            .map(|native| (native.name.to_string(), Value::NativeFunction(native)))
+            .collect();
+
+        Self {
+            stack: Vec::new(),
+            frames: Vec::new(),
+            globals,
+            open_upvalues: Vec::new(),
+            output,
+        }
+    }
+
+    pub fn run(&mut self, function: CompiledFunction) -> Result<(), RuntimeError> {
+        let closure = Rc::new(Closure {
+            function: Rc::new(function),
+            upvalues: Vec::new(),
+        });
+
+        self.stack.push(Value::Closure(closure.clone()));
+        self.frames.push(CallFrame {
+            closure,
+            ip: 0,
+            base: 0,
+        });
+
+        let result = self.execute();
+        if result.is_err() {
+            self.stack.clear();
+            self.frames.clear();
+            self.open_upvalues.clear();
+        }
+
+        result
+    }
+
+    fn execute(&mut self) -> Result<(), RuntimeError> {
+        loop {
+            let offset = self.frame().ip;
+            match self.step() {
+                Ok(true) => return Ok(()),
+                Ok(false) => (),
+                Err(kind) => {
+                    let span = self.frame().closure.function.chunk.span_at(offset);
+                    return Err(RuntimeError::new(kind, span));
+                }
+            }
+        }
+    }
+
+    /// Executes a single instruction, returning whether the program has finished
+    fn step(&mut self) -> Result<bool, RuntimeErrorKind> {
+        let byte = self.read_byte();
+        let op = OpCode::from_byte(byte).expect("the compiler only emits known opcodes");
+
+        match op {
+            OpCode::Constant => {
+                let value = match self.read_constant() {
+                    Constant::Int(value) => Value::Int(*value),
+                    Constant::Float(value) => Value::Float(*value),
+                    Constant::String(value) => Value::String(value.clone()),
+                    Constant::Function(_) => unreachable!("functions are loaded with Closure"),
+                };
+                self.stack.push(value);
+            }
+            OpCode::Null => self.stack.push(Value::Null),
+            OpCode::True => self.stack.push(Value::Bool(true)),
+            OpCode::False => self.stack.push(Value::Bool(false)),
+            OpCode::Pop => {
+                self.stack.pop();
+            }
+
+            OpCode::GetLocal => {
+                let slot = self.frame().base + self.read_byte() as usize;
+                self.stack.push(self.stack[slot].clone());
+            }
+            OpCode::SetLocal => {
+                let slot = self.frame().base + self.read_byte() as usize;
+                self.stack[slot] = self.peek().clone();
+            }
+            OpCode::DefineGlobal => {
+                let name = self.read_name();
+                let value = self.stack.pop().unwrap();
+                self.globals.insert(name, value);
+            }
+            OpCode::GetGlobal => {
+                let name = self.read_name();
+                let value = match self.globals.get(&name) {
+                    Some(value) => value.clone(),
+                    None => return Err(RuntimeErrorKind::UndefinedVariable(name)),
+                };
+                self.stack.push(value);
+            }
+            OpCode::SetGlobal => {
+                let name = self.read_name();
+                if !self.globals.contains_key(&name) {
+                    return Err(RuntimeErrorKind::UndefinedVariable(name));
+                }
+                self.globals.insert(name, self.peek().clone());
+            }
+            OpCode::GetUpvalue => {
+                let upvalue = self.read_upvalue();
+                let value = match &*upvalue.borrow() {
+                    Upvalue::Open(slot) => self.stack[*slot].clone(),
+                    Upvalue::Closed(value) => value.clone(),
+                };
+                self.stack.push(value);
+            }
+            OpCode::SetUpvalue => {
+                let upvalue = self.read_upvalue();
+                let value = self.peek().clone();
+                let mut upvalue = upvalue.borrow_mut();
+                match &mut *upvalue {
+                    Upvalue::Open(slot) => self.stack[*slot] = value,
+                    Upvalue::Closed(closed) => *closed = value,
+                }
+            }
+            OpCode::CloseUpvalue => {
+                self.close_upvalues(self.stack.len() - 1);
+                self.stack.pop();
+            }
+
+            OpCode::Negate | OpCode::Not | OpCode::BitNot => {
+                let operand = self.stack.pop().unwrap();
+                let result = operators::unary(get_operator_kind(op), &operand)?;
+                self.stack.push(result);
+            }
+            OpCode::Add
+            | OpCode::Subtract
+            | OpCode::Multiply
+            | OpCode::Divide
+            | OpCode::FloorDivide
+            | OpCode::Modulo
+            | OpCode::Power
+            | OpCode::BitAnd
+            | OpCode::BitOr
+            | OpCode::BitXor
+            | OpCode::Equal
+            | OpCode::NotEqual
+            | OpCode::Less
+            | OpCode::LessEqual
+            | OpCode::Greater
+            | OpCode::GreaterEqual => {
+                let right = self.stack.pop().unwrap();
+                let left = self.stack.pop().unwrap();
+                let result = operators::binary(get_operator_kind(op), &left, &right)?;
+                self.stack.push(result);
+            }
+
+            OpCode::Jump => {
+                let jump = self.read_u16();
+                self.frame_mut().ip += jump;
+            }
+            OpCode::JumpIfFalse => {
+                let jump = self.read_u16();
+                match self.stack.pop().unwrap() {
+                    Value::Bool(true) => (),
+                    Value::Bool(false) => self.frame_mut().ip += jump,
+                    value => return Err(RuntimeErrorKind::InvalidCondition(value.type_name())),
+                }
+            }
+            OpCode::Loop => {
+                let jump = self.read_u16();
+                self.frame_mut().ip -= jump;
+            }
+            OpCode::AndJump | OpCode::OrJump => {
+                let jump = self.read_u16();
+                let short_circuit = op == OpCode::OrJump;
+                match self.peek() {
+                    Value::Bool(value) if *value == short_circuit => self.frame_mut().ip += jump,
+                    Value::Bool(_) => {
+                        self.stack.pop();
+                    }
+                    value => return Err(invalid_logical_operand(short_circuit, value)),
+                }
+            }
+            OpCode::ExpectBool => {
+                let is_or = self.read_byte() == 1;
+                let value = self.peek();
+                if !matches!(value, Value::Bool(_)) {
+                    return Err(invalid_logical_operand(is_or, value));
+                }
+            }
+            OpCode::ForNext => {
+                let slot = self.frame().base + self.read_byte() as usize;
+                let jump = self.read_u16();
+
+                let Value::Int(index) = self.stack[slot + 1] else {
+                    unreachable!("the slot after the iterable holds the loop index")
+                };
+                match self.stack[slot].item(index)? {
+                    Some(item) => {
+                        self.stack[slot + 1] = Value::Int(index + 1);
+                        self.stack.push(item);
+                    }
+                    None => self.frame_mut().ip += jump,
+                }
+            }
+
+            OpCode::Interpolate => {
+                let count = self.read_byte() as usize;
+                let parts = self.stack.split_off(self.stack.len() - count);
+                let text: String = parts.iter().map(Value::to_string).collect();
+                self.stack.push(Value::String(text));
+            }
+            OpCode::Call => {
+                let argument_count = self.read_byte() as usize;
+                self.call(argument_count)?;
+            }
+            OpCode::Closure => {
+                let Constant::Function(function) = self.read_constant().clone() else {
+                    unreachable!("Closure always refers to a function constant")
+                };
+
+                let mut upvalues = Vec::with_capacity(function.upvalue_count);
+                for _ in 0..function.upvalue_count {
+                    let is_local = self.read_byte() == 1;
+                    let index = self.read_byte() as usize;
+
+                    let upvalue = if is_local {
+                        self.capture_upvalue(self.frame().base + index)
+                    } else {
+                        self.frame().closure.upvalues[index].clone()
+                    };
+                    upvalues.push(upvalue);
+                }
+
+                let closure = Closure { function, upvalues };
+                self.stack.push(Value::Closure(Rc::new(closure)));
+            }
+            OpCode::Return => {
+                let result = self.stack.pop().unwrap();
+                let frame = self.frames.pop().unwrap();
+                self.close_upvalues(frame.base);
+                self.stack.truncate(frame.base);
+
+                if self.frames.is_empty() {
+                    return Ok(true);
+                }
+                self.stack.push(result);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Calls the value below the top `argument_count` values of the stack
+    fn call(&mut self, argument_count: usize) -> Result<(), RuntimeErrorKind> {
+        let callee_index = self.stack.len() - argument_count - 1;
+
+        match self.stack[callee_index].clone() {
+            Value::Closure(closure) => {
+                if closure.function.arity != argument_count {
+                    return Err(RuntimeErrorKind::ArityMismatch {
+                        expected: closure.function.arity,
+                        found: argument_count,
+                    });
+                }
+
+                // the frame running the program itself does not count as a call
+                if self.frames.len() > MAX_CALL_DEPTH {
+                    return Err(RuntimeErrorKind::StackOverflow);
+                }
+
+                self.frames.push(CallFrame {
+                    closure,
+                    ip: 0,
+                    base: callee_index,
+                });
+            }
+            Value::NativeFunction(native) => {
+                if let Some(arity) = native.arity {
+                    if arity != argument_count {
+                        return Err(RuntimeErrorKind::ArityMismatch {
+                            expected: arity,
+                            found: argument_count,
+                        });
+                    }
+                }
+
+                let arguments = &self.stack[callee_index + 1..];
+                let result = (native.function)(self.output.as_mut(), arguments)?;
+                self.stack.truncate(callee_index);
+                self.stack.push(result);
+            }
+            callee => return Err(RuntimeErrorKind::NotCallable(callee.type_name())),
+        }
+
+        Ok(())
+    }
+
+    fn capture_upvalue(&mut self, slot: usize) -> Rc<RefCell<Upvalue>> {
+        let existing = self
+            .open_upvalues
+            .iter()
+            .find(|upvalue| matches!(*upvalue.borrow(), Upvalue::Open(open) if open == slot));
+        if let Some(upvalue) = existing {
+            return upvalue.clone();
+        }
+
+        let upvalue = Rc::new(RefCell::new(Upvalue::Open(slot)));
+        self.open_upvalues.push(upvalue.clone());
+        upvalue
+    }
+
+    /// Moves every variable at or above stack index `from` into the upvalues capturing it
+    fn close_upvalues(&mut self, from: usize) -> () {
+        let stack = &self.stack;
+        self.open_upvalues.retain(|upvalue| {
+            let mut upvalue = upvalue.borrow_mut();
+            match *upvalue {
+                Upvalue::Open(slot) if slot >= from => {
+                    *upvalue = Upvalue::Closed(stack[slot].clone());
+                    false
+                }
+                _ => true,
+            }
+        });
+    }
+
+    fn frame(&self) -> &CallFrame {
+        self.frames.last().unwrap()
+    }
+
+    fn frame_mut(&mut self) -> &mut CallFrame {
+        self.frames.last_mut().unwrap()
+    }
+
+    fn peek(&self) -> &Value {
+        self.stack.last().unwrap()
+    }
+
+    fn read_byte(&mut self) -> u8 {
+        let frame = self.frame_mut();
+        let byte = frame.closure.function.chunk.code[frame.ip];
+        frame.ip += 1;
+        byte
+    }
+
+    fn read_u16(&mut self) -> usize {
+        let frame = self.frame_mut();
+        let value = frame.closure.function.chunk.read_u16(frame.ip);
+        frame.ip += 2;
+        value as usize
+    }
+
+    fn read_constant(&mut self) -> &Constant {
+        let index = self.read_u16();
+        &self.frame().closure.function.chunk.constants[index]
+    }
+
+    fn read_name(&mut self) -> String {
+        match self.read_constant() {
+            Constant::String(name) => name.clone(),
+            _ => unreachable!("variable names are string constants"),
+        }
+    }
+
+    fn read_upvalue(&mut self) -> Rc<RefCell<Upvalue>> {
+        let index = self.read_byte() as usize;
+        self.frame().closure.upvalues[index].clone()
+    }
+}
+
+/// Returns the item at `index` when iterating over `iterable`, or None once it is exhausted
+fn invalid_logical_operand(is_or: bool, operand: &Value) -> RuntimeErrorKind {
+    RuntimeErrorKind::InvalidOperand {
+        operator: if is_or { "||" } else { "&&" }.to_string(),
+        operand: operand.type_name(),
+    }
+}
+
+fn get_operator_kind(op: OpCode) -> SyntaxKind {
+    match op {
+        OpCode::Add => SyntaxKind::Plus,
+        OpCode::Subtract | OpCode::Negate => SyntaxKind::Minus,
+        OpCode::Multiply => SyntaxKind::Star,
+        OpCode::Divide => SyntaxKind::Slash,
+        OpCode::FloorDivide => SyntaxKind::TildeSlash,
+        OpCode::Modulo => SyntaxKind::Percent,
+        OpCode::Power => SyntaxKind::Carat,
+        OpCode::BitAnd => SyntaxKind::Ampersand,
+        OpCode::BitOr => SyntaxKind::Pipe,
+        OpCode::BitXor | OpCode::BitNot => SyntaxKind::Tilde,
+        OpCode::Not => SyntaxKind::Bang,
+        OpCode::Equal => SyntaxKind::EqualsEquals,
+        OpCode::NotEqual => SyntaxKind::BangEquals,
+        OpCode::Less => SyntaxKind::LT,
+        OpCode::LessEqual => SyntaxKind::LTE,
+        OpCode::Greater => SyntaxKind::GT,
+        OpCode::GreaterEqual => SyntaxKind::GTE,
+        _ => unreachable!("{:?} is not an operator", op),
+    }
+}